@@ -0,0 +1,373 @@
+//! Operations on observations grouped by batch identifiers.
+
+use crate::random::{RngOps, sample_indices, sample_weighted_without_replacement};
+#[cfg(feature = "ndarray")]
+use crate::stats::median_in_place;
+#[cfg(feature = "ndarray")]
+use crate::traits::{FloatOpsTS, MatrixLike};
+#[cfg(feature = "ndarray")]
+use crate::types::{AggregationMethod, Direction};
+use crate::types::{BalanceStrategy, BatchEncoder, BatchIdentifier, CompositeEncoder, CountOrder};
+#[cfg(feature = "ndarray")]
+use crate::utils::parallel::map_indices;
+use anyhow::anyhow;
+#[cfg(feature = "ndarray")]
+use ndarray::Array2;
+
+/// Aggregates the observations of a matrix by group label (pseudobulk).
+///
+/// The `direction` determines which axis holds the observations: with `Direction::ROW`
+/// every row is an observation and the result is a groups × features matrix, with
+/// `Direction::COLUMN` every column is an observation and the result is a
+/// features × groups matrix. Groups are ordered by first occurrence in `labels`.
+/// Sparse inputs are aggregated without densifying; implicit zeros are still taken
+/// into account for the mean and the median.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `labels` - The group label of every observation
+/// * `direction` - The axis along which observations are laid out
+/// * `method` - How the values of each group are combined
+///
+/// # Returns
+/// The aggregated matrix and the encoder mapping group labels to their result index
+#[cfg(feature = "ndarray")]
+pub fn aggregate_by_group<T, B, M>(
+    matrix: &M,
+    labels: &[B],
+    direction: Direction,
+    method: AggregationMethod,
+) -> anyhow::Result<(Array2<T>, BatchEncoder<B>)>
+where
+    T: FloatOpsTS,
+    B: BatchIdentifier,
+    M: MatrixLike<T> + Sync,
+{
+    let n_obs = if direction.is_row() {
+        matrix.nrows()
+    } else {
+        matrix.ncols()
+    };
+    if labels.len() != n_obs {
+        return Err(anyhow!(
+            "Number of labels ({}) does not match the number of observations ({})!",
+            labels.len(),
+            n_obs
+        ));
+    }
+
+    let (encoder, codes, members) = group_members(labels);
+    let n_groups = encoder.len();
+
+    let result = if direction.is_row() {
+        let n_features = matrix.ncols();
+        let rows = map_indices(n_groups, |g| {
+            aggregate_rows(matrix, &members[g], n_features, method)
+        });
+        Array2::from_shape_vec((n_groups, n_features), rows.concat())?
+    } else {
+        let n_features = matrix.nrows();
+        let sizes: Vec<usize> = members.iter().map(Vec::len).collect();
+        let rows = map_indices(n_features, |feature| {
+            aggregate_columns(matrix, feature, &codes, &sizes, method)
+        });
+        Array2::from_shape_vec((n_features, n_groups), rows.concat())?
+    };
+
+    Ok((result, encoder))
+}
+
+/// Returns the unique labels in order of first occurrence.
+pub fn unique<B: BatchIdentifier>(labels: &[B]) -> Vec<B> {
+    BatchEncoder::fit(labels).labels().to_vec()
+}
+
+/// Returns the unique labels in order of first occurrence together with the
+/// index of every label into the unique list.
+///
+/// The codes are aligned with `BatchEncoder::fit(labels)`.
+pub fn unique_with_codes<B: BatchIdentifier>(labels: &[B]) -> (Vec<B>, Vec<usize>) {
+    let (encoder, codes) = BatchEncoder::fit_transform(labels);
+    (encoder.labels().to_vec(), codes)
+}
+
+/// Counts how often every unique label occurs.
+///
+/// # Arguments
+/// * `labels` - The labels to count
+/// * `order` - The order of the returned `(label, count)` pairs
+pub fn value_counts<B: BatchIdentifier + Ord>(labels: &[B], order: CountOrder) -> Vec<(B, usize)> {
+    let (encoder, codes) = BatchEncoder::fit_transform(labels);
+    let mut counts = vec![0usize; encoder.len()];
+    for &code in &codes {
+        counts[code] += 1;
+    }
+    let mut result: Vec<(B, usize)> = encoder.labels().iter().cloned().zip(counts).collect();
+    match order {
+        // Stable sort keeps first-occurrence order among equal counts.
+        CountOrder::Count => result.sort_by_key(|entry| std::cmp::Reverse(entry.1)),
+        CountOrder::Key => result.sort_by(|a, b| a.0.cmp(&b.0)),
+        CountOrder::FirstOccurrence => {}
+    }
+    result
+}
+
+/// Counts how often every label of an existing encoder occurs in `labels`.
+///
+/// # Returns
+/// The counts indexed by encoder code, or an error if a label is unknown to the encoder
+pub fn value_counts_aligned<B: BatchIdentifier>(
+    labels: &[B],
+    encoder: &BatchEncoder<B>,
+) -> anyhow::Result<Vec<usize>> {
+    let mut counts = vec![0usize; encoder.len()];
+    for (pos, label) in labels.iter().enumerate() {
+        let code = encoder
+            .code(label)
+            .ok_or_else(|| anyhow!("Label at position {} is not known to the encoder!", pos))?;
+        counts[code] += 1;
+    }
+    Ok(counts)
+}
+
+/// Groups observations by the combination of two label columns.
+///
+/// Every column is encoded once and combinations are keyed by their column codes,
+/// so grouping by e.g. sample × condition does not allocate concatenated strings.
+/// More than two columns can be combined by grouping the returned codes with the
+/// next column, since `usize` is a `BatchIdentifier` itself.
+///
+/// # Arguments
+/// * `first` - The first label of every observation
+/// * `second` - The second label of every observation
+///
+/// # Returns
+/// The encoder over all observed combinations and the composite code of every
+/// observation, or an error if the columns have different lengths
+pub fn group_by_keys<A: BatchIdentifier, B: BatchIdentifier>(
+    first: &[A],
+    second: &[B],
+) -> anyhow::Result<(CompositeEncoder<A, B>, Vec<usize>)> {
+    if first.len() != second.len() {
+        return Err(anyhow!(
+            "Label columns must have the same length ({} != {})!",
+            first.len(),
+            second.len()
+        ));
+    }
+    let mut encoder = CompositeEncoder::new();
+    let codes = first
+        .iter()
+        .zip(second)
+        .map(|(a, b)| encoder.encode(a, b))
+        .collect();
+    Ok((encoder, codes))
+}
+
+/// Returns a permutation that shuffles observations only within their own group.
+///
+/// Position `i` of the result holds the observation that is moved to position `i`;
+/// every observation is replaced by one carrying the same label, so
+/// `labels[perm[i]] == labels[i]` holds for all `i`. This is the null model that
+/// keeps the batch structure intact.
+///
+/// # Arguments
+/// * `labels` - The group label of every observation
+/// * `rng` - The random number generator
+pub fn shuffle_within_groups<B: BatchIdentifier, R: RngOps>(
+    labels: &[B],
+    rng: &mut R,
+) -> Vec<usize> {
+    let (_, _, members) = group_members(labels);
+    let mut perm = vec![0usize; labels.len()];
+    for group in members {
+        let mut shuffled = group.clone();
+        rng.shuffle(&mut shuffled);
+        for (&pos, obs) in group.iter().zip(shuffled) {
+            perm[pos] = obs;
+        }
+    }
+    perm
+}
+
+/// Returns a permutation that reorders whole groups while keeping each group contiguous.
+///
+/// The groups are visited in a random order and the observations of every group
+/// are emitted as one block in their original relative order. This is the null
+/// model that permutes batch blocks wholesale.
+///
+/// # Arguments
+/// * `labels` - The group label of every observation
+/// * `rng` - The random number generator
+pub fn shuffle_groups<B: BatchIdentifier, R: RngOps>(labels: &[B], rng: &mut R) -> Vec<usize> {
+    let (_, _, mut members) = group_members(labels);
+    rng.shuffle(&mut members);
+    members.concat()
+}
+
+/// Encodes `labels` and collects the observation indices of every group, indexed by code.
+fn group_members<B: BatchIdentifier>(
+    labels: &[B],
+) -> (BatchEncoder<B>, Vec<usize>, Vec<Vec<usize>>) {
+    let (encoder, codes) = BatchEncoder::fit_transform(labels);
+    let mut members: Vec<Vec<usize>> = vec![Vec::new(); encoder.len()];
+    for (obs, &code) in codes.iter().enumerate() {
+        members[code].push(obs);
+    }
+    (encoder, codes, members)
+}
+
+/// Aggregates the given rows of `matrix` into a single row.
+#[cfg(feature = "ndarray")]
+fn aggregate_rows<T, M>(
+    matrix: &M,
+    rows: &[usize],
+    n_features: usize,
+    method: AggregationMethod,
+) -> Vec<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T>,
+{
+    match method {
+        AggregationMethod::Sum | AggregationMethod::Mean => {
+            let mut acc = vec![T::zero(); n_features];
+            for &row in rows {
+                matrix.for_each_in_row(row, |col, val| acc[col] += val);
+            }
+            if method == AggregationMethod::Mean {
+                let n = T::from(rows.len()).unwrap();
+                acc.iter_mut().for_each(|v| *v = *v / n);
+            }
+            acc
+        }
+        AggregationMethod::Median => {
+            let mut values: Vec<Vec<T>> = vec![Vec::new(); n_features];
+            for &row in rows {
+                matrix.for_each_in_row(row, |col, val| values[col].push(val));
+            }
+            values
+                .into_iter()
+                .map(|mut v| {
+                    v.resize(rows.len(), T::zero());
+                    median_in_place(&mut v)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Aggregates one row of `matrix` whose columns are observations labelled by `codes`.
+#[cfg(feature = "ndarray")]
+fn aggregate_columns<T, M>(
+    matrix: &M,
+    row: usize,
+    codes: &[usize],
+    sizes: &[usize],
+    method: AggregationMethod,
+) -> Vec<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T>,
+{
+    match method {
+        AggregationMethod::Sum | AggregationMethod::Mean => {
+            let mut acc = vec![T::zero(); sizes.len()];
+            matrix.for_each_in_row(row, |col, val| acc[codes[col]] += val);
+            if method == AggregationMethod::Mean {
+                for (v, &n) in acc.iter_mut().zip(sizes) {
+                    *v = *v / T::from(n).unwrap();
+                }
+            }
+            acc
+        }
+        AggregationMethod::Median => {
+            let mut values: Vec<Vec<T>> = sizes.iter().map(|&n| Vec::with_capacity(n)).collect();
+            matrix.for_each_in_row(row, |col, val| values[codes[col]].push(val));
+            values
+                .into_iter()
+                .zip(sizes)
+                .map(|(mut v, &n)| {
+                    v.resize(n, T::zero());
+                    median_in_place(&mut v)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Downsamples every group so that all groups contribute a comparable number of observations.
+///
+/// # Arguments
+/// * `labels` - The group label of every observation
+/// * `rng` - The random number generator
+/// * `strategy` - Determines the number of observations kept per group
+///
+/// # Returns
+/// The indices of the kept observations in ascending order
+pub fn balance_groups<B: BatchIdentifier, R: RngOps + ?Sized>(
+    labels: &[B],
+    rng: &mut R,
+    strategy: BalanceStrategy,
+) -> Vec<usize> {
+    balance_with(labels, strategy, |members, k| {
+        sample_indices(rng, members.len(), k)
+    })
+}
+
+/// Downsamples every group like `balance_groups`, drawing observations proportionally to `weights`.
+///
+/// Observations with a zero, negative or non-finite weight are never selected, so
+/// groups may end up smaller than the requested size if they contain too few
+/// observations with positive weight.
+///
+/// # Arguments
+/// * `labels` - The group label of every observation
+/// * `weights` - The sampling weight of every observation
+/// * `rng` - The random number generator
+/// * `strategy` - Determines the number of observations kept per group
+///
+/// # Returns
+/// The indices of the kept observations in ascending order, or an error if the
+/// number of weights does not match the number of labels
+pub fn balance_groups_weighted<B: BatchIdentifier, R: RngOps + ?Sized>(
+    labels: &[B],
+    weights: &[f64],
+    rng: &mut R,
+    strategy: BalanceStrategy,
+) -> anyhow::Result<Vec<usize>> {
+    if weights.len() != labels.len() {
+        return Err(anyhow!(
+            "Number of weights ({}) does not match the number of labels ({})!",
+            weights.len(),
+            labels.len()
+        ));
+    }
+    Ok(balance_with(labels, strategy, |members, k| {
+        let group_weights: Vec<f64> = members.iter().map(|&i| weights[i]).collect();
+        sample_weighted_without_replacement(&group_weights, k, rng)
+    }))
+}
+
+fn balance_with<B, F>(labels: &[B], strategy: BalanceStrategy, mut sample: F) -> Vec<usize>
+where
+    B: BatchIdentifier,
+    F: FnMut(&[usize], usize) -> Vec<usize>,
+{
+    let (_, _, members) = group_members(labels);
+
+    let target = match strategy {
+        BalanceStrategy::Smallest => members.iter().map(Vec::len).min().unwrap_or(0),
+        BalanceStrategy::Target(n) => n,
+    };
+
+    let mut selected: Vec<usize> = members
+        .iter()
+        .flat_map(|group| {
+            let picked = sample(group, target.min(group.len()));
+            picked.into_iter().map(|pos| group[pos]).collect::<Vec<_>>()
+        })
+        .collect();
+    selected.sort_unstable();
+    selected
+}
@@ -25,6 +25,69 @@
 //! - **Direction Handling**: Utilities for row/column-oriented operations
 //! - **Distance Metrics**: Common distance functions for similarity calculations
 //! - **Batch Processing**: Identifiers and utilities for batch operations
+//! - **Categorical Data**: Dictionary-encoded label columns for observation/feature annotations
+//! - **Gene Regulatory Graphs**: Directed weighted graphs over the feature space with node attributes
+//! - **Pathway Networks**: Compact gene set collections with GMT, TSV edge list and binary I/O,
+//!   over-representation analysis and enrichment scoring
+//! - **Sparse Matrices**: A compressed sparse row container usable wherever `MatrixLike` is accepted
+//!
+//! ### Batch Module
+//! - **Aggregation**: Pseudobulk sum/mean/median of observations grouped by batch labels
+//! - **Balancing**: Seeded downsampling of unbalanced groups
+//! - **Composite Keys**: Grouping by combinations of several label columns
+//! - **Label Summaries**: Unique labels and value counts
+//! - **Permutations**: Batch-aware shuffling for permutation-test null models
+//!
+//! ### Random Module
+//! - **Seedable RNG**: A deterministic generator shared by all randomized utilities, behind the
+//!   `RngOps` trait with forkable parallel streams
+//! - **Reservoir Sampling**: Uniform and weighted (A-ES) sampling from streams of unknown length
+//! - **Weighted Sampling**: Efraimidis–Spirakis sampling without replacement proportional to weights
+//!
+//! ### Sort Module
+//! - **Argsort**: NaN-aware argsort, argmin, argmax and nth-element selection, with variants
+//!   reusing an index buffer
+//! - **Top-k Selection**: Partial selection of the k largest or smallest values of slices and lanes
+//! - **Permutations**: Cycle-following in-place reordering of one or several buffers and inversion
+//!
+//! ### Stats Module
+//! - **Descriptive Statistics**: Sum, mean, variance and standard deviation of slices and matrix lanes,
+//!   geometric means and a sparse mean/variance kernel that never densifies
+//! - **Dispersion Trends**: Polynomial and binned-median fits of the mean–dispersion relationship
+//!   yielding normalized dispersions for highly variable feature selection
+//! - **Robust Statistics**: Selection-based median and median absolute deviation
+//! - **Rolling Statistics**: Sliding-window sums, means and medians with configurable edge handling
+//! - **Quantiles**: NumPy-compatible quantiles with linear, lower, higher, midpoint and nearest interpolation
+//! - **Histograms**: Mergeable histograms with uniform, explicit or Freedman–Diaconis/Sturges bins
+//! - **Ranks**: Rank transforms with average, min, max, dense and ordinal tie handling
+//! - **Correlation**: Pearson and Spearman coefficients and blocked, parallel correlation matrices
+//! - **Hypothesis Tests**: Welch's t-test and Mann–Whitney U test for pairs of samples and per
+//!   feature between labelled groups, Wilcoxon signed-rank test for paired samples,
+//!   Kruskal–Wallis test across several groups and two-sample Kolmogorov–Smirnov test
+//! - **Contingency Tests**: Hypergeometric tail probabilities, Fisher's exact test on 2×2 tables and
+//!   chi-square test of independence
+//! - **Multiple Testing**: Benjamini–Hochberg, Benjamini–Yekutieli, Bonferroni and Holm adjustment
+//! - **Clustering Metrics**: Silhouette coefficients with blocked pairwise distances and subsampling,
+//!   adjusted Rand index and normalized mutual information between partitions
+//! - **Classification Metrics**: Confusion matrices with per-class, macro- and micro-averaged
+//!   precision, recall and F1 scores
+//! - **Bootstrap**: Percentile and BCa confidence intervals for arbitrary statistics
+//! - **Special Functions**: Log-gamma, log-beta and regularized incomplete beta and gamma functions
+//! - **Streaming Moments**: Mergeable Welford accumulators for chunked and parallel processing
+//!
+//! ### Transform Module
+//! - **Count Normalization**: Total-count (CPM-style) scaling of dense and CSR matrices returning size
+//!   factors, optionally excluding highly expressed features
+//! - **Pearson Residuals**: Analytic negative binomial residuals of UMI count matrices
+//! - **TF-IDF**: Latent semantic indexing weights for sparse scATAC-seq peak matrices
+//! - **Log Transforms**: Parallel, SIMD-vectorized `log1p` and `expm1` of dense matrices and sparse values
+//! - **Softmax**: Numerically stable softmax and log-softmax of slices and matrix lanes
+//! - **Clipping**: Clipping to fixed bounds and quantile-based winsorizing
+//! - **Scaling**: Per-lane z-scoring with clipping, min–max rescaling and robust median/MAD or IQR
+//!   scaling, mapping implicit zeros of sparse inputs analytically
+//! - **Quantile Normalization**: Mapping every lane onto the mean sorted profile of all lanes
+//! - **Compositional Data**: Centered log-ratio transform for CITE-seq protein normalization
+//! - **Downsampling**: Reproducible binomial thinning of dense and sparse count matrices
 //!
 //! ## Usage
 //!
@@ -47,7 +110,15 @@
 //!
 //! ## Feature Flags
 //!
-//! - `simd`: Enables SIMD-accelerated operations using the `simba` crate
+//! - `simd`: Enables SIMD-accelerated operations using the `simba` and `wide` crates
+//! - `ndarray`: Enables the dense-matrix APIs on `ndarray` arrays: `MatrixLike` for 2-D arrays,
+//!   matrix transforms, pseudobulk aggregation, correlation matrices, the chi-square test,
+//!   confusion matrices and pathway scoring
+//! - `convert`: Enables conversions between `ndarray` and `nalgebra` types; implies `ndarray`
+//! - `serde`: Implements `Serialize`/`Deserialize` for `PathwayNetwork`
+//! - `rayon`: Parallelizes lane- and group-wise computations using the `rayon` crate
+//! - `io`: Streams delimited edge lists into `PathwayNetwork` construction using the `csv` crate
+//! - `rand`: Adds `ChaChaRng`, a ChaCha8-backed `RngOps` implementation using `rand_chacha`
 //!
 //! ## Compatibility
 //!
@@ -57,8 +128,18 @@
 //! - SIMD libraries like `simba` (when feature is enabled)
 //! - Custom mathematical and scientific computing libraries
 
+pub mod batch;
+
+pub mod random;
+
+pub mod sort;
+
+pub mod stats;
+
 pub mod traits;
 
+pub mod transform;
+
 pub mod types;
 
 pub(crate) mod utils;
@@ -0,0 +1,237 @@
+//! Seedable pseudo-random number generation and sampling primitives.
+
+#[cfg(feature = "rand")]
+mod chacha;
+mod reservoir;
+
+#[cfg(feature = "rand")]
+pub use chacha::ChaChaRng;
+pub use reservoir::{
+    reservoir_sample, reservoir_sample_indices, weighted_reservoir_sample,
+    weighted_reservoir_sample_indices,
+};
+
+/// Operations shared by the seedable random number generators of this crate.
+///
+/// Randomized utilities accept any `RngOps` implementation, so the same seeded
+/// handle can drive all of them. Only `next_u64` and `fork` need to be implemented;
+/// the remaining methods derive uniform and discrete draws from the raw bits.
+pub trait RngOps {
+    /// Returns the next 64 random bits.
+    fn next_u64(&mut self) -> u64;
+
+    /// Returns an independent generator for the parallel stream `stream`.
+    ///
+    /// The child depends only on the state of `self` and on `stream`, and `self` is
+    /// not advanced, so forking one generator per chunk or observation gives the same
+    /// results regardless of how work is scheduled across threads.
+    fn fork(&self, stream: u64) -> Self
+    where
+        Self: Sized;
+
+    /// Returns a uniformly distributed `f64` in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+
+    /// Returns a uniformly distributed integer in `[0, n)`.
+    ///
+    /// # Panics
+    /// Panics if `n` is zero.
+    fn next_range(&mut self, n: usize) -> usize {
+        assert!(n > 0, "Cannot sample from an empty range");
+        let n = n as u64;
+        // Lemire's nearly divisionless method with rejection to remove bias.
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let m = (self.next_u64() as u128) * (n as u128);
+            if (m as u64) >= threshold {
+                return (m >> 64) as usize;
+            }
+        }
+    }
+
+    /// Fills `out` with uniformly distributed values in `[0, 1)`.
+    fn fill_uniform(&mut self, out: &mut [f64]) {
+        out.iter_mut().for_each(|x| *x = self.next_f64());
+    }
+
+    /// Returns the number of successes in `n` independent trials with success
+    /// probability `p`.
+    ///
+    /// Small `n` are drawn trial by trial; larger ones by skipping geometrically
+    /// distributed gaps between successes, which takes time proportional to
+    /// `n * min(p, 1 - p)`. `p` is clamped to `[0, 1]`.
+    fn next_binomial(&mut self, n: u64, p: f64) -> u64 {
+        if n == 0 || p.is_nan() || p <= 0.0 {
+            return 0;
+        }
+        if p >= 1.0 {
+            return n;
+        }
+        if p > 0.5 {
+            return n - self.next_binomial(n, 1.0 - p);
+        }
+        if n <= 16 {
+            return (0..n).filter(|_| self.next_f64() < p).count() as u64;
+        }
+        let log_q = (-p).ln_1p();
+        let mut successes = 0;
+        let mut position = 0u64;
+        loop {
+            // 1 - u lies in (0, 1], which keeps the logarithm finite.
+            let u = 1.0 - self.next_f64();
+            let gap = (u.ln() / log_q).floor() as u64 + 1;
+            position = position.saturating_add(gap);
+            if position > n {
+                return successes;
+            }
+            successes += 1;
+        }
+    }
+
+    /// Shuffles a slice in place using the Fisher–Yates algorithm.
+    fn shuffle<T>(&mut self, data: &mut [T])
+    where
+        Self: Sized,
+    {
+        for i in (1..data.len()).rev() {
+            let j = self.next_range(i + 1);
+            data.swap(i, j);
+        }
+    }
+}
+
+/// A small, fast and seedable pseudo-random number generator.
+///
+/// Implements xoshiro256++ seeded through SplitMix64, so every `u64` seed yields a
+/// well-mixed state. The generator is deterministic across platforms, which makes
+/// randomized utilities in this crate reproducible from a single seed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeededRng {
+    state: [u64; 4],
+}
+
+impl SeededRng {
+    /// Creates a new generator from a seed.
+    pub fn new(seed: u64) -> Self {
+        let mut sm = seed;
+        let mut next = || {
+            sm = sm.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = sm;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        };
+        Self {
+            state: [next(), next(), next(), next()],
+        }
+    }
+
+    /// Returns the next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.state;
+        let result = s[0].wrapping_add(s[3]).rotate_left(23).wrapping_add(s[0]);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// Returns a uniformly distributed `f64` in `[0, 1)`.
+    pub fn next_f64(&mut self) -> f64 {
+        RngOps::next_f64(self)
+    }
+
+    /// Returns a uniformly distributed integer in `[0, n)`.
+    ///
+    /// # Panics
+    /// Panics if `n` is zero.
+    pub fn next_range(&mut self, n: usize) -> usize {
+        RngOps::next_range(self, n)
+    }
+
+    /// Returns the number of successes in `n` independent trials with success
+    /// probability `p`.
+    ///
+    /// See `RngOps::next_binomial`.
+    pub fn next_binomial(&mut self, n: u64, p: f64) -> u64 {
+        RngOps::next_binomial(self, n, p)
+    }
+
+    /// Shuffles a slice in place using the Fisher–Yates algorithm.
+    pub fn shuffle<T>(&mut self, data: &mut [T]) {
+        RngOps::shuffle(self, data)
+    }
+}
+
+impl RngOps for SeededRng {
+    fn next_u64(&mut self) -> u64 {
+        SeededRng::next_u64(self)
+    }
+
+    fn fork(&self, stream: u64) -> Self {
+        let [a, b, c, d] = self.state;
+        let digest = a ^ b.rotate_left(16) ^ c.rotate_left(32) ^ d.rotate_left(48);
+        Self::new(digest ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+    }
+}
+
+/// Samples `k` distinct positions from `0..n` uniformly without replacement.
+pub(crate) fn sample_indices<R: RngOps + ?Sized>(rng: &mut R, n: usize, k: usize) -> Vec<usize> {
+    let mut pool: Vec<usize> = (0..n).collect();
+    let k = k.min(n);
+    // Partial Fisher–Yates: only the first k positions need to be drawn.
+    for i in 0..k {
+        let j = i + rng.next_range(n - i);
+        pool.swap(i, j);
+    }
+    pool.truncate(k);
+    pool
+}
+
+/// Samples `k` distinct positions proportionally to `weights` without replacement.
+///
+/// Uses the exponential-key method of Efraimidis and Spirakis: every item with
+/// positive weight `w` draws the key `ln(u) / w` and the `k` largest keys are kept,
+/// found by selection in linear average time. The result has the distribution of `k`
+/// successive draws that each pick a remaining item with probability proportional to
+/// its weight, e.g. for importance-weighted sketching of cells. Items with a weight
+/// that is zero, negative or not finite are never selected.
+///
+/// # Arguments
+/// * `weights` - The sampling weight of every position
+/// * `k` - The number of positions to sample
+/// * `rng` - The random number generator
+///
+/// # Returns
+/// At most `k` positions, in the order of the successive draws
+pub fn sample_weighted_without_replacement<R: RngOps + ?Sized>(
+    weights: &[f64],
+    k: usize,
+    rng: &mut R,
+) -> Vec<usize> {
+    let mut keyed: Vec<(f64, usize)> = weights
+        .iter()
+        .enumerate()
+        .filter(|(_, w)| w.is_finite() && **w > 0.0)
+        .map(|(i, &w)| {
+            // 1 - u lies in (0, 1], which keeps the logarithm finite.
+            let u = 1.0 - rng.next_f64();
+            (u.ln() / w, i)
+        })
+        .collect();
+    let k = k.min(keyed.len());
+    if k == 0 {
+        return Vec::new();
+    }
+    let by_key = |a: &(f64, usize), b: &(f64, usize)| b.0.total_cmp(&a.0);
+    keyed.select_nth_unstable_by(k - 1, by_key);
+    keyed.truncate(k);
+    keyed.sort_unstable_by(by_key);
+    keyed.into_iter().map(|(_, i)| i).collect()
+}
@@ -0,0 +1,135 @@
+use super::RngOps;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+/// Draws `k` items uniformly without replacement from a stream of unknown length.
+///
+/// The stream is consumed once and only the `k` sampled items are held in memory.
+/// Uses Li's Algorithm L, which skips over runs of items with geometrically
+/// distributed lengths, so the number of random draws grows with `k log(n / k)`
+/// rather than with the length `n` of the stream.
+///
+/// # Arguments
+/// * `iter` - The stream of items
+/// * `k` - The number of items to sample
+/// * `rng` - The random number generator
+///
+/// # Returns
+/// `min(k, n)` items in unspecified order
+pub fn reservoir_sample<I, R>(iter: I, k: usize, rng: &mut R) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    R: RngOps + ?Sized,
+{
+    let mut iter = iter.into_iter();
+    let mut reservoir: Vec<I::Item> = iter.by_ref().take(k).collect();
+    if reservoir.len() < k || k == 0 {
+        return reservoir;
+    }
+    // 1 - u lies in (0, 1], which keeps the logarithms finite.
+    let log_uniform = |rng: &mut R| (1.0 - rng.next_f64()).ln();
+    let mut w = (log_uniform(rng) / k as f64).exp();
+    loop {
+        let skip = (log_uniform(rng) / (-w).ln_1p()).floor();
+        let Some(item) = iter.nth(skip.min(usize::MAX as f64) as usize) else {
+            return reservoir;
+        };
+        reservoir[rng.next_range(k)] = item;
+        w *= (log_uniform(rng) / k as f64).exp();
+    }
+}
+
+/// Draws `k` positions uniformly without replacement from a stream, see
+/// `reservoir_sample`.
+///
+/// # Returns
+/// `min(k, n)` positions into the stream in unspecified order
+pub fn reservoir_sample_indices<I, R>(iter: I, k: usize, rng: &mut R) -> Vec<usize>
+where
+    I: IntoIterator,
+    R: RngOps + ?Sized,
+{
+    let indices = iter.into_iter().enumerate().map(|(i, _)| i);
+    reservoir_sample(indices, k, rng)
+}
+
+/// Draws `k` items without replacement from a stream of `(item, weight)` pairs, with
+/// probabilities proportional to the weights.
+///
+/// Implements the A-ES algorithm of Efraimidis and Spirakis: every item draws the key
+/// `ln(u) / w` and the `k` items with the largest keys seen so far are kept in a heap.
+/// Items with a weight that is zero, negative or not finite are never selected.
+///
+/// # Arguments
+/// * `iter` - The stream of items and their weights
+/// * `k` - The number of items to sample
+/// * `rng` - The random number generator
+///
+/// # Returns
+/// At most `k` items, ordered from the largest to the smallest key
+pub fn weighted_reservoir_sample<I, T, R>(iter: I, k: usize, rng: &mut R) -> Vec<T>
+where
+    I: IntoIterator<Item = (T, f64)>,
+    R: RngOps + ?Sized,
+{
+    if k == 0 {
+        return Vec::new();
+    }
+    let mut heap: BinaryHeap<Keyed<T>> = BinaryHeap::with_capacity(k + 1);
+    for (item, weight) in iter {
+        if !(weight.is_finite() && weight > 0.0) {
+            continue;
+        }
+        // 1 - u lies in (0, 1], which keeps the logarithm finite.
+        let key = (1.0 - rng.next_f64()).ln() / weight;
+        if heap.len() < k {
+            heap.push(Keyed { key, item });
+        } else if heap.peek().is_some_and(|smallest| key > smallest.key) {
+            heap.pop();
+            heap.push(Keyed { key, item });
+        }
+    }
+    // the heap pops the smallest keys first
+    let mut sample: Vec<T> = std::iter::from_fn(|| heap.pop().map(|k| k.item)).collect();
+    sample.reverse();
+    sample
+}
+
+/// Draws `k` positions without replacement from a stream of weights, with
+/// probabilities proportional to the weights, see `weighted_reservoir_sample`.
+///
+/// # Returns
+/// At most `k` positions into the stream, ordered from the largest to the smallest key
+pub fn weighted_reservoir_sample_indices<I, R>(weights: I, k: usize, rng: &mut R) -> Vec<usize>
+where
+    I: IntoIterator<Item = f64>,
+    R: RngOps + ?Sized,
+{
+    weighted_reservoir_sample(weights.into_iter().enumerate(), k, rng)
+}
+
+/// An item with its sampling key, ordered so that `BinaryHeap` pops the smallest key.
+struct Keyed<T> {
+    key: f64,
+    item: T,
+}
+
+impl<T> PartialEq for Keyed<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key.total_cmp(&other.key) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Keyed<T> {}
+
+impl<T> PartialOrd for Keyed<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Keyed<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key.total_cmp(&self.key)
+    }
+}
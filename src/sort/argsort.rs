@@ -0,0 +1,164 @@
+use crate::traits::FloatOps;
+use crate::types::{NanPolicy, SortOrder};
+use std::cmp::Ordering;
+
+/// Returns the positions that sort `data`.
+///
+/// Equal values keep the order of their positions, so the result is deterministic
+/// even though an unstable sort is used.
+///
+/// # Arguments
+/// * `data` - The values
+/// * `order` - Whether the smallest or largest value comes first
+/// * `nans` - Where the positions of NaN values are placed
+///
+/// # Returns
+/// The positions of the values in sorted order
+pub fn argsort<T: FloatOps>(data: &[T], order: SortOrder, nans: NanPolicy) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..data.len()).collect();
+    argsort_in_place(data, &mut indices, order, nans);
+    indices
+}
+
+/// Sorts a buffer of positions into `data` by the values they refer to.
+///
+/// The buffer may hold any subset of positions, e.g. the members of one cluster, and
+/// can be reused across calls to avoid allocating. Under `NanPolicy::Omit` the
+/// positions of NaN values are removed from it.
+///
+/// # Arguments
+/// * `data` - The values
+/// * `indices` - The positions to sort; every position must be smaller than `data.len()`
+/// * `order` - Whether the smallest or largest value comes first
+/// * `nans` - Where the positions of NaN values are placed
+pub fn argsort_in_place<T: FloatOps>(
+    data: &[T],
+    indices: &mut Vec<usize>,
+    order: SortOrder,
+    nans: NanPolicy,
+) {
+    if nans == NanPolicy::Omit {
+        indices.retain(|&i| !num_traits::Float::is_nan(data[i]));
+    }
+    indices.sort_unstable_by(|&a, &b| compare(data, a, b, order, nans));
+}
+
+/// Returns the position of the smallest value, ignoring NaN values.
+///
+/// # Returns
+/// The first position holding the smallest value, or `None` if `data` is empty or
+/// contains only NaN values
+pub fn argmin<T: FloatOps>(data: &[T]) -> Option<usize> {
+    extreme(data, Ordering::Less)
+}
+
+/// Returns the position of the largest value, ignoring NaN values.
+///
+/// # Returns
+/// The first position holding the largest value, or `None` if `data` is empty or
+/// contains only NaN values
+pub fn argmax<T: FloatOps>(data: &[T]) -> Option<usize> {
+    extreme(data, Ordering::Greater)
+}
+
+/// Returns the position that `argsort` would place at index `n`, in linear average
+/// time.
+///
+/// # Arguments
+/// * `data` - The values
+/// * `n` - The index into the sorted order, e.g. `0` for the smallest value under
+///   `SortOrder::Ascending`
+/// * `order` - Whether the smallest or largest value comes first
+/// * `nans` - Where the positions of NaN values are placed
+///
+/// # Returns
+/// The position, or `None` if fewer than `n + 1` positions take part in the order
+pub fn arg_nth<T: FloatOps>(
+    data: &[T],
+    n: usize,
+    order: SortOrder,
+    nans: NanPolicy,
+) -> Option<usize> {
+    let mut indices: Vec<usize> = (0..data.len()).collect();
+    arg_nth_in_place(data, &mut indices, n, order, nans)
+}
+
+/// Partially sorts a buffer of positions into `data` so that index `n` holds the
+/// position `argsort_in_place` would place there.
+///
+/// Positions before `n` refer to values ordered before it and positions after `n` to
+/// values ordered after it, in unspecified order. Under `NanPolicy::Omit` the
+/// positions of NaN values are removed from the buffer.
+///
+/// # Arguments
+/// * `data` - The values
+/// * `indices` - The positions to select from; every position must be smaller than
+///   `data.len()`
+/// * `n` - The index into the sorted order
+/// * `order` - Whether the smallest or largest value comes first
+/// * `nans` - Where the positions of NaN values are placed
+///
+/// # Returns
+/// The position, or `None` if the buffer holds fewer than `n + 1` positions
+pub fn arg_nth_in_place<T: FloatOps>(
+    data: &[T],
+    indices: &mut Vec<usize>,
+    n: usize,
+    order: SortOrder,
+    nans: NanPolicy,
+) -> Option<usize> {
+    if nans == NanPolicy::Omit {
+        indices.retain(|&i| !num_traits::Float::is_nan(data[i]));
+    }
+    if n >= indices.len() {
+        return None;
+    }
+    let (_, &mut nth, _) =
+        indices.select_nth_unstable_by(n, |&a, &b| compare(data, a, b, order, nans));
+    Some(nth)
+}
+
+/// Orders two positions by their values, placing NaN values according to `nans` and
+/// breaking ties by position.
+pub(crate) fn compare<T: FloatOps>(
+    data: &[T],
+    a: usize,
+    b: usize,
+    order: SortOrder,
+    nans: NanPolicy,
+) -> Ordering {
+    let nan_first = nans == NanPolicy::First;
+    let by_value = match (
+        num_traits::Float::is_nan(data[a]),
+        num_traits::Float::is_nan(data[b]),
+    ) {
+        (true, true) => Ordering::Equal,
+        (true, false) if nan_first => Ordering::Less,
+        (true, false) => Ordering::Greater,
+        (false, true) if nan_first => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => {
+            let ordering = data[a].partial_cmp(&data[b]).unwrap_or(Ordering::Equal);
+            match order {
+                SortOrder::Ascending => ordering,
+                SortOrder::Descending => ordering.reverse(),
+            }
+        }
+    };
+    by_value.then(a.cmp(&b))
+}
+
+/// Returns the first position whose value compares as `wanted` to all others.
+fn extreme<T: FloatOps>(data: &[T], wanted: Ordering) -> Option<usize> {
+    let mut best: Option<usize> = None;
+    for (i, &x) in data.iter().enumerate() {
+        if num_traits::Float::is_nan(x) {
+            continue;
+        }
+        match best {
+            Some(b) if x.partial_cmp(&data[b]) != Some(wanted) => {}
+            _ => best = Some(i),
+        }
+    }
+    best
+}
@@ -0,0 +1,17 @@
+//! Sorting, selection and permutation of index vectors.
+//!
+//! Functions return or rearrange positions into the input instead of the values
+//! themselves, so the same order can be applied to several parallel arrays. Floating
+//! point values are compared without panicking on NaN; where NaN values end up is
+//! controlled by a `NanPolicy`.
+
+mod argsort;
+mod permutation;
+mod top_k;
+
+pub use argsort::{arg_nth, arg_nth_in_place, argmax, argmin, argsort, argsort_in_place};
+pub use permutation::{
+    apply_permutation_in_place, apply_permutation_in_place_pair, apply_permutation_with,
+    invert_permutation,
+};
+pub use top_k::{top_k_indices, top_k_indices_axis};
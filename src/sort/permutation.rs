@@ -0,0 +1,168 @@
+use anyhow::anyhow;
+
+/// Reorders `data` so that position `i` holds the value previously at `perm[i]`.
+///
+/// Follows the cycles of the permutation and swaps values along them, so no copy of
+/// `data` is made. Visited positions are marked inside `perm` itself, which needs
+/// mutable access but is restored before returning; extra memory is constant.
+/// The result equals gathering `perm.iter().map(|&i| data[i])` into a new vector.
+///
+/// # Arguments
+/// * `data` - The values to reorder
+/// * `perm` - The source position of every target position
+///
+/// # Returns
+/// An error if `perm` is not a permutation of `0..data.len()`, in which case neither
+/// slice is modified
+pub fn apply_permutation_in_place<T>(data: &mut [T], perm: &mut [usize]) -> anyhow::Result<()> {
+    check_length(data.len(), perm.len())?;
+    apply_permutation_with(perm, |a, b| data.swap(a, b))
+}
+
+/// Reorders two parallel slices by the same permutation, see
+/// `apply_permutation_in_place`.
+///
+/// # Returns
+/// An error if the slices differ in length or `perm` is not a permutation of their
+/// positions, in which case no slice is modified
+pub fn apply_permutation_in_place_pair<A, B>(
+    first: &mut [A],
+    second: &mut [B],
+    perm: &mut [usize],
+) -> anyhow::Result<()> {
+    check_length(first.len(), perm.len())?;
+    check_length(second.len(), perm.len())?;
+    apply_permutation_with(perm, |a, b| {
+        first.swap(a, b);
+        second.swap(a, b);
+    })
+}
+
+/// Applies a permutation to any number of buffers through a swap callback.
+///
+/// `swap(a, b)` must exchange the entries at positions `a` and `b` of every buffer
+/// to reorder, e.g. the values, labels and embedding rows of every cell at once.
+/// Afterwards position `i` holds the entries previously at `perm[i]`.
+///
+/// # Arguments
+/// * `perm` - The source position of every target position, restored before returning
+/// * `swap` - Exchanges two positions of all buffers
+///
+/// # Returns
+/// An error if `perm` is not a permutation of `0..perm.len()`, in which case `swap`
+/// is never called
+pub fn apply_permutation_with<F>(perm: &mut [usize], mut swap: F) -> anyhow::Result<()>
+where
+    F: FnMut(usize, usize),
+{
+    check_permutation(perm)?;
+    // a visited position is marked by storing the complement of its source, which
+    // can never be a valid position
+    for start in 0..perm.len() {
+        if is_marked(perm[start]) {
+            continue;
+        }
+        let mut current = start;
+        loop {
+            let source = perm[current];
+            perm[current] = !source;
+            if source == start {
+                break;
+            }
+            swap(current, source);
+            current = source;
+        }
+    }
+    perm.iter_mut().for_each(|p| *p = !*p);
+    Ok(())
+}
+
+/// Returns the inverse of a permutation, mapping every position back to where it came
+/// from: `inverse[perm[i]] == i`.
+///
+/// # Returns
+/// The inverse permutation, or an error if `perm` is not a permutation of
+/// `0..perm.len()`
+pub fn invert_permutation(perm: &[usize]) -> anyhow::Result<Vec<usize>> {
+    let mut inverse = vec![usize::MAX; perm.len()];
+    for (i, &p) in perm.iter().enumerate() {
+        match inverse.get_mut(p) {
+            Some(slot) if *slot == usize::MAX => *slot = i,
+            _ => return Err(invalid_permutation(i, p)),
+        }
+    }
+    Ok(inverse)
+}
+
+/// Returns `true` for positions marked as visited by their complement.
+fn is_marked(value: usize) -> bool {
+    value > isize::MAX as usize
+}
+
+/// Checks that every position occurs exactly once, using the marks of
+/// `apply_permutation_with` to record which positions have been seen.
+///
+/// Values are range-checked before any of them is marked, since a value above
+/// `isize::MAX` would be mistaken for a mark and flipped when clearing the marks.
+fn check_permutation(perm: &mut [usize]) -> anyhow::Result<()> {
+    let n = perm.len();
+    if let Some((i, &p)) = perm.iter().enumerate().find(|&(_, &p)| p >= n) {
+        return Err(invalid_permutation(i, p));
+    }
+    let mut result = Ok(());
+    for i in 0..n {
+        let target = perm[i];
+        let target = if is_marked(target) { !target } else { target };
+        if is_marked(perm[target]) {
+            result = Err(invalid_permutation(i, target));
+            break;
+        }
+        perm[target] = !perm[target];
+    }
+    // clear the marks, also after an early exit
+    perm.iter_mut()
+        .filter(|p| is_marked(**p))
+        .for_each(|p| *p = !*p);
+    result
+}
+
+fn check_length(len: usize, perm_len: usize) -> anyhow::Result<()> {
+    if len != perm_len {
+        return Err(anyhow!(
+            "Permutation length ({}) does not match the buffer length ({})!",
+            perm_len,
+            len
+        ));
+    }
+    Ok(())
+}
+
+fn invalid_permutation(position: usize, value: usize) -> anyhow::Error {
+    anyhow!(
+        "Not a permutation: entry {} ({}) is out of range or repeated!",
+        position,
+        value
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_permutations_are_left_unchanged() {
+        for perm in [vec![0, usize::MAX], vec![1, 1, 0], vec![0, 3, 1]] {
+            let mut checked = perm.clone();
+            let mut data = vec!['a', 'b', 'c'][..perm.len()].to_vec();
+            assert!(apply_permutation_in_place(&mut data, &mut checked).is_err());
+            assert_eq!(checked, perm);
+            assert_eq!(data, ['a', 'b', 'c'][..perm.len()]);
+        }
+
+        let mut perm = vec![2, 0, 1];
+        let mut data = vec!['a', 'b', 'c'];
+        apply_permutation_in_place(&mut data, &mut perm).unwrap();
+        assert_eq!(data, ['c', 'a', 'b']);
+        assert_eq!(perm, [2, 0, 1]);
+    }
+}
@@ -0,0 +1,62 @@
+use super::argsort::compare;
+use crate::stats::map_lanes;
+use crate::traits::{FloatOps, FloatOpsTS, MatrixLike};
+use crate::types::{Direction, NanPolicy, SortOrder};
+
+/// Returns the positions of the `k` first values in the given order without sorting
+/// all values.
+///
+/// Selects the `k` values in linear average time and sorts only those, so the cost
+/// is `O(n + k log k)` instead of `O(n log n)` for a full argsort. NaN values are
+/// never selected and ties are broken by position.
+///
+/// # Arguments
+/// * `data` - The values
+/// * `k` - The number of positions to return
+/// * `order` - `SortOrder::Descending` for the largest and `SortOrder::Ascending` for
+///   the smallest values
+///
+/// # Returns
+/// At most `k` positions, sorted like `argsort` would order them
+pub fn top_k_indices<T: FloatOps>(data: &[T], k: usize, order: SortOrder) -> Vec<usize> {
+    if k == 0 {
+        return Vec::new();
+    }
+    let mut indices: Vec<usize> = (0..data.len())
+        .filter(|&i| !num_traits::Float::is_nan(data[i]))
+        .collect();
+    let cmp = |&a: &usize, &b: &usize| compare(data, a, b, order, NanPolicy::Omit);
+    if k < indices.len() {
+        indices.select_nth_unstable_by(k - 1, cmp);
+        indices.truncate(k);
+    }
+    indices.sort_unstable_by(cmp);
+    indices
+}
+
+/// Applies `top_k_indices` to every lane of a matrix.
+///
+/// For a clusters × genes score matrix, `Direction::ROW` yields the marker genes of
+/// every cluster. Implicit zeros of sparse inputs take part in the selection.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to select within every row or within every column
+/// * `k` - The number of positions to return per lane
+/// * `order` - `SortOrder::Descending` for the largest and `SortOrder::Ascending` for
+///   the smallest values
+///
+/// # Returns
+/// The selected positions within every lane
+pub fn top_k_indices_axis<T, M>(
+    matrix: &M,
+    direction: Direction,
+    k: usize,
+    order: SortOrder,
+) -> Vec<Vec<usize>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    map_lanes(matrix, &direction, |lane| top_k_indices(lane, k, order))
+}
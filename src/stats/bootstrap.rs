@@ -0,0 +1,194 @@
+use super::special::{normal_quantile, normal_sf};
+use super::{Interpolation, quantiles_in_place};
+use crate::random::RngOps;
+use crate::utils::parallel::map_indices;
+use anyhow::anyhow;
+
+/// How `bootstrap_ci` turns the bootstrap distribution into an interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootstrapMethod {
+    /// Quantiles `alpha / 2` and `1 - alpha / 2` of the bootstrap distribution
+    Percentile,
+    /// Bias-corrected and accelerated quantiles, which correct for skewed and biased
+    /// estimators at the cost of a jackknife pass over the data. Falls back to the
+    /// percentile interval if all or none of the replicates lie below the estimate,
+    /// as the bias correction is infinite then
+    Bca,
+}
+
+/// Confidence interval of a statistic from `bootstrap_ci`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BootstrapInterval {
+    /// The statistic of the original data
+    pub estimate: f64,
+    /// Lower bound of the interval
+    pub lower: f64,
+    /// Upper bound of the interval
+    pub upper: f64,
+    /// Standard deviation of the bootstrap distribution
+    pub standard_error: f64,
+}
+
+/// Bootstrap confidence interval of an arbitrary statistic of a sample.
+///
+/// The statistic is evaluated on `n_resamples` samples drawn with replacement from
+/// `data`, in parallel when the `rayon` feature is enabled. Every resample draws from
+/// its own stream forked from `rng`, which is advanced by a single draw, so results
+/// do not depend on the number of threads. Quantiles of the bootstrap distribution
+/// are linearly interpolated, as in scipy's `bootstrap`.
+///
+/// # Arguments
+/// * `data` - The sample
+/// * `statistic` - The statistic, e.g. a mean or median of the values
+/// * `n_resamples` - The number of bootstrap resamples, e.g. `9999`
+/// * `alpha` - One minus the confidence level, e.g. `0.05` for a 95% interval
+/// * `method` - How the interval is derived from the bootstrap distribution
+/// * `rng` - The random number generator
+///
+/// # Returns
+/// The estimate, interval and standard error, or an error if `data` is empty,
+/// `n_resamples` is zero or `alpha` lies outside `(0, 1)`. The bounds are NaN if the
+/// statistic is NaN for any resample.
+pub fn bootstrap_ci<T, F, R>(
+    data: &[T],
+    statistic: F,
+    n_resamples: usize,
+    alpha: f64,
+    method: BootstrapMethod,
+    rng: &mut R,
+) -> anyhow::Result<BootstrapInterval>
+where
+    T: Copy + Send + Sync,
+    F: Fn(&[T]) -> f64 + Sync + Send,
+    R: RngOps + Sync,
+{
+    if data.is_empty() {
+        return Err(anyhow!("Cannot bootstrap an empty sample!"));
+    }
+    if n_resamples == 0 {
+        return Err(anyhow!("Number of resamples must be positive!"));
+    }
+    if !(alpha > 0.0 && alpha < 1.0) {
+        return Err(anyhow!("Alpha {} must lie in (0, 1)!", alpha));
+    }
+    let n = data.len();
+    let estimate = statistic(data);
+    let stream = rng.next_u64();
+    let parent = rng.fork(stream);
+    let mut replicates = map_indices(n_resamples, |i| {
+        let mut rng = parent.fork(i as u64);
+        let resample: Vec<T> = (0..n).map(|_| data[rng.next_range(n)]).collect();
+        statistic(&resample)
+    });
+
+    let mean = replicates.iter().sum::<f64>() / n_resamples as f64;
+    let standard_error = if n_resamples > 1 {
+        let squares: f64 = replicates.iter().map(|r| (r - mean) * (r - mean)).sum();
+        (squares / (n_resamples - 1) as f64).sqrt()
+    } else {
+        f64::NAN
+    };
+
+    let tails = [alpha / 2.0, 1.0 - alpha / 2.0];
+    let levels = match method {
+        BootstrapMethod::Percentile => tails.to_vec(),
+        BootstrapMethod::Bca => {
+            let below = replicates.iter().filter(|&&r| r < estimate).count();
+            if below == 0 || below == n_resamples {
+                tails.to_vec()
+            } else {
+                bca_levels(data, &statistic, &tails, below as f64 / n_resamples as f64)
+            }
+        }
+    };
+    let bounds = quantiles_in_place(&mut replicates, &levels, Interpolation::Linear);
+    Ok(BootstrapInterval {
+        estimate,
+        lower: bounds[0],
+        upper: bounds[1],
+        standard_error,
+    })
+}
+
+/// Quantile levels of the BCa interval for the two `tails`.
+///
+/// # Arguments
+/// * `below` - The fraction of replicates below the estimate, in `(0, 1)`
+fn bca_levels<T, F>(data: &[T], statistic: &F, tails: &[f64], below: f64) -> Vec<f64>
+where
+    T: Copy + Send + Sync,
+    F: Fn(&[T]) -> f64 + Sync + Send,
+{
+    let bias = normal_quantile(below);
+    let acceleration = jackknife_acceleration(data, statistic);
+    tails
+        .iter()
+        .map(|&tail| {
+            let shift = bias + normal_quantile(tail);
+            let z = if acceleration == 0.0 {
+                bias + shift
+            } else {
+                bias + shift / (1.0 - acceleration * shift)
+            };
+            // P(Z <= z)
+            normal_sf(-z)
+        })
+        .collect()
+}
+
+/// Acceleration of the BCa interval from the skewness of the leave-one-out
+/// statistics; zero if they do not vary.
+fn jackknife_acceleration<T, F>(data: &[T], statistic: &F) -> f64
+where
+    T: Copy + Send + Sync,
+    F: Fn(&[T]) -> f64 + Sync + Send,
+{
+    let n = data.len();
+    let jackknife = map_indices(n, |left_out| {
+        let rest: Vec<T> = data
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != left_out)
+            .map(|(_, &v)| v)
+            .collect();
+        statistic(&rest)
+    });
+    let mean = jackknife.iter().sum::<f64>() / n as f64;
+    let (squares, cubes) = jackknife.iter().fold((0.0, 0.0), |(s, c), &j| {
+        let d = mean - j;
+        (s + d * d, c + d * d * d)
+    });
+    if squares > 0.0 {
+        cubes / (6.0 * squares.powf(1.5))
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::SeededRng;
+
+    #[test]
+    fn bca_falls_back_to_percentile_without_replicates_below_the_estimate() {
+        let data = [3.0, 1.0, 4.0, 1.0, 5.0, 9.0, 2.0, 6.0];
+        let min = |x: &[f64]| x.iter().copied().fold(f64::INFINITY, f64::min);
+        let interval =
+            |method| bootstrap_ci(&data, min, 200, 0.1, method, &mut SeededRng::new(5)).unwrap();
+        let bca = interval(BootstrapMethod::Bca);
+        assert!(bca.lower.is_finite() && bca.upper.is_finite());
+        assert_eq!(bca, interval(BootstrapMethod::Percentile));
+
+        let constant = bootstrap_ci(
+            &[2.0; 5],
+            |x: &[f64]| x.iter().sum::<f64>() / x.len() as f64,
+            50,
+            0.05,
+            BootstrapMethod::Bca,
+            &mut SeededRng::new(1),
+        )
+        .unwrap();
+        assert_eq!((constant.lower, constant.upper), (2.0, 2.0));
+    }
+}
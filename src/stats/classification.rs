@@ -0,0 +1,148 @@
+use crate::types::{BatchEncoder, BatchIdentifier};
+use anyhow::anyhow;
+use ndarray::Array2;
+
+/// Precision, recall and F1 score of one class or an average over classes.
+///
+/// Ratios with a zero denominator, e.g. the precision of a class that is never
+/// predicted, are 0 as in scikit-learn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClassMetrics {
+    /// Fraction of the observations predicted as the class that belong to it
+    pub precision: f64,
+    /// Fraction of the observations of the class that are predicted as it
+    pub recall: f64,
+    /// Harmonic mean of precision and recall
+    pub f1: f64,
+    /// Number of observations of the class; the total for averages
+    pub support: usize,
+}
+
+/// Counts of true against predicted labels, e.g. from cell-type classification or
+/// label transfer against annotated ground truth.
+///
+/// Rows correspond to the true and columns to the predicted labels. The classes are
+/// the labels occurring in either, in order of first occurrence among the true and
+/// then the predicted labels.
+#[derive(Debug, Clone)]
+pub struct ConfusionMatrix<B: BatchIdentifier> {
+    encoder: BatchEncoder<B>,
+    counts: Array2<usize>,
+}
+
+/// Tabulates true against predicted labels.
+///
+/// # Arguments
+/// * `truth` - The true label of every observation
+/// * `pred` - The predicted label of every observation
+///
+/// # Returns
+/// The confusion matrix, or an error if the label slices differ in length
+pub fn confusion_matrix<B: BatchIdentifier>(
+    truth: &[B],
+    pred: &[B],
+) -> anyhow::Result<ConfusionMatrix<B>> {
+    if truth.len() != pred.len() {
+        return Err(anyhow!(
+            "Number of true labels ({}) does not match the number of predictions ({})!",
+            truth.len(),
+            pred.len()
+        ));
+    }
+    let (mut encoder, truth_codes) = BatchEncoder::fit_transform(truth);
+    let pred_codes: Vec<usize> = pred.iter().map(|label| encoder.encode(label)).collect();
+    let mut counts = Array2::zeros((encoder.len(), encoder.len()));
+    for (&t, &p) in truth_codes.iter().zip(&pred_codes) {
+        counts[[t, p]] += 1;
+    }
+    Ok(ConfusionMatrix { encoder, counts })
+}
+
+impl<B: BatchIdentifier> ConfusionMatrix<B> {
+    /// Returns the classes, indexing the rows and columns of `counts`.
+    pub fn labels(&self) -> &[B] {
+        self.encoder.labels()
+    }
+
+    /// Returns the number of observations of every true (row) and predicted (column) class.
+    pub fn counts(&self) -> &Array2<usize> {
+        &self.counts
+    }
+
+    /// Returns the number of observations of class `truth` predicted as `pred`, 0 if
+    /// either class is unknown.
+    pub fn count(&self, truth: &B, pred: &B) -> usize {
+        match (self.encoder.code(truth), self.encoder.code(pred)) {
+            (Some(t), Some(p)) => self.counts[[t, p]],
+            _ => 0,
+        }
+    }
+
+    /// Returns the fraction of observations whose prediction is correct.
+    pub fn accuracy(&self) -> f64 {
+        ratio(self.correct(), self.counts.sum())
+    }
+
+    /// Returns the precision, recall and F1 score of every class, indexed like `labels`.
+    pub fn class_metrics(&self) -> Vec<ClassMetrics> {
+        (0..self.encoder.len())
+            .map(|class| {
+                let correct = self.counts[[class, class]];
+                let predicted = self.counts.column(class).sum();
+                let support = self.counts.row(class).sum();
+                metrics(correct, predicted, support)
+            })
+            .collect()
+    }
+
+    /// Returns the unweighted mean of the precision, recall and F1 score of all
+    /// classes, which weighs rare classes as much as common ones.
+    pub fn macro_average(&self) -> ClassMetrics {
+        let classes = self.class_metrics();
+        let mean = |value: fn(&ClassMetrics) -> f64| {
+            classes.iter().map(value).sum::<f64>() / classes.len().max(1) as f64
+        };
+        ClassMetrics {
+            precision: mean(|m| m.precision),
+            recall: mean(|m| m.recall),
+            f1: mean(|m| m.f1),
+            support: self.counts.sum(),
+        }
+    }
+
+    /// Returns precision, recall and F1 score from the counts pooled over all classes,
+    /// which weighs every observation equally.
+    ///
+    /// As every misclassification is a false positive of one class and a false
+    /// negative of another, all three equal the accuracy.
+    pub fn micro_average(&self) -> ClassMetrics {
+        let total = self.counts.sum();
+        metrics(self.correct(), total, total)
+    }
+
+    fn correct(&self) -> usize {
+        self.counts.diag().sum()
+    }
+}
+
+fn metrics(correct: usize, predicted: usize, support: usize) -> ClassMetrics {
+    let precision = ratio(correct, predicted);
+    let recall = ratio(correct, support);
+    // 2 tp / (2 tp + fp + fn) is the harmonic mean of precision and recall, and 0
+    // rather than undefined if both are 0
+    ClassMetrics {
+        precision,
+        recall,
+        f1: ratio(2 * correct, predicted + support),
+        support,
+    }
+}
+
+/// `numerator / denominator`, or 0 if the denominator is zero.
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator > 0 {
+        numerator as f64 / denominator as f64
+    } else {
+        0.0
+    }
+}
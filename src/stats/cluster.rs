@@ -0,0 +1,328 @@
+use crate::random::{RngOps, sample_indices};
+use crate::traits::{FloatOpsTS, MatrixLike};
+use crate::types::{BatchEncoder, BatchIdentifier, DistanceMetric};
+use crate::utils::parallel::map_indices;
+use anyhow::anyhow;
+
+/// Number of observations per block of the pairwise distances computed by one task.
+const BLOCK: usize = 64;
+
+/// Mean of the two entropies that `nmi` divides the mutual information by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NmiNormalization {
+    /// Arithmetic mean, the default of scikit-learn and equal to the V-measure
+    Arithmetic,
+    /// Geometric mean
+    Geometric,
+    /// Smaller of the two entropies
+    Min,
+    /// Larger of the two entropies
+    Max,
+}
+
+/// Silhouette coefficient of every observation (row) of a matrix given its cluster
+/// labels, as scikit-learn's `silhouette_samples`.
+///
+/// For an observation with mean distance `a` to the other members of its cluster and
+/// smallest mean distance `b` to the members of another cluster, the coefficient is
+/// `(b - a) / max(a, b)`; it is 0 for observations alone in their cluster. Pairwise
+/// distances are computed block by block without storing the distance matrix, in
+/// parallel when the `rayon` feature is enabled, and take time quadratic in the
+/// number of observations. Rows are densified, so the input is typically a
+/// low-dimensional embedding such as principal components.
+///
+/// # Arguments
+/// * `matrix` - An observations × dimensions matrix, dense or sparse
+/// * `labels` - The cluster of every observation
+/// * `metric` - The distance between observations
+///
+/// # Returns
+/// The coefficient of every observation in `[-1, 1]`, or an error if the number of
+/// labels does not match the number of rows or the number of clusters does not lie
+/// between 2 and the number of observations minus one
+pub fn silhouette_samples<T, M, B>(
+    matrix: &M,
+    labels: &[B],
+    metric: DistanceMetric,
+) -> anyhow::Result<Vec<f64>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+    B: BatchIdentifier,
+{
+    check_labels(matrix, labels)?;
+    let observations: Vec<usize> = (0..matrix.nrows()).collect();
+    silhouettes(matrix, labels, &observations, metric)
+}
+
+/// Mean silhouette coefficient of all observations (rows) of a matrix, see
+/// `silhouette_samples`.
+///
+/// As the cost grows quadratically with the number of observations, large matrices
+/// can be scored on a uniform subsample drawn without replacement, within which all
+/// distances are then computed.
+///
+/// # Arguments
+/// * `matrix` - An observations × dimensions matrix, dense or sparse
+/// * `labels` - The cluster of every observation
+/// * `metric` - The distance between observations
+/// * `sample_size` - The number of observations to score, e.g. `10_000`; `None`
+///   scores all of them
+/// * `rng` - The random number generator drawing the subsample
+///
+/// # Returns
+/// The mean coefficient, or an error under the conditions of `silhouette_samples`,
+/// applied to the subsample
+pub fn silhouette_score<T, M, B, R>(
+    matrix: &M,
+    labels: &[B],
+    metric: DistanceMetric,
+    sample_size: Option<usize>,
+    rng: &mut R,
+) -> anyhow::Result<f64>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+    B: BatchIdentifier,
+    R: RngOps + ?Sized,
+{
+    check_labels(matrix, labels)?;
+    let n = matrix.nrows();
+    let observations = match sample_size {
+        Some(size) if size < n => {
+            let mut sample = sample_indices(rng, n, size);
+            sample.sort_unstable();
+            sample
+        }
+        _ => (0..n).collect(),
+    };
+    let coefficients = silhouettes(matrix, labels, &observations, metric)?;
+    Ok(coefficients.iter().sum::<f64>() / coefficients.len() as f64)
+}
+
+/// Adjusted Rand index between two partitions of the same observations, e.g. a
+/// clustering and ground-truth cell types.
+///
+/// Counts the pairs of observations on whose grouping the partitions agree and
+/// corrects for the agreement expected by chance, so random labelings score close to
+/// 0 and identical partitions score 1, regardless of the label values. Like
+/// scikit-learn, partitions that both have a single group or both put every
+/// observation in its own group score 1.
+///
+/// # Arguments
+/// * `labels_a` - The group of every observation in the first partition
+/// * `labels_b` - The group of every observation in the second partition
+///
+/// # Returns
+/// The index, at most 1, or an error if the label slices differ in length
+pub fn adjusted_rand_index<A, B>(labels_a: &[A], labels_b: &[B]) -> anyhow::Result<f64>
+where
+    A: BatchIdentifier,
+    B: BatchIdentifier,
+{
+    let table = ContingencyTable::new(labels_a, labels_b)?;
+    let pairs = |count: usize| (count * count.saturating_sub(1)) as f64 / 2.0;
+    let sum_pairs = |counts: &[usize]| counts.iter().map(|&c| pairs(c)).sum::<f64>();
+    let index = sum_pairs(&table.counts);
+    let (pairs_a, pairs_b) = (sum_pairs(&table.totals_a), sum_pairs(&table.totals_b));
+    let total = pairs(labels_a.len());
+    if total == 0.0 {
+        return Ok(1.0);
+    }
+    let expected = pairs_a * pairs_b / total;
+    let max_index = (pairs_a + pairs_b) / 2.0;
+    if max_index == expected {
+        return Ok(1.0);
+    }
+    Ok((index - expected) / (max_index - expected))
+}
+
+/// Normalized mutual information between two partitions of the same observations,
+/// e.g. a clustering and ground-truth cell types.
+///
+/// The mutual information of the labels is divided by a mean of their entropies, so
+/// independent partitions score 0 and identical ones 1, regardless of the label
+/// values. Like scikit-learn, partitions that both consist of a single group score 1.
+///
+/// # Arguments
+/// * `labels_a` - The group of every observation in the first partition
+/// * `labels_b` - The group of every observation in the second partition
+/// * `normalization` - The mean of the entropies used for normalization
+///
+/// # Returns
+/// The score in `[0, 1]`, or an error if the label slices differ in length
+pub fn nmi<A, B>(
+    labels_a: &[A],
+    labels_b: &[B],
+    normalization: NmiNormalization,
+) -> anyhow::Result<f64>
+where
+    A: BatchIdentifier,
+    B: BatchIdentifier,
+{
+    let table = ContingencyTable::new(labels_a, labels_b)?;
+    let (groups_a, groups_b) = (table.totals_a.len(), table.totals_b.len());
+    if groups_a == groups_b && groups_a <= 1 {
+        return Ok(1.0);
+    }
+    let n = labels_a.len() as f64;
+    let mut mutual_information = 0.0;
+    for (a, &total_a) in table.totals_a.iter().enumerate() {
+        for (b, &total_b) in table.totals_b.iter().enumerate() {
+            let count = table.counts[a * groups_b + b] as f64;
+            if count > 0.0 {
+                mutual_information +=
+                    count / n * (n * count / (total_a as f64 * total_b as f64)).ln();
+            }
+        }
+    }
+    if mutual_information <= 0.0 {
+        return Ok(0.0);
+    }
+    let entropy = |totals: &[usize]| -> f64 {
+        totals
+            .iter()
+            .map(|&t| t as f64 / n)
+            .map(|p| -p * p.ln())
+            .sum()
+    };
+    let (entropy_a, entropy_b) = (entropy(&table.totals_a), entropy(&table.totals_b));
+    let normalizer = match normalization {
+        NmiNormalization::Arithmetic => (entropy_a + entropy_b) / 2.0,
+        NmiNormalization::Geometric => (entropy_a * entropy_b).sqrt(),
+        NmiNormalization::Min => entropy_a.min(entropy_b),
+        NmiNormalization::Max => entropy_a.max(entropy_b),
+    };
+    Ok((mutual_information / normalizer).min(1.0))
+}
+
+/// Co-occurrence counts of the groups of two partitions.
+struct ContingencyTable {
+    /// Counts of every pair of groups, row-major over the groups of the first partition
+    counts: Vec<usize>,
+    /// Size of every group of the first partition
+    totals_a: Vec<usize>,
+    /// Size of every group of the second partition
+    totals_b: Vec<usize>,
+}
+
+impl ContingencyTable {
+    fn new<A, B>(labels_a: &[A], labels_b: &[B]) -> anyhow::Result<Self>
+    where
+        A: BatchIdentifier,
+        B: BatchIdentifier,
+    {
+        if labels_a.len() != labels_b.len() {
+            return Err(anyhow!(
+                "Label slices must have the same length ({} != {})!",
+                labels_a.len(),
+                labels_b.len()
+            ));
+        }
+        let (encoder_a, codes_a) = BatchEncoder::fit_transform(labels_a);
+        let (encoder_b, codes_b) = BatchEncoder::fit_transform(labels_b);
+        let mut table = Self {
+            counts: vec![0; encoder_a.len() * encoder_b.len()],
+            totals_a: vec![0; encoder_a.len()],
+            totals_b: vec![0; encoder_b.len()],
+        };
+        for (&a, &b) in codes_a.iter().zip(&codes_b) {
+            table.counts[a * encoder_b.len() + b] += 1;
+            table.totals_a[a] += 1;
+            table.totals_b[b] += 1;
+        }
+        Ok(table)
+    }
+}
+
+fn check_labels<T, M, B>(matrix: &M, labels: &[B]) -> anyhow::Result<()>
+where
+    T: Copy,
+    M: MatrixLike<T>,
+{
+    if labels.len() != matrix.nrows() {
+        return Err(anyhow!(
+            "Number of labels ({}) does not match the number of rows ({})!",
+            labels.len(),
+            matrix.nrows()
+        ));
+    }
+    Ok(())
+}
+
+/// Silhouette coefficients of the rows `observations`, relative to each other.
+fn silhouettes<T, M, B>(
+    matrix: &M,
+    labels: &[B],
+    observations: &[usize],
+    metric: DistanceMetric,
+) -> anyhow::Result<Vec<f64>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+    B: BatchIdentifier,
+{
+    let selected: Vec<B> = observations.iter().map(|&o| labels[o].clone()).collect();
+    let (encoder, codes) = BatchEncoder::fit_transform(&selected);
+    let (n, num_clusters) = (observations.len(), encoder.len());
+    if num_clusters < 2 || num_clusters >= n {
+        return Err(anyhow!(
+            "Number of clusters ({}) must lie between 2 and the number of observations minus one ({})!",
+            num_clusters,
+            n.saturating_sub(1)
+        ));
+    }
+    let ncols = matrix.ncols();
+    let rows = map_indices(n, |i| {
+        let mut values = vec![T::zero(); ncols];
+        matrix.row_into(observations[i], &mut values);
+        values
+            .into_iter()
+            .map(|v| v.to_f64().unwrap())
+            .collect::<Vec<f64>>()
+    });
+    let mut sizes = vec![0usize; num_clusters];
+    codes.iter().for_each(|&c| sizes[c] += 1);
+
+    let sums = cluster_distance_sums(&rows, &codes, num_clusters, metric);
+    Ok(sums
+        .iter()
+        .zip(&codes)
+        .map(|(sums, &own)| {
+            if sizes[own] == 1 {
+                return 0.0;
+            }
+            let a = sums[own] / (sizes[own] - 1) as f64;
+            let b = (0..num_clusters)
+                .filter(|&c| c != own)
+                .map(|c| sums[c] / sizes[c] as f64)
+                .fold(f64::INFINITY, f64::min);
+            let spread = a.max(b);
+            if spread > 0.0 { (b - a) / spread } else { 0.0 }
+        })
+        .collect())
+}
+
+/// Sums of the distances of every row to the other rows of every cluster.
+fn cluster_distance_sums(
+    rows: &[Vec<f64>],
+    codes: &[usize],
+    num_clusters: usize,
+    metric: DistanceMetric,
+) -> Vec<Vec<f64>> {
+    let n = rows.len();
+    // every task covers a band of rows and walks the other rows block by block
+    let bands = map_indices(n.div_ceil(BLOCK), |band| {
+        let band = band * BLOCK..((band + 1) * BLOCK).min(n);
+        let mut sums = vec![vec![0.0; num_clusters]; band.len()];
+        for block in (0..n).step_by(BLOCK) {
+            for (i, sums) in band.clone().zip(sums.iter_mut()) {
+                for j in (block..(block + BLOCK).min(n)).filter(|&j| j != i) {
+                    sums[codes[j]] += metric.distance(&rows[i], &rows[j]);
+                }
+            }
+        }
+        sums
+    });
+    bands.into_iter().flatten().collect()
+}
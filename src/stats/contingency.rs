@@ -0,0 +1,194 @@
+#[cfg(feature = "ndarray")]
+use super::special::chi_square_sf;
+use crate::utils::stats::ln_choose;
+#[cfg(feature = "ndarray")]
+use anyhow::anyhow;
+#[cfg(feature = "ndarray")]
+use ndarray::{Array2, ArrayView2};
+
+/// Relative tolerance under which the probability of a table counts as equal to the
+/// observed one in the two-sided Fisher's exact test.
+const RELATIVE_TOLERANCE: f64 = 1e-7;
+
+/// Outcome of Fisher's exact test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FisherExactResult {
+    /// The sample odds ratio `(a * d) / (b * c)`; infinite if only the denominator is
+    /// zero and NaN if both are
+    pub odds_ratio: f64,
+    /// Two-sided p-value
+    pub pvalue: f64,
+}
+
+/// Outcome of Pearson's chi-square test of independence.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg(feature = "ndarray")]
+pub struct ChiSquareResult {
+    /// The chi-square statistic
+    pub statistic: f64,
+    /// Degrees of freedom, `(rows - 1) * (columns - 1)`
+    pub df: usize,
+    /// Upper tail p-value of the statistic
+    pub pvalue: f64,
+    /// Expected count of every cell under independence, from the products of the margins
+    pub expected: Array2<f64>,
+}
+
+/// Upper tail `P(X >= k)` of the hypergeometric distribution.
+///
+/// `X` counts the successes when drawing `n` items without replacement from a
+/// population of `total` items of which `successes` are successes, e.g. the overlap
+/// of a query gene set of size `n` with a pathway of size `successes` within a
+/// universe of `total` genes.
+///
+/// # Arguments
+/// * `k` - The smallest number of drawn successes included in the tail
+/// * `successes` - The number of successes in the population
+/// * `n` - The number of draws
+/// * `total` - The size of the population
+///
+/// # Returns
+/// The tail probability, or NaN if `successes` or `n` exceed `total`
+pub fn hypergeometric_sf(k: usize, successes: usize, n: usize, total: usize) -> f64 {
+    if successes > total || n > total {
+        return f64::NAN;
+    }
+    let lower = n.saturating_sub(total - successes);
+    let upper = successes.min(n);
+    if k <= lower {
+        return 1.0;
+    }
+    if k > upper {
+        return 0.0;
+    }
+    let ln_pmf: Vec<f64> = (k..=upper)
+        .map(|x| ln_hypergeometric_pmf(x, successes, n, total))
+        .collect();
+    let largest = ln_pmf.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let scaled: f64 = ln_pmf.iter().map(|&l| (l - largest).exp()).sum();
+    (largest.exp() * scaled).min(1.0)
+}
+
+/// Fisher's exact test of independence of the rows and columns of a 2×2 contingency
+/// table.
+///
+/// The two-sided p-value sums the probabilities of all tables with the observed
+/// margins that are at most as likely as the observed table.
+///
+/// # Arguments
+/// * `table` - The counts `[[a, b], [c, d]]`
+///
+/// # Returns
+/// The sample odds ratio and two-sided p-value; the p-value is 1 if a margin is zero
+pub fn fisher_exact(table: [[usize; 2]; 2]) -> FisherExactResult {
+    let [[a, b], [c, d]] = table;
+    let numerator = a as f64 * d as f64;
+    let denominator = b as f64 * c as f64;
+    let odds_ratio = if denominator > 0.0 {
+        numerator / denominator
+    } else if numerator > 0.0 {
+        f64::INFINITY
+    } else {
+        f64::NAN
+    };
+
+    // `a` follows a hypergeometric distribution given the margins
+    let (row, column, total) = (a + b, a + c, a + b + c + d);
+    let lower = row.saturating_sub(total - column);
+    let upper = row.min(column);
+    let observed = ln_hypergeometric_pmf(a, column, row, total);
+    let threshold = observed + RELATIVE_TOLERANCE.ln_1p();
+    let pvalue: f64 = (lower..=upper)
+        .map(|x| ln_hypergeometric_pmf(x, column, row, total))
+        .filter(|&l| l <= threshold)
+        .map(f64::exp)
+        .sum();
+    FisherExactResult {
+        odds_ratio,
+        pvalue: pvalue.min(1.0),
+    }
+}
+
+/// Pearson's chi-square test of independence of the rows and columns of a
+/// contingency table, e.g. of cluster membership and condition when comparing the
+/// cluster composition of samples.
+///
+/// Expected counts are the products of the row and column totals divided by the
+/// grand total. With `yates`, the absolute deviation of every cell from its expected
+/// count is reduced by 0.5, but not below zero, if the table has one degree of
+/// freedom, as in scipy's `chi2_contingency`.
+///
+/// # Arguments
+/// * `contingency` - The observed counts, e.g. clusters × conditions
+/// * `yates` - Whether to apply Yates' continuity correction to 2×2 tables
+///
+/// # Returns
+/// The statistic, degrees of freedom, p-value and expected counts, or an error if the
+/// table is empty or has a row or column without counts. Tables with a single row or
+/// column have no degrees of freedom and a p-value of 1.
+#[cfg(feature = "ndarray")]
+pub fn chi_square_test(
+    contingency: ArrayView2<u64>,
+    yates: bool,
+) -> anyhow::Result<ChiSquareResult> {
+    if contingency.is_empty() {
+        return Err(anyhow!("Contingency table must not be empty!"));
+    }
+    let totals = |axis| -> Vec<f64> {
+        contingency
+            .axis_iter(axis)
+            .map(|lane| lane.iter().map(|&c| c as f64).sum())
+            .collect()
+    };
+    let row_totals = totals(ndarray::Axis(0));
+    let col_totals = totals(ndarray::Axis(1));
+    if let Some(row) = row_totals.iter().position(|&t| t == 0.0) {
+        return Err(anyhow!(
+            "Row {} of the contingency table has no counts!",
+            row
+        ));
+    }
+    if let Some(col) = col_totals.iter().position(|&t| t == 0.0) {
+        return Err(anyhow!(
+            "Column {} of the contingency table has no counts!",
+            col
+        ));
+    }
+    let total: f64 = row_totals.iter().sum();
+    let expected = Array2::from_shape_fn(contingency.dim(), |(row, col)| {
+        row_totals[row] * col_totals[col] / total
+    });
+
+    let (nrows, ncols) = contingency.dim();
+    let df = (nrows - 1) * (ncols - 1);
+    if df == 0 {
+        return Ok(ChiSquareResult {
+            statistic: 0.0,
+            df,
+            pvalue: 1.0,
+            expected,
+        });
+    }
+    let correction: f64 = if yates && df == 1 { 0.5 } else { 0.0 };
+    let statistic: f64 = contingency
+        .iter()
+        .zip(&expected)
+        .map(|(&observed, &e)| {
+            let deviation = (observed as f64 - e).abs();
+            let deviation = deviation - correction.min(deviation);
+            deviation * deviation / e
+        })
+        .sum();
+    Ok(ChiSquareResult {
+        statistic,
+        df,
+        pvalue: chi_square_sf(statistic, df as f64),
+        expected,
+    })
+}
+
+/// Logarithm of the probability of drawing exactly `x` successes, see
+/// `hypergeometric_sf`.
+fn ln_hypergeometric_pmf(x: usize, successes: usize, n: usize, total: usize) -> f64 {
+    ln_choose(successes, x) + ln_choose(total - successes, n - x) - ln_choose(total, n)
+}
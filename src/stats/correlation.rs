@@ -0,0 +1,166 @@
+#[cfg(feature = "ndarray")]
+use super::map_lanes;
+use super::rank::rank;
+use crate::traits::FloatOps;
+#[cfg(feature = "ndarray")]
+use crate::traits::{FloatOpsTS, MatrixLike};
+#[cfg(feature = "ndarray")]
+use crate::types::Direction;
+use crate::types::TieMethod;
+#[cfg(feature = "ndarray")]
+use crate::utils::parallel::{concat, map_indices};
+use anyhow::anyhow;
+#[cfg(feature = "ndarray")]
+use ndarray::Array2;
+
+/// Number of lanes per block of the correlation matrix computed by one task.
+#[cfg(feature = "ndarray")]
+const BLOCK: usize = 64;
+
+/// Pearson correlation coefficient of two equally long slices.
+///
+/// # Returns
+/// The correlation in `[-1, 1]`, NaN if either slice has zero variance or fewer than
+/// two values, or an error if the slices differ in length
+pub fn pearson<T: FloatOps>(a: &[T], b: &[T]) -> anyhow::Result<T> {
+    if a.len() != b.len() {
+        return Err(anyhow!(
+            "Cannot correlate slices of different lengths ({} and {})!",
+            a.len(),
+            b.len()
+        ));
+    }
+    match (standardize(a), standardize(b)) {
+        (Some(a), Some(b)) => Ok(dot_clamped(&a, &b)),
+        _ => Ok(num_traits::Float::nan()),
+    }
+}
+
+/// Pearson correlations between all pairs of lanes of a matrix.
+///
+/// With `Direction::COLUMN` the result is a columns × columns matrix, e.g. the
+/// gene–gene correlations of a cells × genes matrix; with `Direction::ROW` it is a
+/// rows × rows matrix. Lanes are standardized once and the symmetric result is
+/// computed in blocks, in parallel when the `rayon` feature is enabled. Rows and
+/// columns of zero-variance lanes are NaN, including their diagonal entry.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to correlate the rows or the columns
+#[cfg(feature = "ndarray")]
+pub fn correlation_matrix<T, M>(matrix: &M, direction: Direction) -> Array2<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let lanes = map_lanes(matrix, &direction, |lane| standardize(lane));
+    correlate_lanes(&lanes)
+}
+
+/// Spearman rank correlation coefficient of two equally long slices.
+///
+/// Computed as the Pearson correlation of the average ranks of both slices, which
+/// equals the tie-corrected Spearman coefficient. NaN values are ranked above all
+/// other values.
+///
+/// # Returns
+/// The correlation in `[-1, 1]`, NaN if either slice is constant or has fewer than
+/// two values, or an error if the slices differ in length
+pub fn spearman<T: FloatOps>(a: &[T], b: &[T]) -> anyhow::Result<T> {
+    if a.len() != b.len() {
+        return Err(anyhow!(
+            "Cannot correlate slices of different lengths ({} and {})!",
+            a.len(),
+            b.len()
+        ));
+    }
+    match (standardize_ranks(a), standardize_ranks(b)) {
+        (Some(a), Some(b)) => Ok(dot_clamped(&a, &b)),
+        _ => Ok(num_traits::Float::nan()),
+    }
+}
+
+/// Spearman rank correlations between all pairs of lanes of a matrix.
+///
+/// Every lane, including the implicit zeros of sparse inputs, is replaced by its
+/// average ranks, which are then correlated like in `correlation_matrix`.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to correlate the rows or the columns
+#[cfg(feature = "ndarray")]
+pub fn spearman_matrix<T, M>(matrix: &M, direction: Direction) -> Array2<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let lanes = map_lanes(matrix, &direction, |lane| standardize_ranks(lane));
+    correlate_lanes(&lanes)
+}
+
+/// Correlations between all pairs of standardized lanes; `None` marks zero-variance lanes.
+#[cfg(feature = "ndarray")]
+fn correlate_lanes<T: FloatOpsTS>(lanes: &[Option<Vec<T>>]) -> Array2<T> {
+    let n = lanes.len();
+    let correlate = |i: usize, j: usize| match (&lanes[i], &lanes[j]) {
+        (Some(_), Some(_)) if i == j => T::one(),
+        (Some(a), Some(b)) => dot_clamped(a, b),
+        _ => num_traits::Float::nan(),
+    };
+    // every task fills the upper triangle of a band of rows, block by block
+    let bands = map_indices(n.div_ceil(BLOCK), |band| {
+        let rows = band * BLOCK..((band + 1) * BLOCK).min(n);
+        let mut values = vec![T::zero(); rows.len() * n];
+        for block in (rows.start..n).step_by(BLOCK) {
+            for i in rows.clone() {
+                for j in block.max(i)..(block + BLOCK).min(n) {
+                    values[(i - rows.start) * n + j] = correlate(i, j);
+                }
+            }
+        }
+        values
+    });
+    let mut result = Array2::from_shape_vec((n, n), concat(&bands))
+        .expect("bands cover every row of the correlation matrix");
+    for i in 0..n {
+        for j in 0..i {
+            result[[i, j]] = result[[j, i]];
+        }
+    }
+    result
+}
+
+/// Centers `values` and scales them to unit Euclidean norm.
+///
+/// # Returns
+/// The standardized values, or `None` if they have zero variance or fewer than two values
+fn standardize<T: FloatOps>(values: &[T]) -> Option<Vec<T>> {
+    if values.len() < 2 {
+        return None;
+    }
+    let mean = values.iter().fold(T::zero(), |acc, &x| acc + x) / T::from(values.len()).unwrap();
+    let mut centered: Vec<T> = values.iter().map(|&x| x - mean).collect();
+    let norm = centered
+        .iter()
+        .fold(T::zero(), |acc, &x| acc + x * x)
+        .sqrt();
+    if !(norm > T::zero() && num_traits::Float::is_finite(norm)) {
+        return None;
+    }
+    centered.iter_mut().for_each(|x| *x = *x / norm);
+    Some(centered)
+}
+
+/// Standardizes the average ranks of `values`; see `standardize`.
+fn standardize_ranks<T: FloatOps>(values: &[T]) -> Option<Vec<T>> {
+    let ranks: Vec<T> = rank(values, TieMethod::Average)
+        .into_iter()
+        .map(|r| T::from_f64(r).unwrap())
+        .collect();
+    standardize(&ranks)
+}
+
+fn dot_clamped<T: FloatOps>(a: &[T], b: &[T]) -> T {
+    let dot = a.iter().zip(b).fold(T::zero(), |acc, (&x, &y)| acc + x * y);
+    num_traits::Float::max(num_traits::Float::min(dot, T::one()), -T::one())
+}
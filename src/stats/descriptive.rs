@@ -0,0 +1,275 @@
+use super::lane_shape;
+use super::moments::RunningMoments;
+use crate::traits::{FloatOps, FloatOpsTS, MatrixLike, SparseRowAccess, UIndex};
+use crate::types::Direction;
+use crate::utils::parallel::{map_indices, num_threads};
+
+/// Sum of all values; 0 for an empty slice.
+pub fn sum<T: FloatOps>(data: &[T]) -> T {
+    data.iter().fold(T::zero(), |acc, &x| acc + x)
+}
+
+/// Arithmetic mean of all values; NaN for an empty slice.
+pub fn mean<T: FloatOps>(data: &[T]) -> T {
+    sum(data) / T::from(data.len()).unwrap()
+}
+
+/// Geometric mean of all values after adding a pseudocount, `exp(mean(ln(x + pseudocount)))`.
+///
+/// # Arguments
+/// * `data` - The values
+/// * `pseudocount` - Added to every value before taking the logarithm, e.g. `1.0` for
+///   counts containing zeros
+///
+/// # Returns
+/// The geometric mean; 0 if a shifted value is 0, NaN if one is negative or the slice
+/// is empty
+pub fn geometric_mean<T: FloatOps>(data: &[T], pseudocount: T) -> T {
+    mean_log(data, pseudocount).exp()
+}
+
+/// Variance of all values.
+///
+/// The squared deviations from the mean are divided by `n - ddof`, so `ddof = 0`
+/// yields the population variance and `ddof = 1` the unbiased sample variance.
+///
+/// # Arguments
+/// * `data` - The values
+/// * `ddof` - Delta degrees of freedom subtracted from the number of values
+///
+/// # Returns
+/// The variance, or NaN if there are not more than `ddof` values
+pub fn variance<T: FloatOps>(data: &[T], ddof: usize) -> T {
+    if data.len() <= ddof {
+        return num_traits::Float::nan();
+    }
+    let m = mean(data);
+    let squares = data
+        .iter()
+        .fold(T::zero(), |acc, &x| acc + (x - m) * (x - m));
+    squares / T::from(data.len() - ddof).unwrap()
+}
+
+/// Standard deviation of all values; the square root of `variance`.
+pub fn std<T: FloatOps>(data: &[T], ddof: usize) -> T {
+    variance(data, ddof).sqrt()
+}
+
+/// Sums of every lane of a matrix.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to compute one value per row or per column
+pub fn sum_axis<T, M>(matrix: &M, direction: Direction) -> Vec<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    lane_sums(matrix, &direction)
+}
+
+/// Arithmetic means of every lane of a matrix; NaN for lanes of length 0.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to compute one value per row or per column
+pub fn mean_axis<T, M>(matrix: &M, direction: Direction) -> Vec<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    lane_means(matrix, &direction)
+}
+
+/// Variances of every lane of a matrix.
+///
+/// See `variance` for the role of `ddof`. Sparse inputs are processed without
+/// densifying; the implicit zeros of a lane contribute their deviation from the
+/// lane mean.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to compute one value per row or per column
+/// * `ddof` - Delta degrees of freedom subtracted from the lane length
+///
+/// # Returns
+/// The variance of every lane, all NaN if lanes do not have more than `ddof` values
+pub fn variance_axis<T, M>(matrix: &M, direction: Direction, ddof: usize) -> Vec<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    mean_var_axis(matrix, direction, ddof).1
+}
+
+/// Means and variances of every lane of a matrix, e.g. the per-feature pairs a
+/// dispersion trend is fitted to.
+///
+/// Computes both in two passes over the matrix; see `variance_axis` for the
+/// handling of sparse inputs and `ddof`.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to compute one pair per row or per column
+/// * `ddof` - Delta degrees of freedom subtracted from the lane length
+///
+/// # Returns
+/// The means and variances of every lane; variances are NaN if lanes do not have
+/// more than `ddof` values
+pub fn mean_var_axis<T, M>(matrix: &M, direction: Direction, ddof: usize) -> (Vec<T>, Vec<T>)
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let (num_lanes, len) = lane_shape(matrix, &direction);
+    let means = lane_means(matrix, &direction);
+    if len <= ddof {
+        return (means, vec![num_traits::Float::nan(); num_lanes]);
+    }
+    let finish = |squares: T, stored: usize, mean: T| {
+        let implicit = T::from(len - stored).unwrap() * mean * mean;
+        (squares + implicit) / T::from(len - ddof).unwrap()
+    };
+
+    let variances = if direction.is_row() {
+        map_indices(num_lanes, |row| {
+            let mean = means[row];
+            let mut squares = T::zero();
+            let mut stored = 0usize;
+            matrix.for_each_in_row(row, |_, val| {
+                squares += (val - mean) * (val - mean);
+                stored += 1;
+            });
+            finish(squares, stored, mean)
+        })
+    } else {
+        let mut squares = vec![T::zero(); num_lanes];
+        let mut stored = vec![0usize; num_lanes];
+        for row in 0..matrix.nrows() {
+            matrix.for_each_in_row(row, |col, val| {
+                squares[col] += (val - means[col]) * (val - means[col]);
+                stored[col] += 1;
+            });
+        }
+        (0..num_lanes)
+            .map(|col| finish(squares[col], stored[col], means[col]))
+            .collect()
+    };
+    (means, variances)
+}
+
+/// Standard deviations of every lane of a matrix; the square roots of `variance_axis`.
+pub fn std_axis<T, M>(matrix: &M, direction: Direction, ddof: usize) -> Vec<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let mut values = variance_axis(matrix, direction, ddof);
+    values.iter_mut().for_each(|v| *v = v.sqrt());
+    values
+}
+
+/// Means and variances of every lane of a sparse matrix, computed from the stored
+/// entries alone.
+///
+/// Implicit zeros are accounted for analytically, so memory use is independent of
+/// the density of the matrix. Row lanes are processed from the compressed rows
+/// directly; column lanes are accumulated over chunks of rows in parallel and merged,
+/// which makes this considerably faster than `variance_axis` for per-gene statistics
+/// of a cells × genes CSR matrix.
+///
+/// # Arguments
+/// * `matrix` - A sparse matrix with direct row access, e.g. `CsrData`
+/// * `direction` - Whether to compute one value per row or per column
+/// * `ddof` - Delta degrees of freedom subtracted from the lane length
+///
+/// # Returns
+/// The means and variances of every lane; variances are NaN if lanes do not have
+/// more than `ddof` values
+pub fn sparse_mean_var<T, M>(matrix: &M, direction: Direction, ddof: usize) -> (Vec<T>, Vec<T>)
+where
+    T: FloatOpsTS,
+    M: SparseRowAccess<T> + Sync,
+{
+    let (num_lanes, len) = lane_shape(matrix, &direction);
+    let lanes: Vec<RunningMoments<T>> = if direction.is_row() {
+        map_indices(num_lanes, |row| {
+            let mut moments = RunningMoments::new();
+            matrix
+                .row_entries(row)
+                .1
+                .iter()
+                .for_each(|&v| moments.push(v));
+            moments
+        })
+    } else {
+        let num_chunks = num_threads().min(len).max(1);
+        let chunk_len = len.div_ceil(num_chunks);
+        let chunks = map_indices(num_chunks, |chunk| {
+            let mut moments = vec![RunningMoments::new(); num_lanes];
+            for row in chunk * chunk_len..len.min((chunk + 1) * chunk_len) {
+                let (indices, values) = matrix.row_entries(row);
+                for (&col, &v) in indices.iter().zip(values) {
+                    moments[col.to_index()].push(v);
+                }
+            }
+            moments
+        });
+        let mut chunks = chunks.into_iter();
+        let mut lanes = chunks
+            .next()
+            .unwrap_or_else(|| vec![RunningMoments::new(); num_lanes]);
+        for chunk in chunks {
+            lanes.iter_mut().zip(&chunk).for_each(|(a, b)| a.merge(b));
+        }
+        lanes
+    };
+    lanes
+        .into_iter()
+        .map(|mut moments| {
+            moments.push_n(T::zero(), len - moments.count());
+            let moments = moments.finalize(ddof);
+            (moments.mean, moments.variance)
+        })
+        .unzip()
+}
+
+/// Arithmetic mean of `ln(x + pseudocount)`, shared with the centered log-ratio transform.
+pub(crate) fn mean_log<T: FloatOps>(data: &[T], pseudocount: T) -> T {
+    let total = data
+        .iter()
+        .fold(T::zero(), |acc, &x| acc + (x + pseudocount).ln());
+    total / T::from(data.len()).unwrap()
+}
+
+fn lane_sums<T, M>(matrix: &M, direction: &Direction) -> Vec<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    if direction.is_row() {
+        map_indices(matrix.nrows(), |row| {
+            let mut acc = T::zero();
+            matrix.for_each_in_row(row, |_, val| acc += val);
+            acc
+        })
+    } else {
+        let mut acc = vec![T::zero(); matrix.ncols()];
+        for row in 0..matrix.nrows() {
+            matrix.for_each_in_row(row, |col, val| acc[col] += val);
+        }
+        acc
+    }
+}
+
+fn lane_means<T, M>(matrix: &M, direction: &Direction) -> Vec<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let (_, len) = lane_shape(matrix, direction);
+    let len = T::from(len).unwrap();
+    let mut values = lane_sums(matrix, direction);
+    values.iter_mut().for_each(|v| *v = *v / len);
+    values
+}
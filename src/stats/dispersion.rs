@@ -0,0 +1,215 @@
+use super::robust::{mad_in_place, median_in_place};
+use super::{Interpolation, quantiles};
+use anyhow::anyhow;
+
+/// Trend of the log dispersion as a function of the log mean.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DispersionTrend {
+    /// Least-squares polynomial of the given degree; residuals are divided by their
+    /// normal-scaled median absolute deviation
+    Polynomial { degree: usize },
+    /// Median of every bin of features with similar means, the `cell_ranger` flavor of
+    /// scanpy; dispersions are standardized by the normal-scaled median absolute
+    /// deviation of their bin
+    BinnedMedian { num_bins: usize },
+}
+
+/// Dispersions of every feature together with the fitted trend.
+///
+/// All vectors hold one value per feature; features with a mean or variance that
+/// is not positive and finite are NaN throughout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DispersionFit {
+    /// Natural logarithm of the variance-to-mean ratio
+    pub dispersions: Vec<f64>,
+    /// The trend evaluated at the mean of every feature
+    pub trend: Vec<f64>,
+    /// Dispersions relative to the trend in robust standard units, the score that
+    /// highly variable feature selection thresholds
+    pub normalized: Vec<f64>,
+}
+
+/// Fits a trend to the mean–dispersion relationship of features and normalizes their
+/// dispersions against it.
+///
+/// The dispersion of a feature is the log of its variance-to-mean ratio, which grows
+/// with the mean for count data, so raw dispersions favour highly expressed features.
+/// Subtracting a trend fitted over `ln(mean)` and dividing by a robust spread leaves
+/// scores that are comparable across the expression range. The mean–variance pairs
+/// typically come from `mean_var_axis` or `sparse_mean_var` over the features of a
+/// normalized matrix.
+///
+/// # Arguments
+/// * `means` - The mean of every feature
+/// * `variances` - The variance of every feature
+/// * `trend` - The trend fitted to the dispersions
+///
+/// # Returns
+/// The dispersions, trend and normalized dispersions of every feature, or an error if
+/// the lengths differ, the number of bins is zero or there are not more valid
+/// features than the polynomial degree
+pub fn fit_dispersion_trend(
+    means: &[f64],
+    variances: &[f64],
+    trend: DispersionTrend,
+) -> anyhow::Result<DispersionFit> {
+    if means.len() != variances.len() {
+        return Err(anyhow!(
+            "Number of means ({}) does not match the number of variances ({})!",
+            means.len(),
+            variances.len()
+        ));
+    }
+    let n = means.len();
+    let positive = |v: f64| v.is_finite() && v > 0.0;
+    let valid: Vec<usize> = (0..n)
+        .filter(|&i| positive(means[i]) && positive(variances[i]))
+        .collect();
+    let x: Vec<f64> = valid.iter().map(|&i| means[i].ln()).collect();
+    let y: Vec<f64> = valid
+        .iter()
+        .map(|&i| (variances[i] / means[i]).ln())
+        .collect();
+
+    let (fitted, normalized) = match trend {
+        DispersionTrend::Polynomial { degree } => polynomial_trend(&x, &y, degree)?,
+        DispersionTrend::BinnedMedian { num_bins } => binned_trend(&x, &y, num_bins)?,
+    };
+    let mut fit = DispersionFit {
+        dispersions: vec![f64::NAN; n],
+        trend: vec![f64::NAN; n],
+        normalized: vec![f64::NAN; n],
+    };
+    for (k, &i) in valid.iter().enumerate() {
+        fit.dispersions[i] = y[k];
+        fit.trend[i] = fitted[k];
+        fit.normalized[i] = normalized[k];
+    }
+    Ok(fit)
+}
+
+/// Fits a least-squares polynomial of `degree` to `y` over `x` and standardizes the
+/// residuals by their normal-scaled MAD.
+fn polynomial_trend(x: &[f64], y: &[f64], degree: usize) -> anyhow::Result<(Vec<f64>, Vec<f64>)> {
+    if x.len() <= degree {
+        return Err(anyhow!(
+            "A polynomial of degree {} needs more than {} valid features, got {}!",
+            degree,
+            degree,
+            x.len()
+        ));
+    }
+    // Mapping x onto [-1, 1] keeps the normal equations well conditioned.
+    let (lo, hi) = x
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+            (lo.min(v), hi.max(v))
+        });
+    let half_width = if hi > lo { (hi - lo) / 2.0 } else { 1.0 };
+    let scaled = |v: f64| (v - (lo + hi) / 2.0) / half_width;
+    let powers =
+        |v: f64| std::iter::successors(Some(1.0), move |p| Some(p * scaled(v))).take(degree + 1);
+
+    let size = degree + 1;
+    let mut gram = vec![vec![0.0; size]; size];
+    let mut rhs = vec![0.0; size];
+    for (&xi, &yi) in x.iter().zip(y) {
+        let p: Vec<f64> = powers(xi).collect();
+        for (row, &pr) in p.iter().enumerate() {
+            rhs[row] += pr * yi;
+            for (col, &pc) in p.iter().enumerate() {
+                gram[row][col] += pr * pc;
+            }
+        }
+    }
+    let coefs = solve(gram, rhs).ok_or_else(|| {
+        anyhow!("Polynomial fit is singular; the means take too few distinct values!")
+    })?;
+
+    let fitted: Vec<f64> = x
+        .iter()
+        .map(|&xi| powers(xi).zip(&coefs).map(|(p, c)| p * c).sum())
+        .collect();
+    let mut residuals: Vec<f64> = y.iter().zip(&fitted).map(|(yi, fi)| yi - fi).collect();
+    let spread = unit_if_zero(mad_in_place(&mut residuals.clone(), true));
+    residuals.iter_mut().for_each(|r| *r /= spread);
+    Ok((fitted, residuals))
+}
+
+/// Bins the features into `num_bins` bins of equal size by the quantiles of `x` and
+/// standardizes `y` by the median and normal-scaled MAD of every bin.
+fn binned_trend(x: &[f64], y: &[f64], num_bins: usize) -> anyhow::Result<(Vec<f64>, Vec<f64>)> {
+    if num_bins == 0 {
+        return Err(anyhow!("Number of bins must be positive!"));
+    }
+    if x.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    let qs: Vec<f64> = (1..num_bins).map(|b| b as f64 / num_bins as f64).collect();
+    let edges = quantiles(x, &qs, Interpolation::Linear)?;
+    // features equal to an edge fall into the lower bin, so ties share a bin
+    let bins: Vec<usize> = x
+        .iter()
+        .map(|&v| edges.partition_point(|&e| e < v))
+        .collect();
+
+    let mut members: Vec<Vec<f64>> = vec![Vec::new(); num_bins];
+    for (&bin, &yi) in bins.iter().zip(y) {
+        members[bin].push(yi);
+    }
+    let stats: Vec<(f64, f64)> = members
+        .into_iter()
+        .map(|mut values| {
+            if values.is_empty() {
+                return (f64::NAN, f64::NAN);
+            }
+            let center = median_in_place(&mut values);
+            (center, unit_if_zero(mad_in_place(&mut values, true)))
+        })
+        .collect();
+
+    Ok(bins
+        .iter()
+        .zip(y)
+        .map(|(&bin, &yi)| {
+            let (center, spread) = stats[bin];
+            (center, (yi - center) / spread)
+        })
+        .unzip())
+}
+
+/// Replaces a spread of zero, e.g. of a bin with a single feature, by one.
+fn unit_if_zero(spread: f64) -> f64 {
+    if spread > 0.0 { spread } else { 1.0 }
+}
+
+/// Solves the linear system `a x = b` by Gaussian elimination with partial pivoting;
+/// `None` if it is singular.
+fn solve(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    let scale = a.iter().flatten().fold(0.0f64, |m, v| m.max(v.abs()));
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() <= scale * 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+        let (upper, lower) = a.split_at_mut(col + 1);
+        let pivot_row = &upper[col];
+        for (offset, row) in lower.iter_mut().enumerate() {
+            let factor = row[col] / pivot_row[col];
+            row.iter_mut()
+                .zip(pivot_row)
+                .skip(col)
+                .for_each(|(v, p)| *v -= factor * p);
+            b[col + 1 + offset] -= factor * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let tail: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - tail) / a[row][row];
+    }
+    Some(x)
+}
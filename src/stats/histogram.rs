@@ -0,0 +1,278 @@
+use super::quantile::{Interpolation, quantiles};
+use crate::traits::FloatOps;
+use anyhow::anyhow;
+
+/// Rule choosing the bins of a `Histogram` from the data, following NumPy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinRule {
+    /// Bins of width `2 IQR / n^(1/3)`; robust to outliers. Falls back to Sturges
+    /// when the IQR is 0 and uses at most one bin per value (or the Sturges count, if
+    /// larger), so a tiny IQR cannot produce an unbounded number of bins
+    FreedmanDiaconis,
+    /// `log2(n) + 1` bins; suited to small, roughly normal samples
+    Sturges,
+}
+
+/// Counts of values falling into consecutive bins.
+///
+/// Bins are half-open `[left, right)`, except for the last bin which also contains
+/// its right edge. Values outside of the bins and NaN values are counted separately.
+/// Histograms with the same edges can be merged, so chunks of data can be binned
+/// independently.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram<F> {
+    edges: Vec<F>,
+    counts: Vec<usize>,
+    uniform: bool,
+    underflow: usize,
+    overflow: usize,
+    nan: usize,
+}
+
+impl<F: FloatOps> Histogram<F> {
+    /// Creates an empty histogram with `bins` bins of equal width spanning `[min, max]`.
+    ///
+    /// # Returns
+    /// The histogram, or an error if `bins` is 0 or the range is not finite and increasing
+    pub fn uniform(min: F, max: F, bins: usize) -> anyhow::Result<Self> {
+        if bins == 0 {
+            return Err(anyhow!("A histogram needs at least one bin!"));
+        }
+        if !(num_traits::Float::is_finite(min) && num_traits::Float::is_finite(max) && min < max) {
+            return Err(anyhow!(
+                "Histogram range [{:?}, {:?}] must be finite and increasing!",
+                min,
+                max
+            ));
+        }
+        let width = (max - min) / F::from(bins).unwrap();
+        let mut edges: Vec<F> = (0..bins)
+            .map(|i| min + width * F::from(i).unwrap())
+            .collect();
+        edges.push(max);
+        Ok(Self::from_edges(edges, true))
+    }
+
+    /// Creates an empty histogram with explicit bin edges.
+    ///
+    /// # Returns
+    /// The histogram, or an error if there are fewer than two edges or the edges are
+    /// not finite and strictly increasing
+    pub fn with_edges(edges: Vec<F>) -> anyhow::Result<Self> {
+        if edges.len() < 2 {
+            return Err(anyhow!("A histogram needs at least two bin edges!"));
+        }
+        if edges.iter().any(|&e| !num_traits::Float::is_finite(e))
+            || edges.windows(2).any(|pair| pair[0] >= pair[1])
+        {
+            return Err(anyhow!(
+                "Histogram edges must be finite and strictly increasing!"
+            ));
+        }
+        Ok(Self::from_edges(edges, false))
+    }
+
+    /// Bins `data` into uniform bins spanning its finite range, chosen by `rule`.
+    ///
+    /// A range of width 0 is widened to `[value - 0.5, value + 0.5]`.
+    ///
+    /// # Returns
+    /// The histogram of `data`, or an error if `data` has no finite value
+    pub fn from_data(data: &[F], rule: BinRule) -> anyhow::Result<Self> {
+        let finite: Vec<F> = data
+            .iter()
+            .copied()
+            .filter(|&x| num_traits::Float::is_finite(x))
+            .collect();
+        if finite.is_empty() {
+            return Err(anyhow!(
+                "Cannot choose histogram bins without finite values!"
+            ));
+        }
+        let (mut min, mut max) = finite.iter().fold((finite[0], finite[0]), |(lo, hi), &x| {
+            (num_traits::Float::min(lo, x), num_traits::Float::max(hi, x))
+        });
+        if min == max {
+            let half = F::from(0.5).unwrap();
+            (min, max) = (min - half, max + half);
+        }
+        let n = finite.len() as f64;
+        let range = (max - min).to_f64().unwrap();
+        let sturges = (n.log2() + 1.0).ceil();
+        let bins = match rule {
+            BinRule::Sturges => sturges,
+            BinRule::FreedmanDiaconis => {
+                let quartiles = quantiles(&finite, &[0.25, 0.75], Interpolation::Linear)?;
+                let width = 2.0 * (quartiles[1] - quartiles[0]).to_f64().unwrap() / n.cbrt();
+                if width > 0.0 {
+                    (range / width).ceil().min(n.max(sturges))
+                } else {
+                    sturges
+                }
+            }
+        };
+        let mut histogram = Self::uniform(min, max, (bins as usize).max(1))?;
+        histogram.extend(data);
+        Ok(histogram)
+    }
+
+    fn from_edges(edges: Vec<F>, uniform: bool) -> Self {
+        Self {
+            counts: vec![0; edges.len() - 1],
+            edges,
+            uniform,
+            underflow: 0,
+            overflow: 0,
+            nan: 0,
+        }
+    }
+
+    /// Adds a value.
+    pub fn add(&mut self, value: F) {
+        let (first, last) = (self.edges[0], self.edges[self.edges.len() - 1]);
+        if num_traits::Float::is_nan(value) {
+            self.nan += 1;
+        } else if value < first {
+            self.underflow += 1;
+        } else if value > last {
+            self.overflow += 1;
+        } else {
+            let bin = self.bin_of(value);
+            self.counts[bin] += 1;
+        }
+    }
+
+    /// Adds every value of a slice.
+    pub fn extend(&mut self, values: &[F]) {
+        values.iter().for_each(|&value| self.add(value));
+    }
+
+    /// Combines the counts of another histogram with the same edges into this one.
+    ///
+    /// # Returns
+    /// An error if the bin edges differ
+    pub fn merge(&mut self, other: &Self) -> anyhow::Result<()> {
+        if self.edges != other.edges {
+            return Err(anyhow!("Cannot merge histograms with different bin edges!"));
+        }
+        for (count, &other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+        self.underflow += other.underflow;
+        self.overflow += other.overflow;
+        self.nan += other.nan;
+        Ok(())
+    }
+
+    /// Returns the bin index of a value within the histogram range.
+    fn bin_of(&self, value: F) -> usize {
+        let last = self.counts.len() - 1;
+        if self.uniform {
+            let (min, max) = (self.edges[0], self.edges[last + 1]);
+            let position = (value - min) / (max - min) * F::from(self.counts.len()).unwrap();
+            // rounding may put values next to an edge into the neighbouring bin
+            let mut bin = position.to_usize().unwrap_or(0).min(last);
+            if bin > 0 && value < self.edges[bin] {
+                bin -= 1;
+            } else if bin < last && value >= self.edges[bin + 1] {
+                bin += 1;
+            }
+            bin
+        } else {
+            self.edges
+                .partition_point(|&edge| edge <= value)
+                .saturating_sub(1)
+                .min(last)
+        }
+    }
+
+    /// Returns the bin edges; there is one edge more than there are bins.
+    pub fn edges(&self) -> &[F] {
+        &self.edges
+    }
+
+    /// Returns the number of values in every bin.
+    pub fn counts(&self) -> &[usize] {
+        &self.counts
+    }
+
+    /// Returns the number of values that fell into a bin.
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// Returns the number of values below the first edge.
+    pub fn underflow(&self) -> usize {
+        self.underflow
+    }
+
+    /// Returns the number of values above the last edge.
+    pub fn overflow(&self) -> usize {
+        self.overflow
+    }
+
+    /// Returns the number of NaN values added.
+    pub fn nan_count(&self) -> usize {
+        self.nan
+    }
+
+    /// Returns the probability density of every bin.
+    ///
+    /// Counts are divided by the number of binned values and the bin width, so the
+    /// densities integrate to 1 over the histogram range; all 0 without binned values.
+    pub fn densities(&self) -> Vec<F> {
+        let total = self.total();
+        if total == 0 {
+            return vec![F::zero(); self.counts.len()];
+        }
+        let total = F::from(total).unwrap();
+        self.counts
+            .iter()
+            .zip(self.edges.windows(2))
+            .map(|(&count, pair)| F::from(count).unwrap() / (total * (pair[1] - pair[0])))
+            .collect()
+    }
+
+    /// Returns the number of binned values up to and including every bin.
+    pub fn cumulative_counts(&self) -> Vec<usize> {
+        self.counts
+            .iter()
+            .scan(0usize, |acc, &count| {
+                *acc += count;
+                Some(*acc)
+            })
+            .collect()
+    }
+
+    /// Returns the fraction of binned values up to and including every bin, i.e. the
+    /// empirical distribution function at the right bin edges; all 0 without binned values.
+    pub fn cumulative_fractions(&self) -> Vec<F> {
+        let total = F::from(self.total().max(1)).unwrap();
+        self.cumulative_counts()
+            .into_iter()
+            .map(|count| F::from(count).unwrap() / total)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freedman_diaconis_bins_are_bounded() {
+        // IQR of 0: Sturges bins instead of a single one
+        let mut data = vec![1.0f64; 30];
+        data.extend([0.0, 2.0]);
+        let histogram = Histogram::from_data(&data, BinRule::FreedmanDiaconis).unwrap();
+        assert_eq!(histogram.counts().len(), 6);
+        assert_eq!(histogram.total(), 32);
+
+        // a tiny IQR next to an outlier: at most one bin per value
+        let mut data: Vec<f64> = (0..100).map(|i| 1.0 + i as f64 * 1e-12).collect();
+        data.push(1e6);
+        let histogram = Histogram::from_data(&data, BinRule::FreedmanDiaconis).unwrap();
+        assert_eq!(histogram.counts().len(), 101);
+        assert_eq!(histogram.total(), 101);
+    }
+}
@@ -0,0 +1,186 @@
+//! Descriptive statistics over slices and matrices.
+//!
+//! Matrix variants take a `Direction` selecting the lanes a statistic is computed
+//! over: `Direction::ROW` yields one value per row and `Direction::COLUMN` one value
+//! per column. They accept any `MatrixLike` input; implicit zeros of sparse inputs
+//! are part of every lane.
+
+mod bootstrap;
+#[cfg(feature = "ndarray")]
+mod classification;
+mod cluster;
+mod contingency;
+mod correlation;
+mod descriptive;
+mod dispersion;
+mod histogram;
+mod moments;
+mod multitest;
+mod nonparametric;
+mod quantile;
+mod rank;
+mod robust;
+mod rolling;
+pub mod special;
+mod ttest;
+
+pub use bootstrap::{BootstrapInterval, BootstrapMethod, bootstrap_ci};
+#[cfg(feature = "ndarray")]
+pub use classification::{ClassMetrics, ConfusionMatrix, confusion_matrix};
+pub use cluster::{
+    NmiNormalization, adjusted_rand_index, nmi, silhouette_samples, silhouette_score,
+};
+#[cfg(feature = "ndarray")]
+pub use contingency::{ChiSquareResult, chi_square_test};
+pub use contingency::{FisherExactResult, fisher_exact, hypergeometric_sf};
+#[cfg(feature = "ndarray")]
+pub use correlation::{correlation_matrix, spearman_matrix};
+pub use correlation::{pearson, spearman};
+pub use descriptive::{
+    geometric_mean, mean, mean_axis, mean_var_axis, sparse_mean_var, std, std_axis, sum, sum_axis,
+    variance, variance_axis,
+};
+pub use dispersion::{DispersionFit, DispersionTrend, fit_dispersion_trend};
+pub use histogram::{BinRule, Histogram};
+pub use moments::{Moments, RunningMoments, running_moments_axis};
+pub use multitest::{AdjustmentMethod, adjust_pvalues, adjust_pvalues_indexed};
+pub use nonparametric::{
+    EXACT_MAX_SIZE, PValueMethod, RankTestResult, ZeroPolicy, kruskal_wallis, kruskal_wallis_axis,
+    ks_two_sample, ks_two_sample_axis, mann_whitney_u, mann_whitney_u_axis, wilcoxon_signed_rank,
+};
+pub use quantile::{Interpolation, quantile, quantile_axis, quantiles, quantiles_axis};
+pub use rank::{rank, rank_axis};
+pub use robust::{MAD_NORMAL_SCALE, mad, mad_axis, median, median_axis};
+pub use rolling::{WindowEdge, rolling_mean, rolling_median, rolling_sum};
+pub use ttest::{TTestResult, welch_t_test, welch_t_test_axis};
+
+pub(crate) use descriptive::mean_log;
+pub(crate) use quantile::quantiles_in_place;
+#[cfg(feature = "ndarray")]
+pub(crate) use robust::mad_in_place;
+pub(crate) use robust::median_in_place;
+
+use crate::traits::{FloatOpsTS, MatrixLike};
+use crate::types::{BatchIdentifier, Direction};
+use crate::utils::parallel::{map_indices, map_vec};
+use anyhow::anyhow;
+
+/// Returns the number of lanes and the length of every lane of a matrix.
+pub(crate) fn lane_shape<T: Copy, M: MatrixLike<T>>(
+    matrix: &M,
+    direction: &Direction,
+) -> (usize, usize) {
+    if direction.is_row() {
+        (matrix.nrows(), matrix.ncols())
+    } else {
+        (matrix.ncols(), matrix.nrows())
+    }
+}
+
+/// Maps the dense values of every lane of a matrix through `f`, preserving order.
+///
+/// The values passed to `f` include the implicit zeros of sparse inputs and may be
+/// reordered by it.
+pub(crate) fn map_lanes<T, M, R, F>(matrix: &M, direction: &Direction, f: F) -> Vec<R>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+    R: Send,
+    F: Fn(&mut [T]) -> R + Sync + Send,
+{
+    let (num_lanes, len) = lane_shape(matrix, direction);
+    if direction.is_row() {
+        map_indices(num_lanes, |row| {
+            let mut values = vec![T::zero(); len];
+            matrix.row_into(row, &mut values);
+            f(&mut values)
+        })
+    } else {
+        let mut lanes: Vec<Vec<T>> = vec![Vec::new(); num_lanes];
+        for row in 0..len {
+            matrix.for_each_in_row(row, |col, val| lanes[col].push(val));
+        }
+        map_vec(lanes, |mut values| {
+            values.resize(len, T::zero());
+            f(&mut values)
+        })
+    }
+}
+
+/// Assigns every position within a lane to the group whose label it carries.
+///
+/// # Returns
+/// The index into `groups` of every position, `None` for positions outside of all
+/// groups, or an error if the number of labels does not match the lane length
+fn group_codes<T, M, B>(
+    matrix: &M,
+    direction: &Direction,
+    labels: &[B],
+    groups: &[&B],
+) -> anyhow::Result<Vec<Option<usize>>>
+where
+    T: Copy,
+    M: MatrixLike<T>,
+    B: BatchIdentifier,
+{
+    let (_, len) = lane_shape(matrix, direction);
+    if labels.len() != len {
+        return Err(anyhow!(
+            "Number of labels ({}) does not match the lane length ({})!",
+            labels.len(),
+            len
+        ));
+    }
+    Ok(labels
+        .iter()
+        .map(|label| groups.iter().position(|&group| group == label))
+        .collect())
+}
+
+/// Accumulates the values of every lane of a matrix separately for every group of
+/// positions, including the implicit zeros of sparse inputs.
+///
+/// # Arguments
+/// * `codes` - The group of every position within a lane, as returned by `group_codes`
+/// * `num_groups` - The number of groups
+fn group_moments<T, M>(
+    matrix: &M,
+    direction: &Direction,
+    codes: &[Option<usize>],
+    num_groups: usize,
+) -> Vec<Vec<RunningMoments<f64>>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let mut sizes = vec![0usize; num_groups];
+    codes.iter().flatten().for_each(|&g| sizes[g] += 1);
+    let add_zeros = |groups: &mut Vec<RunningMoments<f64>>| {
+        for (moments, &size) in groups.iter_mut().zip(&sizes) {
+            moments.push_n(0.0, size - moments.count());
+        }
+    };
+
+    let (num_lanes, len) = lane_shape(matrix, direction);
+    if direction.is_row() {
+        map_indices(num_lanes, |row| {
+            let mut groups = vec![RunningMoments::new(); num_groups];
+            matrix.for_each_in_row(row, |col, val| {
+                if let Some(g) = codes[col] {
+                    groups[g].push(val.to_f64().unwrap());
+                }
+            });
+            add_zeros(&mut groups);
+            groups
+        })
+    } else {
+        let mut lanes = vec![vec![RunningMoments::new(); num_groups]; num_lanes];
+        for (row, code) in codes.iter().enumerate().take(len) {
+            if let Some(g) = *code {
+                matrix.for_each_in_row(row, |col, val| lanes[col][g].push(val.to_f64().unwrap()));
+            }
+        }
+        lanes.iter_mut().for_each(add_zeros);
+        lanes
+    }
+}
@@ -0,0 +1,202 @@
+use super::lane_shape;
+use crate::traits::{FloatOps, FloatOpsTS, MatrixLike};
+use crate::types::Direction;
+use crate::utils::parallel::map_indices;
+
+/// Online accumulator of the count, mean and sum of squared deviations of a stream
+/// of values, using Welford's algorithm.
+///
+/// Accumulators over disjoint parts of the data can be combined with `merge`, which
+/// gives the same result as pushing all values into one accumulator up to rounding.
+/// This allows exact statistics over matrices processed in chunks or in parallel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunningMoments<F> {
+    count: usize,
+    mean: F,
+    m2: F,
+    track_extrema: bool,
+    min: F,
+    max: F,
+}
+
+/// Final statistics of a `RunningMoments` accumulator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Moments<F> {
+    /// Number of values
+    pub count: usize,
+    /// Arithmetic mean; NaN without values
+    pub mean: F,
+    /// Variance with the requested delta degrees of freedom; NaN if there are not
+    /// more values than `ddof`
+    pub variance: F,
+    /// Smallest value, if extrema are tracked and there are values
+    pub min: Option<F>,
+    /// Largest value, if extrema are tracked and there are values
+    pub max: Option<F>,
+}
+
+impl<F: FloatOps> Default for RunningMoments<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: FloatOps> RunningMoments<F> {
+    /// Creates an empty accumulator that does not track the minimum and maximum.
+    pub fn new() -> Self {
+        Self {
+            count: 0,
+            mean: F::zero(),
+            m2: F::zero(),
+            track_extrema: false,
+            min: num_traits::Float::infinity(),
+            max: num_traits::Float::neg_infinity(),
+        }
+    }
+
+    /// Creates an empty accumulator that also tracks the minimum and maximum.
+    pub fn with_extrema() -> Self {
+        Self {
+            track_extrema: true,
+            ..Self::new()
+        }
+    }
+
+    /// Adds a value.
+    pub fn push(&mut self, value: F) {
+        self.count += 1;
+        let delta = value - self.mean;
+        self.mean += delta / F::from(self.count).unwrap();
+        self.m2 += delta * (value - self.mean);
+        self.update_extrema(value, value);
+    }
+
+    /// Adds `n` copies of a value at once, e.g. the implicit zeros of a sparse lane.
+    pub fn push_n(&mut self, value: F, n: usize) {
+        if n == 0 {
+            return;
+        }
+        self.merge(&Self {
+            count: n,
+            mean: value,
+            m2: F::zero(),
+            track_extrema: self.track_extrema,
+            min: value,
+            max: value,
+        });
+    }
+
+    /// Combines the values of another accumulator into this one.
+    ///
+    /// Extrema are only kept if both accumulators track them.
+    pub fn merge(&mut self, other: &Self) {
+        self.track_extrema &= other.track_extrema;
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            let track_extrema = self.track_extrema;
+            *self = other.clone();
+            self.track_extrema = track_extrema;
+            return;
+        }
+        let count = self.count + other.count;
+        let delta = other.mean - self.mean;
+        let (n_a, n_b, n) = (
+            F::from(self.count).unwrap(),
+            F::from(other.count).unwrap(),
+            F::from(count).unwrap(),
+        );
+        self.mean += delta * n_b / n;
+        self.m2 += other.m2 + delta * delta * n_a * n_b / n;
+        self.count = count;
+        self.update_extrema(other.min, other.max);
+    }
+
+    /// Returns the number of values added so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the mean of the values added so far; NaN without values.
+    pub fn mean(&self) -> F {
+        if self.count == 0 {
+            num_traits::Float::nan()
+        } else {
+            self.mean
+        }
+    }
+
+    /// Computes the final statistics of the values added so far.
+    ///
+    /// # Arguments
+    /// * `ddof` - Delta degrees of freedom subtracted from the count for the variance
+    pub fn finalize(&self, ddof: usize) -> Moments<F> {
+        let variance = if self.count > ddof {
+            self.m2 / F::from(self.count - ddof).unwrap()
+        } else {
+            num_traits::Float::nan()
+        };
+        let tracked = self.track_extrema && self.count > 0;
+        Moments {
+            count: self.count,
+            mean: self.mean(),
+            variance,
+            min: tracked.then_some(self.min),
+            max: tracked.then_some(self.max),
+        }
+    }
+
+    fn update_extrema(&mut self, min: F, max: F) {
+        if self.track_extrema {
+            self.min = num_traits::Float::min(self.min, min);
+            self.max = num_traits::Float::max(self.max, max);
+        }
+    }
+}
+
+/// Accumulates the values of every lane of a matrix.
+///
+/// The accumulators of consecutive chunks of observations can be merged lane by lane,
+/// so per-feature statistics of matrices too large for memory are computed exactly.
+/// Implicit zeros of sparse inputs are added with `push_n`.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to accumulate one lane per row or per column
+/// * `extrema` - Whether the accumulators track the minimum and maximum
+pub fn running_moments_axis<T, M>(
+    matrix: &M,
+    direction: Direction,
+    extrema: bool,
+) -> Vec<RunningMoments<T>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let empty = || {
+        if extrema {
+            RunningMoments::with_extrema()
+        } else {
+            RunningMoments::new()
+        }
+    };
+    let (num_lanes, len) = lane_shape(matrix, &direction);
+    let mut lanes = if direction.is_row() {
+        map_indices(num_lanes, |row| {
+            let mut moments = empty();
+            matrix.for_each_in_row(row, |_, val| moments.push(val));
+            moments
+        })
+    } else {
+        let mut lanes: Vec<RunningMoments<T>> = (0..num_lanes).map(|_| empty()).collect();
+        for row in 0..len {
+            matrix.for_each_in_row(row, |col, val| lanes[col].push(val));
+        }
+        lanes
+    };
+    for moments in &mut lanes {
+        moments.push_n(T::zero(), len - moments.count());
+    }
+    lanes
+}
@@ -0,0 +1,110 @@
+use anyhow::anyhow;
+
+/// Procedure used to adjust p-values for multiple testing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdjustmentMethod {
+    /// Benjamini–Hochberg false discovery rate control for independent or positively
+    /// dependent tests
+    BenjaminiHochberg,
+    /// Benjamini–Yekutieli false discovery rate control under arbitrary dependence
+    BenjaminiYekutieli,
+    /// Bonferroni family-wise error rate control
+    Bonferroni,
+    /// Holm's step-down family-wise error rate control, uniformly more powerful than
+    /// Bonferroni
+    Holm,
+}
+
+/// Adjusts p-values for multiple testing.
+///
+/// NaN p-values are not counted as tests and stay NaN. Adjusted p-values are capped
+/// at 1 and match R's `p.adjust`.
+///
+/// # Arguments
+/// * `pvalues` - The raw p-values
+/// * `method` - The adjustment procedure
+///
+/// # Returns
+/// The adjusted p-values, in the order of `pvalues`
+pub fn adjust_pvalues(pvalues: &[f64], method: AdjustmentMethod) -> Vec<f64> {
+    let num_tests = pvalues.iter().filter(|p| !p.is_nan()).count();
+    adjust(pvalues, num_tests, method)
+}
+
+/// Adjusts the p-values of a subset of tests for multiple testing.
+///
+/// Intended for sparse result sets where only some of `num_tests` tests are kept,
+/// e.g. those passing a prefilter. The tests left out are treated as having a p-value
+/// of 1, which leaves the adjusted p-values of the kept tests unchanged. NaN
+/// p-values stay NaN.
+///
+/// # Arguments
+/// * `pvalues` - The index and raw p-value of every kept test
+/// * `num_tests` - The total number of tests performed
+/// * `method` - The adjustment procedure
+///
+/// # Returns
+/// The index and adjusted p-value of every kept test, in the order of `pvalues`, or
+/// an error if `num_tests` is smaller than the number of non-NaN p-values
+pub fn adjust_pvalues_indexed(
+    pvalues: &[(usize, f64)],
+    num_tests: usize,
+    method: AdjustmentMethod,
+) -> anyhow::Result<Vec<(usize, f64)>> {
+    let values: Vec<f64> = pvalues.iter().map(|&(_, p)| p).collect();
+    let kept = values.iter().filter(|p| !p.is_nan()).count();
+    if num_tests < kept {
+        return Err(anyhow!(
+            "Number of tests ({}) is smaller than the number of p-values ({})!",
+            num_tests,
+            kept
+        ));
+    }
+    let adjusted = adjust(&values, num_tests, method);
+    Ok(pvalues
+        .iter()
+        .zip(adjusted)
+        .map(|(&(idx, _), p)| (idx, p))
+        .collect())
+}
+
+/// Adjusts `pvalues` as part of `num_tests` tests, the missing ones having a p-value of 1.
+fn adjust(pvalues: &[f64], num_tests: usize, method: AdjustmentMethod) -> Vec<f64> {
+    let m = num_tests as f64;
+    let mut order: Vec<usize> = (0..pvalues.len())
+        .filter(|&i| !pvalues[i].is_nan())
+        .collect();
+    order.sort_by(|&a, &b| pvalues[a].total_cmp(&pvalues[b]));
+
+    let mut adjusted = vec![f64::NAN; pvalues.len()];
+    match method {
+        AdjustmentMethod::Bonferroni => {
+            for &idx in &order {
+                adjusted[idx] = (pvalues[idx] * m).min(1.0);
+            }
+        }
+        AdjustmentMethod::Holm => {
+            let mut running_max = 0.0f64;
+            for (pos, &idx) in order.iter().enumerate() {
+                let factor = m - pos as f64;
+                running_max = running_max.max((pvalues[idx] * factor).min(1.0));
+                adjusted[idx] = running_max;
+            }
+        }
+        AdjustmentMethod::BenjaminiHochberg | AdjustmentMethod::BenjaminiYekutieli => {
+            let dependence = match method {
+                AdjustmentMethod::BenjaminiYekutieli => {
+                    (1..=num_tests).map(|i| 1.0 / i as f64).sum()
+                }
+                _ => 1.0,
+            };
+            let mut running_min = 1.0f64;
+            for (pos, &idx) in order.iter().enumerate().rev() {
+                let rank = (pos + 1) as f64;
+                running_min = running_min.min(pvalues[idx] * m * dependence / rank);
+                adjusted[idx] = running_min;
+            }
+        }
+    }
+    adjusted
+}
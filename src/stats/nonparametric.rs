@@ -0,0 +1,433 @@
+use super::rank::{rank, tie_term};
+use super::special::{chi_square_sf, kolmogorov_sf, normal_sf};
+use super::{group_codes, map_lanes};
+use crate::traits::{FloatOps, FloatOpsTS, MatrixLike};
+use crate::types::{BatchIdentifier, Direction, TieMethod};
+use anyhow::anyhow;
+
+/// How the p-value of a rank-based test is computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PValueMethod {
+    /// Normal approximation with tie and continuity correction
+    Asymptotic,
+    /// Exact null distribution of the statistic, ignoring ties; only feasible for
+    /// small samples
+    Exact,
+    /// Exact for samples without ties of at most `EXACT_MAX_SIZE` values, asymptotic otherwise
+    Auto,
+}
+
+/// Largest sample size for which `PValueMethod::Auto` uses the exact distribution.
+pub const EXACT_MAX_SIZE: usize = 8;
+
+/// How zero differences between paired values enter the Wilcoxon signed-rank test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZeroPolicy {
+    /// Discard zero differences before ranking
+    Wilcox,
+    /// Rank zero differences along with the others, then discard their ranks
+    Pratt,
+    /// Rank zero differences along with the others and split their ranks evenly
+    /// between the positive and negative rank sums
+    ZeroSplit,
+}
+
+/// Outcome of a rank-based test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankTestResult {
+    /// The test statistic
+    pub statistic: f64,
+    /// Two-sided p-value
+    pub pvalue: f64,
+}
+
+impl RankTestResult {
+    fn nan() -> Self {
+        Self {
+            statistic: f64::NAN,
+            pvalue: f64::NAN,
+        }
+    }
+}
+
+/// Mann–Whitney U test of whether two independent samples come from the same
+/// distribution.
+///
+/// The statistic is the U of the first sample, i.e. the number of pairs in which
+/// the value of the first sample is larger, counting ties as one half.
+///
+/// # Arguments
+/// * `a` - The first sample
+/// * `b` - The second sample
+/// * `method` - How the p-value is computed
+///
+/// # Returns
+/// The U statistic and two-sided p-value; NaN if a sample is empty or, with the
+/// normal approximation, all values are tied
+pub fn mann_whitney_u<T: FloatOps>(a: &[T], b: &[T], method: PValueMethod) -> RankTestResult {
+    let (m, n) = (a.len(), b.len());
+    if m == 0 || n == 0 {
+        return RankTestResult::nan();
+    }
+    let combined: Vec<T> = a.iter().chain(b).copied().collect();
+    let ranks = rank(&combined, TieMethod::Average);
+    let rank_sum: f64 = ranks[..m].iter().sum();
+    let statistic = rank_sum - (m * (m + 1)) as f64 / 2.0;
+    let ties = tie_term(&combined);
+
+    let exact = match method {
+        PValueMethod::Exact => true,
+        PValueMethod::Asymptotic => false,
+        PValueMethod::Auto => ties == 0.0 && m.max(n) <= EXACT_MAX_SIZE,
+    };
+    let pvalue = if exact {
+        exact_u_pvalue(statistic, m, n)
+    } else {
+        let (mf, nf) = (m as f64, n as f64);
+        let total = mf + nf;
+        let variance = mf * nf / 12.0 * ((total + 1.0) - ties / (total * (total - 1.0)));
+        if variance > 0.0 {
+            let z = ((statistic - mf * nf / 2.0).abs() - 0.5).max(0.0) / variance.sqrt();
+            (2.0 * normal_sf(z)).min(1.0)
+        } else {
+            f64::NAN
+        }
+    };
+    RankTestResult { statistic, pvalue }
+}
+
+/// Mann–Whitney U test between two groups of positions within every lane of a matrix.
+///
+/// For a cells × genes matrix, `Direction::COLUMN` tests every gene between two
+/// groups of cells. Implicit zeros of sparse inputs are part of both groups, and
+/// positions whose label is neither `first` nor `second` are ignored.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to test every row or every column
+/// * `labels` - The group label of every position within a lane
+/// * `first` - The label of the first group
+/// * `second` - The label of the second group
+/// * `method` - How the p-values are computed
+///
+/// # Returns
+/// The result of every lane, or an error if the number of labels does not match the
+/// lane length
+pub fn mann_whitney_u_axis<T, M, B>(
+    matrix: &M,
+    direction: Direction,
+    labels: &[B],
+    first: &B,
+    second: &B,
+    method: PValueMethod,
+) -> anyhow::Result<Vec<RankTestResult>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+    B: BatchIdentifier,
+{
+    map_group_pairs(matrix, &direction, labels, first, second, |a, b| {
+        mann_whitney_u(a, b, method)
+    })
+}
+
+/// Wilcoxon signed-rank test of whether the differences between paired values are
+/// symmetric around zero, e.g. pre- and post-treatment pseudobulk profiles of the same
+/// donors.
+///
+/// The statistic is the smaller of the rank sums of the positive and negative
+/// differences. The p-value uses the normal approximation with tie correction and
+/// without continuity correction.
+///
+/// # Arguments
+/// * `paired_a` - The first value of every pair
+/// * `paired_b` - The second value of every pair
+/// * `zeros` - How zero differences are handled
+///
+/// # Returns
+/// The statistic and two-sided p-value, NaN if no differences remain or all are
+/// tied at zero, or an error if the slices differ in length
+pub fn wilcoxon_signed_rank<T: FloatOps>(
+    paired_a: &[T],
+    paired_b: &[T],
+    zeros: ZeroPolicy,
+) -> anyhow::Result<RankTestResult> {
+    if paired_a.len() != paired_b.len() {
+        return Err(anyhow!(
+            "Cannot pair slices of different lengths ({} and {})!",
+            paired_a.len(),
+            paired_b.len()
+        ));
+    }
+    let mut differences: Vec<f64> = paired_a
+        .iter()
+        .zip(paired_b)
+        .map(|(&a, &b)| (a - b).to_f64().unwrap())
+        .collect();
+    if zeros == ZeroPolicy::Wilcox {
+        differences.retain(|&d| d != 0.0);
+    }
+    if differences.is_empty() {
+        return Ok(RankTestResult::nan());
+    }
+    let magnitudes: Vec<f64> = differences.iter().map(|d| d.abs()).collect();
+    let ranks = rank(&magnitudes, TieMethod::Average);
+
+    let (mut positive, mut negative, mut zero) = (0.0, 0.0, 0.0);
+    for (&d, &r) in differences.iter().zip(&ranks) {
+        if d > 0.0 {
+            positive += r;
+        } else if d < 0.0 {
+            negative += r;
+        } else {
+            zero += r;
+        }
+    }
+    let n = differences.len() as f64;
+    let mut mean = n * (n + 1.0) / 4.0;
+    let mut variance = n * (n + 1.0) * (2.0 * n + 1.0);
+    let ties = match zeros {
+        ZeroPolicy::ZeroSplit => {
+            positive += zero / 2.0;
+            negative += zero / 2.0;
+            tie_term(&magnitudes)
+        }
+        _ => {
+            // under `Pratt` the ranks of zero differences are excluded from both sums
+            let z = differences.iter().filter(|&&d| d == 0.0).count() as f64;
+            mean -= z * (z + 1.0) / 4.0;
+            variance -= z * (z + 1.0) * (2.0 * z + 1.0);
+            let nonzero: Vec<f64> = magnitudes.iter().copied().filter(|&m| m != 0.0).collect();
+            tie_term(&nonzero)
+        }
+    };
+    variance = (variance - ties / 2.0) / 24.0;
+
+    let statistic = positive.min(negative);
+    let pvalue = if variance > 0.0 {
+        (2.0 * normal_sf((statistic - mean).abs() / variance.sqrt())).min(1.0)
+    } else {
+        f64::NAN
+    };
+    Ok(RankTestResult { statistic, pvalue })
+}
+
+/// Kruskal–Wallis H test of whether several independent samples come from the same
+/// distribution.
+///
+/// The statistic is corrected for ties and its p-value taken from the chi-square
+/// distribution with one degree of freedom less than the number of non-empty groups.
+///
+/// # Arguments
+/// * `groups` - The samples to compare
+///
+/// # Returns
+/// The H statistic and its p-value, NaN if fewer than two groups are non-empty or all
+/// values are tied
+pub fn kruskal_wallis<T: FloatOps>(groups: &[&[T]]) -> RankTestResult {
+    let groups: Vec<&[T]> = groups.iter().copied().filter(|g| !g.is_empty()).collect();
+    if groups.len() < 2 {
+        return RankTestResult::nan();
+    }
+    let combined: Vec<T> = groups.iter().flat_map(|g| g.iter().copied()).collect();
+    let ranks = rank(&combined, TieMethod::Average);
+    let n = combined.len() as f64;
+
+    let mut offset = 0;
+    let mut between = 0.0;
+    for group in &groups {
+        let rank_sum: f64 = ranks[offset..offset + group.len()].iter().sum();
+        between += rank_sum * rank_sum / group.len() as f64;
+        offset += group.len();
+    }
+    let correction = 1.0 - tie_term(&combined) / (n * n * n - n);
+    if correction <= 0.0 {
+        return RankTestResult::nan();
+    }
+    let statistic = (12.0 / (n * (n + 1.0)) * between - 3.0 * (n + 1.0)) / correction;
+    let pvalue = chi_square_sf(statistic, (groups.len() - 1) as f64);
+    RankTestResult { statistic, pvalue }
+}
+
+/// Kruskal–Wallis H test between all groups of positions within every lane of a matrix.
+///
+/// For a cells × genes matrix, `Direction::COLUMN` tests every gene across the
+/// clusters given by `labels`. Implicit zeros of sparse inputs are part of every group.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to test every row or every column
+/// * `labels` - The group label of every position within a lane
+///
+/// # Returns
+/// The result of every lane, or an error if the number of labels does not match the
+/// lane length
+pub fn kruskal_wallis_axis<T, M, B>(
+    matrix: &M,
+    direction: Direction,
+    labels: &[B],
+) -> anyhow::Result<Vec<RankTestResult>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+    B: BatchIdentifier,
+{
+    let mut groups: Vec<&B> = Vec::new();
+    for label in labels {
+        if !groups.contains(&label) {
+            groups.push(label);
+        }
+    }
+    let codes = group_codes(matrix, &direction, labels, &groups)?;
+    let num_groups = groups.len();
+    Ok(map_lanes(matrix, &direction, |lane| {
+        let mut samples = vec![Vec::new(); num_groups];
+        for (&value, code) in lane.iter().zip(&codes) {
+            if let Some(group) = code {
+                samples[*group].push(value);
+            }
+        }
+        let samples: Vec<&[T]> = samples.iter().map(Vec::as_slice).collect();
+        kruskal_wallis(&samples)
+    }))
+}
+
+/// Two-sample Kolmogorov–Smirnov test of whether two independent samples come from
+/// the same distribution, e.g. to detect shifts of a QC metric between batches.
+///
+/// The statistic `D` is the largest absolute difference between the empirical
+/// distribution functions of the samples. The p-value is the upper tail of the
+/// asymptotic Kolmogorov distribution at `sqrt(m n / (m + n)) D`, as with R's
+/// `ks.test(exact = FALSE)`.
+///
+/// # Arguments
+/// * `a` - The first sample
+/// * `b` - The second sample
+///
+/// # Returns
+/// The D statistic and two-sided p-value; NaN if a sample is empty or contains NaN
+pub fn ks_two_sample<T: FloatOps>(a: &[T], b: &[T]) -> RankTestResult {
+    let sorted = |data: &[T]| -> Vec<f64> {
+        let mut values: Vec<f64> = data.iter().map(|v| v.to_f64().unwrap()).collect();
+        values.sort_by(f64::total_cmp);
+        values
+    };
+    let (a, b) = (sorted(a), sorted(b));
+    let (m, n) = (a.len(), b.len());
+    if m == 0 || n == 0 || a.iter().chain(&b).any(|v| v.is_nan()) {
+        return RankTestResult::nan();
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut statistic = 0.0f64;
+    while i < m && j < n {
+        // step past all values equal to the smaller current value in both samples
+        let value = a[i].min(b[j]);
+        while i < m && a[i] == value {
+            i += 1;
+        }
+        while j < n && b[j] == value {
+            j += 1;
+        }
+        statistic = statistic.max((i as f64 / m as f64 - j as f64 / n as f64).abs());
+    }
+    let effective_size = (m * n) as f64 / (m + n) as f64;
+    RankTestResult {
+        statistic,
+        pvalue: kolmogorov_sf(effective_size.sqrt() * statistic),
+    }
+}
+
+/// Two-sample Kolmogorov–Smirnov test between two groups of positions within every
+/// lane of a matrix.
+///
+/// For a cells × genes matrix, `Direction::COLUMN` compares the distribution of every
+/// gene between two batches of cells. Implicit zeros of sparse inputs are part of both
+/// groups, and positions whose label is neither `first` nor `second` are ignored.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to test every row or every column
+/// * `labels` - The group label of every position within a lane
+/// * `first` - The label of the first group
+/// * `second` - The label of the second group
+///
+/// # Returns
+/// The result of every lane, or an error if the number of labels does not match the
+/// lane length
+pub fn ks_two_sample_axis<T, M, B>(
+    matrix: &M,
+    direction: Direction,
+    labels: &[B],
+    first: &B,
+    second: &B,
+) -> anyhow::Result<Vec<RankTestResult>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+    B: BatchIdentifier,
+{
+    map_group_pairs(matrix, &direction, labels, first, second, |a, b| {
+        ks_two_sample(a, b)
+    })
+}
+
+/// Splits every lane of a matrix into the values labelled `first` and `second` and
+/// maps the two samples through `f`.
+fn map_group_pairs<T, M, B, F>(
+    matrix: &M,
+    direction: &Direction,
+    labels: &[B],
+    first: &B,
+    second: &B,
+    f: F,
+) -> anyhow::Result<Vec<RankTestResult>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+    B: BatchIdentifier,
+    F: Fn(&[T], &[T]) -> RankTestResult + Sync + Send,
+{
+    let codes = group_codes(matrix, direction, labels, &[first, second])?;
+    Ok(map_lanes(matrix, direction, |lane| {
+        let (mut a, mut b) = (Vec::new(), Vec::new());
+        for (&value, code) in lane.iter().zip(&codes) {
+            match code {
+                Some(0) => a.push(value),
+                Some(_) => b.push(value),
+                None => {}
+            }
+        }
+        f(&a, &b)
+    }))
+}
+
+/// Two-sided p-value of `u` under the exact null distribution of the U statistic of
+/// samples of sizes `m` and `n` without ties.
+fn exact_u_pvalue(u: f64, m: usize, n: usize) -> f64 {
+    // counts[i][k] holds the number of arrangements of i values of the first and j
+    // values of the second sample with U = k, for increasing j
+    let max_u = m * n;
+    let mut counts: Vec<Vec<f64>> = (0..=m)
+        .map(|_| {
+            let mut row = vec![0.0; max_u + 1];
+            row[0] = 1.0;
+            row
+        })
+        .collect();
+    for j in 1..=n {
+        for i in 1..=m {
+            // the largest value belongs to the first sample and exceeds all j values
+            // of the second one, or to the second sample and exceeds none
+            for k in (j..=max_u).rev() {
+                counts[i][k] += counts[i - 1][k - j];
+            }
+        }
+    }
+    let distribution = &counts[m];
+    let total: f64 = distribution.iter().sum();
+    let k = u.round() as usize;
+    let lower: f64 = distribution[..=k.min(max_u)].iter().sum();
+    let upper: f64 = distribution[k.min(max_u)..].iter().sum();
+    (2.0 * lower.min(upper) / total).min(1.0)
+}
@@ -0,0 +1,183 @@
+use super::map_lanes;
+use crate::traits::{FloatOps, FloatOpsTS, MatrixLike};
+use crate::types::Direction;
+use anyhow::anyhow;
+use std::cmp::Ordering;
+
+/// Value returned for a quantile that falls between two data points, following the
+/// conventions of NumPy's `quantile`.
+///
+/// With `n` sorted values, quantile `q` lies at the fractional position
+/// `(n - 1) * q` between the values `lower` and `higher` enclosing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Linear interpolation between `lower` and `higher`
+    Linear,
+    /// The value `lower`
+    Lower,
+    /// The value `higher`
+    Higher,
+    /// The average of `lower` and `higher`
+    Midpoint,
+    /// The value closest to the position; ties go to the value at the even position
+    Nearest,
+}
+
+/// Computes a quantile of all values.
+///
+/// Runs in linear average time by selecting the enclosing values instead of sorting.
+///
+/// # Arguments
+/// * `data` - The values
+/// * `q` - The quantile in `[0, 1]`
+/// * `interpolation` - How positions between two values are resolved
+///
+/// # Returns
+/// The quantile, NaN if `data` is empty or contains NaN, or an error if `q` lies
+/// outside `[0, 1]`
+pub fn quantile<T: FloatOps>(
+    data: &[T],
+    q: f64,
+    interpolation: Interpolation,
+) -> anyhow::Result<T> {
+    check_quantile(q)?;
+    Ok(quantile_in_place(&mut data.to_vec(), q, interpolation))
+}
+
+/// Computes several quantiles of all values, sorting them once.
+///
+/// # Returns
+/// The quantiles in the order of `qs`, all NaN if `data` is empty or contains NaN,
+/// or an error if a quantile lies outside `[0, 1]`
+pub fn quantiles<T: FloatOps>(
+    data: &[T],
+    qs: &[f64],
+    interpolation: Interpolation,
+) -> anyhow::Result<Vec<T>> {
+    qs.iter().try_for_each(|&q| check_quantile(q))?;
+    Ok(quantiles_in_place(&mut data.to_vec(), qs, interpolation))
+}
+
+/// Computes a quantile of every lane of a matrix.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to compute one value per row or per column
+/// * `q` - The quantile in `[0, 1]`
+/// * `interpolation` - How positions between two values are resolved
+pub fn quantile_axis<T, M>(
+    matrix: &M,
+    direction: Direction,
+    q: f64,
+    interpolation: Interpolation,
+) -> anyhow::Result<Vec<T>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    check_quantile(q)?;
+    Ok(map_lanes(matrix, &direction, |lane| {
+        quantile_in_place(lane, q, interpolation)
+    }))
+}
+
+/// Computes several quantiles of every lane of a matrix.
+///
+/// # Returns
+/// The quantiles of every lane in the order of `qs`, or an error if a quantile lies
+/// outside `[0, 1]`
+pub fn quantiles_axis<T, M>(
+    matrix: &M,
+    direction: Direction,
+    qs: &[f64],
+    interpolation: Interpolation,
+) -> anyhow::Result<Vec<Vec<T>>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    qs.iter().try_for_each(|&q| check_quantile(q))?;
+    Ok(map_lanes(matrix, &direction, |lane| {
+        quantiles_in_place(lane, qs, interpolation)
+    }))
+}
+
+fn check_quantile(q: f64) -> anyhow::Result<()> {
+    if (0.0..=1.0).contains(&q) {
+        Ok(())
+    } else {
+        Err(anyhow!("Quantile {} is outside of [0, 1]!", q))
+    }
+}
+
+fn cmp<T: FloatOps>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+/// Positions of the values enclosing quantile `q` of `n` sorted values and the
+/// fractional distance of the quantile from the lower one.
+fn enclosing(n: usize, q: f64) -> (usize, usize, f64) {
+    let position = (n - 1) as f64 * q;
+    let lower = position.floor() as usize;
+    (lower, position.ceil() as usize, position - lower as f64)
+}
+
+fn interpolate<T: FloatOps>(
+    (lower, higher, fraction): (usize, usize, f64),
+    values: (T, T),
+    interpolation: Interpolation,
+) -> T {
+    let (low, high) = values;
+    match interpolation {
+        Interpolation::Linear => low + (high - low) * T::from(fraction).unwrap(),
+        Interpolation::Lower => low,
+        Interpolation::Higher => high,
+        Interpolation::Midpoint => (low + high) / T::from(2).unwrap(),
+        Interpolation::Nearest => {
+            if fraction < 0.5 || (fraction == 0.5 && lower % 2 == 0) || lower == higher {
+                low
+            } else {
+                high
+            }
+        }
+    }
+}
+
+fn quantile_in_place<T: FloatOps>(values: &mut [T], q: f64, interpolation: Interpolation) -> T {
+    if values.is_empty() || values.iter().any(|v| num_traits::Float::is_nan(*v)) {
+        return num_traits::Float::nan();
+    }
+    let (lower, higher, fraction) = enclosing(values.len(), q);
+    let (_, &mut low, above) = values.select_nth_unstable_by(lower, cmp);
+    let high = if higher == lower {
+        low
+    } else {
+        above
+            .iter()
+            .copied()
+            .fold(above[0], |a, b| if b < a { b } else { a })
+    };
+    interpolate((lower, higher, fraction), (low, high), interpolation)
+}
+
+/// Quantiles of `values`, reordering them; all NaN if `values` is empty or contains NaN.
+pub(crate) fn quantiles_in_place<T: FloatOps>(
+    values: &mut [T],
+    qs: &[f64],
+    interpolation: Interpolation,
+) -> Vec<T> {
+    if values.is_empty() || values.iter().any(|v| num_traits::Float::is_nan(*v)) {
+        return vec![num_traits::Float::nan(); qs.len()];
+    }
+    values.sort_unstable_by(cmp);
+    qs.iter()
+        .map(|&q| {
+            let (lower, higher, fraction) = enclosing(values.len(), q);
+            interpolate(
+                (lower, higher, fraction),
+                (values[lower], values[higher]),
+                interpolation,
+            )
+        })
+        .collect()
+}
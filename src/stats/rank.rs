@@ -0,0 +1,77 @@
+use super::map_lanes;
+use crate::traits::{FloatOps, FloatOpsTS, MatrixLike};
+use crate::types::{Direction, TieMethod};
+
+/// Ranks all values, assigning rank 1 to the smallest value.
+///
+/// Values are ordered by their IEEE 754 total order, so NaN values are ranked above
+/// all other values; NaN values are never tied.
+///
+/// # Arguments
+/// * `data` - The values
+/// * `ties` - How ranks are assigned to equal values
+///
+/// # Returns
+/// The rank of every value, in the order of `data`
+pub fn rank<T: FloatOps>(data: &[T], ties: TieMethod) -> Vec<f64> {
+    let n = data.len();
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by(|&a, &b| {
+        let (a_val, b_val) = (data[a].to_f64().unwrap(), data[b].to_f64().unwrap());
+        a_val.total_cmp(&b_val).then(a.cmp(&b))
+    });
+
+    let mut ranks = vec![0.0f64; n];
+    let mut run_start = 0;
+    let mut distinct = 0;
+    while run_start < n {
+        let mut run_end = run_start + 1;
+        while run_end < n && data[order[run_end]] == data[order[run_start]] {
+            run_end += 1;
+        }
+        distinct += 1;
+        for (k, &pos) in order[run_start..run_end].iter().enumerate() {
+            ranks[pos] = match ties {
+                TieMethod::Average => (run_start + run_end + 1) as f64 / 2.0,
+                TieMethod::Min => (run_start + 1) as f64,
+                TieMethod::Max => run_end as f64,
+                TieMethod::Dense => distinct as f64,
+                TieMethod::First => (run_start + k + 1) as f64,
+            };
+        }
+        run_start = run_end;
+    }
+    ranks
+}
+
+/// Ranks the values of every lane of a matrix, including the implicit zeros of
+/// sparse inputs.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to rank within every row or within every column
+/// * `ties` - How ranks are assigned to equal values
+///
+/// # Returns
+/// The ranks of every lane
+pub fn rank_axis<T, M>(matrix: &M, direction: Direction, ties: TieMethod) -> Vec<Vec<f64>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    map_lanes(matrix, &direction, |lane| rank(lane, ties))
+}
+
+/// Sum of `t³ - t` over the groups of `t` tied values, the tie correction term of
+/// rank-based tests.
+pub(crate) fn tie_term<T: FloatOps>(data: &[T]) -> f64 {
+    let mut sorted: Vec<f64> = data.iter().map(|v| v.to_f64().unwrap()).collect();
+    sorted.sort_by(f64::total_cmp);
+    sorted
+        .chunk_by(|a, b| a == b)
+        .map(|run| {
+            let t = run.len() as f64;
+            t * t * t - t
+        })
+        .sum()
+}
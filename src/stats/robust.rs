@@ -0,0 +1,109 @@
+use super::map_lanes;
+use crate::traits::{FloatOps, FloatOpsTS, MatrixLike};
+use crate::types::Direction;
+use std::cmp::Ordering;
+
+/// Scale factor making the MAD a consistent estimator of the standard deviation of
+/// normally distributed data, `1 / Φ⁻¹(3/4)`.
+pub const MAD_NORMAL_SCALE: f64 = 1.482_602_218_505_602;
+
+/// Median of all values; NaN for an empty slice or if any value is NaN.
+///
+/// Runs in linear average time by selecting the middle values instead of sorting.
+pub fn median<T: FloatOps>(data: &[T]) -> T {
+    median_in_place(&mut data.to_vec())
+}
+
+/// Median absolute deviation from the median of all values; NaN for an empty slice
+/// or if any value is NaN.
+///
+/// # Arguments
+/// * `data` - The values
+/// * `normal` - Whether to multiply by `MAD_NORMAL_SCALE`, making the result
+///   comparable to the standard deviation of normally distributed data
+pub fn mad<T: FloatOps>(data: &[T], normal: bool) -> T {
+    mad_in_place(&mut data.to_vec(), normal)
+}
+
+/// Medians of every lane of a matrix; NaN for lanes of length 0 or containing NaN.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to compute one value per row or per column
+pub fn median_axis<T, M>(matrix: &M, direction: Direction) -> Vec<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    map_lanes(matrix, &direction, |lane| median_in_place(lane))
+}
+
+/// Median absolute deviations of every lane of a matrix; NaN for lanes of length 0 or
+/// containing NaN.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to compute one value per row or per column
+/// * `normal` - Whether to multiply by `MAD_NORMAL_SCALE`
+pub fn mad_axis<T, M>(matrix: &M, direction: Direction, normal: bool) -> Vec<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    map_lanes(matrix, &direction, |lane| mad_in_place(lane, normal))
+}
+
+/// Median of `values`, reordering them; NaN for an empty slice or if any value is NaN.
+pub(crate) fn median_in_place<T: FloatOps>(values: &mut [T]) -> T {
+    let n = values.len();
+    if n == 0 || values.iter().any(|v| num_traits::Float::is_nan(*v)) {
+        return num_traits::Float::nan();
+    }
+    let cmp = |a: &T, b: &T| a.partial_cmp(b).unwrap_or(Ordering::Equal);
+    let mid = n / 2;
+    let (lower, upper, _) = values.select_nth_unstable_by(mid, cmp);
+    let upper = *upper;
+    if n % 2 == 1 {
+        return upper;
+    }
+    let lower = lower[1..]
+        .iter()
+        .copied()
+        .fold(lower[0], |a, b| if b > a { b } else { a });
+    (lower + upper) / T::from(2).unwrap()
+}
+
+/// Median absolute deviation of `values`, overwriting them.
+pub(crate) fn mad_in_place<T: FloatOps>(values: &mut [T], normal: bool) -> T {
+    let center = median_in_place(values);
+    values
+        .iter_mut()
+        .for_each(|v| *v = num_traits::Float::abs(*v - center));
+    let mad = median_in_place(values);
+    if normal {
+        mad * T::from(MAD_NORMAL_SCALE).unwrap()
+    } else {
+        mad
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::{Interpolation, quantile};
+
+    #[test]
+    fn nan_propagates_like_in_quantile() {
+        for data in [[f64::NAN, 1.0, 2.0, 3.0], [1.0, 2.0, 3.0, f64::NAN]] {
+            assert!(median(&data).is_nan());
+            assert!(mad(&data, false).is_nan());
+            assert!(
+                quantile(&data, 0.5, Interpolation::Linear)
+                    .unwrap()
+                    .is_nan()
+            );
+        }
+        assert_eq!(median(&[3.0, 1.0, 2.0, 10.0]), 2.5);
+        assert_eq!(mad(&[3.0, 1.0, 2.0, 10.0], false), 1.0);
+    }
+}
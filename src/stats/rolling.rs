@@ -0,0 +1,236 @@
+use crate::traits::FloatOps;
+use anyhow::anyhow;
+
+/// How windows reaching past the ends of the data are handled by the rolling
+/// statistics.
+///
+/// Windows are centred on their position, following pandas' `center=True`: the
+/// window of size `w` at position `i` covers `i - w / 2 ..= i + (w - 1) / 2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowEdge {
+    /// Only windows lying fully within the data; the `i`-th of the `n - w + 1` values
+    /// summarizes `data[i..i + w]`
+    Valid,
+    /// One value per position, with windows truncated at the ends of the data
+    Shrink,
+    /// One value per position, NaN where the window does not fit into the data
+    Nan,
+}
+
+/// Sums of a sliding window over the values.
+///
+/// Runs in linear time with compensated summation; a window containing NaN, or both
+/// positive and negative infinity, sums to NaN.
+///
+/// # Arguments
+/// * `data` - The values, e.g. per-position coverage or cells ordered by pseudotime
+/// * `window` - The number of values per window
+/// * `edge` - How windows reaching past the ends of the data are handled
+///
+/// # Returns
+/// The sum of every window, or an error if `window` is zero
+pub fn rolling_sum<T: FloatOps>(
+    data: &[T],
+    window: usize,
+    edge: WindowEdge,
+) -> anyhow::Result<Vec<T>> {
+    roll(data, window, edge, WindowSum::default(), |sum| sum.value())
+}
+
+/// Means of a sliding window over the values, see `rolling_sum`.
+///
+/// # Returns
+/// The mean of every window, or an error if `window` is zero
+pub fn rolling_mean<T: FloatOps>(
+    data: &[T],
+    window: usize,
+    edge: WindowEdge,
+) -> anyhow::Result<Vec<T>> {
+    roll(data, window, edge, WindowSum::default(), |sum| {
+        sum.value() / sum.count as f64
+    })
+}
+
+/// Medians of a sliding window over the values.
+///
+/// The window is kept sorted while sliding, so every step costs a binary search and
+/// a shift of at most `window` values. A window containing NaN has a NaN median.
+///
+/// # Arguments
+/// * `data` - The values
+/// * `window` - The number of values per window
+/// * `edge` - How windows reaching past the ends of the data are handled
+///
+/// # Returns
+/// The median of every window, or an error if `window` is zero
+pub fn rolling_median<T: FloatOps>(
+    data: &[T],
+    window: usize,
+    edge: WindowEdge,
+) -> anyhow::Result<Vec<T>> {
+    roll(data, window, edge, WindowMedian::default(), |median| {
+        median.value()
+    })
+}
+
+/// Slides `acc` over the windows selected by `window` and `edge`, evaluating `value`
+/// on every window.
+fn roll<T, W, F>(
+    data: &[T],
+    window: usize,
+    edge: WindowEdge,
+    mut acc: W,
+    value: F,
+) -> anyhow::Result<Vec<T>>
+where
+    T: FloatOps,
+    W: Window,
+    F: Fn(&W) -> f64,
+{
+    if window == 0 {
+        return Err(anyhow!("Window size must be positive!"));
+    }
+    let n = data.len();
+    let (before, after) = (window / 2, (window - 1) / 2);
+    let bounds: Box<dyn Iterator<Item = Option<(usize, usize)>>> = match edge {
+        WindowEdge::Valid => {
+            Box::new((0..(n + 1).saturating_sub(window)).map(|i| Some((i, i + window))))
+        }
+        WindowEdge::Shrink => {
+            Box::new((0..n).map(|i| Some((i.saturating_sub(before), (i + after + 1).min(n)))))
+        }
+        WindowEdge::Nan => Box::new(
+            (0..n).map(|i| (i >= before && i + after < n).then(|| (i - before, i + after + 1))),
+        ),
+    };
+
+    let (mut lo, mut hi) = (0, 0);
+    Ok(bounds
+        .map(|bound| {
+            let Some((start, end)) = bound else {
+                return num_traits::Float::nan();
+            };
+            // Window bounds never decrease, so every value enters and leaves once.
+            while hi < end {
+                acc.push(data[hi].to_f64().unwrap());
+                hi += 1;
+            }
+            while lo < start {
+                acc.pop(data[lo].to_f64().unwrap());
+                lo += 1;
+            }
+            T::from(value(&acc)).unwrap()
+        })
+        .collect())
+}
+
+/// State of a sliding window that values enter and leave in order.
+trait Window {
+    fn push(&mut self, x: f64);
+    fn pop(&mut self, x: f64);
+}
+
+/// Kahan-compensated running sum that keeps non-finite values aside, so they do not
+/// poison the sum once they leave the window.
+#[derive(Default)]
+struct WindowSum {
+    sum: f64,
+    compensation: f64,
+    count: usize,
+    nan: usize,
+    pos_inf: usize,
+    neg_inf: usize,
+}
+
+impl WindowSum {
+    fn add(&mut self, x: f64) {
+        let y = x - self.compensation;
+        let t = self.sum + y;
+        self.compensation = (t - self.sum) - y;
+        self.sum = t;
+    }
+
+    fn tally(&mut self, x: f64) -> Option<&mut usize> {
+        if x.is_nan() {
+            Some(&mut self.nan)
+        } else if x == f64::INFINITY {
+            Some(&mut self.pos_inf)
+        } else if x == f64::NEG_INFINITY {
+            Some(&mut self.neg_inf)
+        } else {
+            None
+        }
+    }
+
+    fn value(&self) -> f64 {
+        match (self.nan > 0, self.pos_inf > 0, self.neg_inf > 0) {
+            (true, _, _) | (_, true, true) => f64::NAN,
+            (_, true, _) => f64::INFINITY,
+            (_, _, true) => f64::NEG_INFINITY,
+            _ => self.sum,
+        }
+    }
+}
+
+impl Window for WindowSum {
+    fn push(&mut self, x: f64) {
+        self.count += 1;
+        match self.tally(x) {
+            Some(count) => *count += 1,
+            None => self.add(x),
+        }
+    }
+
+    fn pop(&mut self, x: f64) {
+        self.count -= 1;
+        match self.tally(x) {
+            Some(count) => *count -= 1,
+            None => self.add(-x),
+        }
+    }
+}
+
+/// Sorted values of a sliding window, with NaN values counted aside.
+#[derive(Default)]
+struct WindowMedian {
+    sorted: Vec<f64>,
+    nan: usize,
+}
+
+impl WindowMedian {
+    fn position(&self, x: f64) -> usize {
+        self.sorted.partition_point(|&v| v < x)
+    }
+
+    fn value(&self) -> f64 {
+        let n = self.sorted.len();
+        if self.nan > 0 || n == 0 {
+            return f64::NAN;
+        }
+        if n % 2 == 1 {
+            self.sorted[n / 2]
+        } else {
+            (self.sorted[n / 2 - 1] + self.sorted[n / 2]) / 2.0
+        }
+    }
+}
+
+impl Window for WindowMedian {
+    fn push(&mut self, x: f64) {
+        if x.is_nan() {
+            self.nan += 1;
+        } else {
+            let at = self.position(x);
+            self.sorted.insert(at, x);
+        }
+    }
+
+    fn pop(&mut self, x: f64) {
+        if x.is_nan() {
+            self.nan -= 1;
+        } else {
+            let at = self.position(x);
+            self.sorted.remove(at);
+        }
+    }
+}
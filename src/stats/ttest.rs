@@ -0,0 +1,87 @@
+use super::moments::RunningMoments;
+use super::special::student_t_two_sided;
+use super::{group_codes, group_moments};
+use crate::traits::{FloatOps, FloatOpsTS, MatrixLike};
+use crate::types::{BatchIdentifier, Direction};
+
+/// Outcome of a t-test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TTestResult {
+    /// The t statistic; positive if the first group has the larger mean
+    pub statistic: f64,
+    /// Degrees of freedom of the t distribution
+    pub df: f64,
+    /// Two-sided p-value
+    pub pvalue: f64,
+}
+
+/// Welch's unequal-variance t-test of the means of two samples.
+///
+/// Degrees of freedom follow the Welch–Satterthwaite approximation. All fields are
+/// NaN if a sample has fewer than two values or both samples are constant.
+pub fn welch_t_test<T: FloatOps>(a: &[T], b: &[T]) -> TTestResult {
+    let moments = |values: &[T]| {
+        let mut moments = RunningMoments::new();
+        values
+            .iter()
+            .for_each(|v| moments.push(v.to_f64().unwrap()));
+        moments
+    };
+    welch(&moments(a), &moments(b))
+}
+
+/// Welch's t-test between two groups of positions within every lane of a matrix.
+///
+/// For a cells × genes matrix, `Direction::COLUMN` tests every gene between two
+/// groups of cells. Implicit zeros of sparse inputs are part of both groups, and
+/// positions whose label is neither `first` nor `second` are ignored.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether to test every row or every column
+/// * `labels` - The group label of every position within a lane
+/// * `first` - The label of the first group
+/// * `second` - The label of the second group
+///
+/// # Returns
+/// The result of every lane, or an error if the number of labels does not match the
+/// lane length
+pub fn welch_t_test_axis<T, M, B>(
+    matrix: &M,
+    direction: Direction,
+    labels: &[B],
+    first: &B,
+    second: &B,
+) -> anyhow::Result<Vec<TTestResult>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+    B: BatchIdentifier,
+{
+    let codes = group_codes(matrix, &direction, labels, &[first, second])?;
+    Ok(group_moments(matrix, &direction, &codes, 2)
+        .iter()
+        .map(|groups| welch(&groups[0], &groups[1]))
+        .collect())
+}
+
+fn welch(a: &RunningMoments<f64>, b: &RunningMoments<f64>) -> TTestResult {
+    let (a, b) = (a.finalize(1), b.finalize(1));
+    let (n_a, n_b) = (a.count as f64, b.count as f64);
+    let (se_a, se_b) = (a.variance / n_a, b.variance / n_b);
+    let se = se_a + se_b;
+    let statistic = (a.mean - b.mean) / se.sqrt();
+    let df = se * se / (se_a * se_a / (n_a - 1.0) + se_b * se_b / (n_b - 1.0));
+    if !df.is_finite() {
+        return TTestResult {
+            statistic: f64::NAN,
+            df: f64::NAN,
+            pvalue: f64::NAN,
+        };
+    }
+    TTestResult {
+        statistic,
+        df,
+        pvalue: student_t_two_sided(statistic, df),
+    }
+}
@@ -138,9 +138,49 @@ impl<T: Default + Clone> ZeroVec for Vec<T> {
 /// ordering capabilities, and conversion to/from `usize`. It's designed for
 /// types that can be safely used as array or vector indices while providing
 /// mathematical operations and bounds checking.
+///
+/// Conversions go through `TryFrom`/`TryInto`, so narrower types such as `u32`
+/// qualify and halve the memory of large index buffers compared to `usize`.
 pub trait UIndex:
-    Unsigned + Zero + One + Copy + Eq + Ord + PartialOrd + From<usize> + Into<usize> + Bounded + Hash
+    Unsigned
+    + Zero
+    + One
+    + Copy
+    + Eq
+    + Ord
+    + PartialOrd
+    + TryFrom<usize>
+    + TryInto<usize>
+    + Bounded
+    + Hash
+    + Debug
 {
+    /// Converts the index to `usize`.
+    ///
+    /// # Panics
+    /// Panics if the value does not fit into `usize`
+    fn to_index(self) -> usize {
+        match self.try_into() {
+            Ok(idx) => idx,
+            Err(_) => panic!("Index {:?} does not fit into usize", self),
+        }
+    }
+
+    /// Converts a `usize` into the index type, returning `None` if it does not fit.
+    fn try_from_index(idx: usize) -> Option<Self> {
+        Self::try_from(idx).ok()
+    }
+
+    /// Converts a `usize` into the index type.
+    ///
+    /// # Panics
+    /// Panics if the value exceeds the range of the index type
+    fn from_index(idx: usize) -> Self {
+        match Self::try_from_index(idx) {
+            Some(value) => value,
+            None => panic!("Index {} exceeds the range of the index type", idx),
+        }
+    }
 }
 
 impl<
@@ -151,10 +191,11 @@ impl<
         + Eq
         + Ord
         + PartialOrd
-        + From<usize>
-        + Into<usize>
+        + TryFrom<usize>
+        + TryInto<usize>
         + Bounded
-        + Hash,
+        + Hash
+        + Debug,
 > UIndex for I
 {
 }
@@ -271,3 +312,94 @@ where
         Self::Out::from_iterator_generic(nrows, ncols, self.t().iter().cloned())
     }
 }
+
+/// A trait for two-dimensional matrices that can be traversed row by row.
+///
+/// This abstracts over dense and sparse storage so that algorithms operating
+/// along a `Direction` can accept either. Dense implementations visit every entry
+/// of a row, sparse implementations only visit explicitly stored entries; all
+/// entries that are not visited are implicitly zero.
+pub trait MatrixLike<T: Copy> {
+    /// Returns the number of rows of the matrix.
+    fn nrows(&self) -> usize;
+
+    /// Returns the number of columns of the matrix.
+    fn ncols(&self) -> usize;
+
+    /// Calls `f(column, value)` for every stored entry of the given row.
+    ///
+    /// # Arguments
+    /// * `row` - The row to traverse
+    /// * `f` - The visitor receiving the column index and the value
+    fn for_each_in_row<F: FnMut(usize, T)>(&self, row: usize, f: F);
+
+    /// Returns `true` if entries that are not visited by `for_each_in_row` are implicit zeros.
+    fn is_sparse(&self) -> bool {
+        false
+    }
+
+    /// Writes the given row into a dense buffer, filling implicit entries with zero.
+    ///
+    /// # Arguments
+    /// * `row` - The row to copy
+    /// * `buf` - The output buffer, which must have a length of `ncols()`
+    fn row_into(&self, row: usize, buf: &mut [T])
+    where
+        T: Zero,
+    {
+        if self.is_sparse() {
+            buf.fill(T::zero());
+        }
+        self.for_each_in_row(row, |col, val| buf[col] = val);
+    }
+}
+
+/// A trait for sparse matrices providing direct access to compressed rows.
+///
+/// Implementors expose the column indices and values of every row as slices,
+/// which allows kernels to avoid the per-entry callback of `MatrixLike`.
+pub trait SparseRowAccess<T: Copy>: MatrixLike<T> {
+    /// The integer type used to store column indices.
+    type Index: UIndex;
+
+    /// Returns the column indices and values stored in the given row.
+    fn row_entries(&self, row: usize) -> (&[Self::Index], &[T]);
+
+    /// Returns the number of explicitly stored entries.
+    fn nnz(&self) -> usize;
+}
+
+impl<T: Copy, M: MatrixLike<T>> MatrixLike<T> for &M {
+    fn nrows(&self) -> usize {
+        (**self).nrows()
+    }
+
+    fn ncols(&self) -> usize {
+        (**self).ncols()
+    }
+
+    fn for_each_in_row<F: FnMut(usize, T)>(&self, row: usize, f: F) {
+        (**self).for_each_in_row(row, f)
+    }
+
+    fn is_sparse(&self) -> bool {
+        (**self).is_sparse()
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl<T: Copy, S: ndarray::Data<Elem = T>> MatrixLike<T> for ndarray::ArrayBase<S, ndarray::Ix2> {
+    fn nrows(&self) -> usize {
+        self.nrows()
+    }
+
+    fn ncols(&self) -> usize {
+        self.ncols()
+    }
+
+    fn for_each_in_row<F: FnMut(usize, T)>(&self, row: usize, mut f: F) {
+        for (col, val) in self.row(row).iter().enumerate() {
+            f(col, *val);
+        }
+    }
+}
@@ -0,0 +1,152 @@
+#[cfg(feature = "ndarray")]
+use super::for_each_lane_mut;
+use crate::stats::{Interpolation, quantiles};
+use crate::traits::FloatOps;
+#[cfg(feature = "ndarray")]
+use crate::traits::FloatOpsTS;
+#[cfg(feature = "ndarray")]
+use crate::types::Direction;
+use anyhow::anyhow;
+#[cfg(feature = "ndarray")]
+use ndarray::{ArrayBase, DataMut, Ix2};
+
+/// Limits all values to `[min, max]`; NaN values are left unchanged.
+///
+/// # Arguments
+/// * `data` - The values to clip
+/// * `min` - The lower bound
+/// * `max` - The upper bound
+///
+/// # Returns
+/// An error if `min` is larger than `max` or either bound is NaN
+pub fn clip_in_place<T: FloatOps>(data: &mut [T], min: T, max: T) -> anyhow::Result<()> {
+    check_bounds(min, max)?;
+    clip_slice(data, min, max);
+    Ok(())
+}
+
+/// Limits all values of a dense matrix to `[min, max]`; NaN values are left unchanged.
+///
+/// # Arguments
+/// * `matrix` - The values to clip
+/// * `min` - The lower bound
+/// * `max` - The upper bound
+///
+/// # Returns
+/// An error if `min` is larger than `max` or either bound is NaN
+#[cfg(feature = "ndarray")]
+pub fn clip_matrix_in_place<T, S>(
+    matrix: &mut ArrayBase<S, Ix2>,
+    min: T,
+    max: T,
+) -> anyhow::Result<()>
+where
+    T: FloatOpsTS,
+    S: DataMut<Elem = T>,
+{
+    check_bounds(min, max)?;
+    for_each_lane_mut(matrix, &Direction::ROW, |_, lane| {
+        clip_slice(lane, min, max)
+    });
+    Ok(())
+}
+
+/// Limits all values to the range between two of their quantiles.
+///
+/// Values below the `lower_q` quantile are replaced by it and values above the
+/// `upper_q` quantile likewise, with quantiles interpolated linearly. Slices containing
+/// NaN are left unchanged.
+///
+/// # Arguments
+/// * `data` - The values to winsorize
+/// * `lower_q` - The quantile below which values are raised, e.g. `0.05`
+/// * `upper_q` - The quantile above which values are lowered, e.g. `0.95`
+///
+/// # Returns
+/// An error if a quantile is outside of `[0, 1]` or `lower_q` is larger than `upper_q`
+pub fn winsorize_in_place<T: FloatOps>(
+    data: &mut [T],
+    lower_q: f64,
+    upper_q: f64,
+) -> anyhow::Result<()> {
+    check_quantiles(lower_q, upper_q)?;
+    winsorize_slice(data, lower_q, upper_q);
+    Ok(())
+}
+
+/// Applies `winsorize_in_place` to every lane of a dense matrix.
+///
+/// For a cells × genes matrix, `Direction::COLUMN` caps the outlying cells of every
+/// gene separately.
+///
+/// # Arguments
+/// * `matrix` - The values to winsorize
+/// * `direction` - Whether every row or every column is winsorized
+/// * `lower_q` - The quantile below which values are raised
+/// * `upper_q` - The quantile above which values are lowered
+///
+/// # Returns
+/// An error if a quantile is outside of `[0, 1]` or `lower_q` is larger than `upper_q`
+#[cfg(feature = "ndarray")]
+pub fn winsorize_axis_in_place<T, S>(
+    matrix: &mut ArrayBase<S, Ix2>,
+    direction: Direction,
+    lower_q: f64,
+    upper_q: f64,
+) -> anyhow::Result<()>
+where
+    T: FloatOpsTS,
+    S: DataMut<Elem = T>,
+{
+    check_quantiles(lower_q, upper_q)?;
+    for_each_lane_mut(matrix, &direction, |_, lane| {
+        winsorize_slice(lane, lower_q, upper_q)
+    });
+    Ok(())
+}
+
+fn check_bounds<T: FloatOps>(min: T, max: T) -> anyhow::Result<()> {
+    if min <= max {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Lower bound {:?} must not exceed upper bound {:?}!",
+            min,
+            max
+        ))
+    }
+}
+
+fn check_quantiles(lower_q: f64, upper_q: f64) -> anyhow::Result<()> {
+    for q in [lower_q, upper_q] {
+        if !(0.0..=1.0).contains(&q) {
+            return Err(anyhow!("Quantile {} is outside of [0, 1]!", q));
+        }
+    }
+    if lower_q > upper_q {
+        return Err(anyhow!(
+            "Lower quantile {} must not exceed upper quantile {}!",
+            lower_q,
+            upper_q
+        ));
+    }
+    Ok(())
+}
+
+pub(super) fn clip_slice<T: FloatOps>(data: &mut [T], min: T, max: T) {
+    for x in data.iter_mut() {
+        if *x < min {
+            *x = min;
+        } else if *x > max {
+            *x = max;
+        }
+    }
+}
+
+fn winsorize_slice<T: FloatOps>(data: &mut [T], lower_q: f64, upper_q: f64) {
+    // the quantiles are validated by the callers
+    let bounds = quantiles(data, &[lower_q, upper_q], Interpolation::Linear).unwrap();
+    if !bounds.iter().any(|&b| num_traits::Float::is_nan(b)) {
+        clip_slice(data, bounds[0], bounds[1]);
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(feature = "ndarray")]
+use super::for_each_lane_mut;
+use crate::stats::mean_log;
+use crate::traits::FloatOps;
+#[cfg(feature = "ndarray")]
+use crate::traits::FloatOpsTS;
+#[cfg(feature = "ndarray")]
+use crate::types::Direction;
+#[cfg(feature = "ndarray")]
+use ndarray::{ArrayBase, DataMut, Ix2};
+
+/// Replaces the values with their centered log-ratio, `ln(x + pseudocount)` minus the
+/// mean of these logarithms.
+///
+/// Equivalently, every shifted value is divided by the geometric mean of all shifted
+/// values before taking the logarithm, so the transformed values sum to zero.
+///
+/// # Arguments
+/// * `data` - The composition, overwritten by its centered log-ratios
+/// * `pseudocount` - Added to every value before taking the logarithm, e.g. `1.0` for
+///   counts containing zeros
+pub fn clr_in_place<T: FloatOps>(data: &mut [T], pseudocount: T) {
+    if data.is_empty() {
+        return;
+    }
+    let center = mean_log(data, pseudocount);
+    data.iter_mut()
+        .for_each(|x| *x = (*x + pseudocount).ln() - center);
+}
+
+/// Applies `clr_in_place` to every lane of a dense matrix.
+///
+/// For a cells × proteins CITE-seq count matrix, `Direction::COLUMN` normalizes every
+/// protein across cells and `Direction::ROW` every cell across proteins.
+///
+/// # Arguments
+/// * `matrix` - The compositions, overwritten by their centered log-ratios
+/// * `direction` - Whether every row or every column is transformed
+/// * `pseudocount` - Added to every value before taking the logarithm
+#[cfg(feature = "ndarray")]
+pub fn clr_axis_in_place<T, S>(matrix: &mut ArrayBase<S, Ix2>, direction: Direction, pseudocount: T)
+where
+    T: FloatOpsTS,
+    S: DataMut<Elem = T>,
+{
+    for_each_lane_mut(matrix, &direction, |_, lane| {
+        clr_in_place(lane, pseudocount)
+    });
+}
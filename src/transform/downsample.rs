@@ -0,0 +1,116 @@
+#[cfg(feature = "ndarray")]
+use super::for_each_lane_mut;
+use crate::random::RngOps;
+use crate::traits::{FloatOps, FloatOpsTS, UIndex};
+use crate::types::CsrData;
+#[cfg(feature = "ndarray")]
+use crate::types::Direction;
+use crate::utils::parallel::map_vec;
+#[cfg(feature = "ndarray")]
+use ndarray::{ArrayBase, DataMut, Ix2};
+
+/// Downsamples the counts of every observation (row) of a dense matrix to an expected
+/// total of `target_total` by binomial thinning.
+///
+/// Every count `c` of an observation whose total exceeds `target_total` is replaced by
+/// a draw from `Binomial(c, target_total / total)`, so depth-matched profiles keep the
+/// sampling noise of a shallower sequencing run. Values of every observation are
+/// rounded to non-negative integer counts first; observations whose rounded total is
+/// at or below the target keep these rounded counts without thinning. Every
+/// observation draws from its own stream forked from `rng`, which is advanced by a
+/// single draw, so results do not depend on the number of threads.
+///
+/// # Arguments
+/// * `counts` - An observations × features count matrix, thinned in place
+/// * `target_total` - The expected total count of every observation after thinning
+/// * `rng` - The random number generator
+#[cfg(feature = "ndarray")]
+pub fn downsample_counts<T, S, R>(counts: &mut ArrayBase<S, Ix2>, target_total: u64, rng: &mut R)
+where
+    T: FloatOpsTS,
+    S: DataMut<Elem = T>,
+    R: RngOps + Sync,
+{
+    let stream = rng.next_u64();
+    let parent = rng.fork(stream);
+    for_each_lane_mut(counts, &Direction::ROW, |row, values| {
+        thin(values, target_total, &mut parent.fork(row as u64))
+    });
+}
+
+/// Downsamples the counts of every observation (row) of a CSR matrix to an expected
+/// total of `target_total` by binomial thinning.
+///
+/// Behaves like `downsample_counts` and gives the same result for a generator in the
+/// same state.
+/// The sparsity structure is kept, so entries thinned to zero remain stored as
+/// explicit zeros.
+///
+/// # Arguments
+/// * `counts` - An observations × features count matrix, thinned in place
+/// * `target_total` - The expected total count of every observation after thinning
+/// * `rng` - The random number generator
+pub fn downsample_counts_sparse<T, I, R>(counts: &mut CsrData<T, I>, target_total: u64, rng: &mut R)
+where
+    T: FloatOpsTS,
+    I: UIndex,
+    R: RngOps + Sync,
+{
+    let stream = rng.next_u64();
+    let parent = rng.fork(stream);
+    let rows: Vec<_> = counts
+        .rows_mut()
+        .into_iter()
+        .map(|(_, values)| values)
+        .enumerate()
+        .collect();
+    map_vec(rows, |(row, values)| {
+        thin(values, target_total, &mut parent.fork(row as u64))
+    });
+}
+
+/// Rounds the counts of one observation and thins them to an expected total of
+/// `target_total`.
+fn thin<T: FloatOps, R: RngOps>(values: &mut [T], target_total: u64, rng: &mut R) {
+    let counts: Vec<u64> = values
+        .iter()
+        .map(|v| v.to_f64().unwrap().round().max(0.0) as u64)
+        .collect();
+    let total: u64 = counts.iter().sum();
+    if total <= target_total {
+        for (value, count) in values.iter_mut().zip(counts) {
+            *value = T::from(count).unwrap();
+        }
+        return;
+    }
+    let p = target_total as f64 / total as f64;
+    for (value, count) in values.iter_mut().zip(counts) {
+        *value = T::from(rng.next_binomial(count, p)).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::SeededRng;
+
+    #[test]
+    fn rows_at_or_below_the_target_are_rounded() {
+        let mut counts = CsrData::<f64, usize>::new(
+            2,
+            3,
+            vec![0, 3, 6],
+            vec![0, 1, 2, 0, 1, 2],
+            vec![1.4, 2.6, -0.7, 40.2, 30.0, 29.9],
+        )
+        .unwrap();
+        downsample_counts_sparse(&mut counts, 10, &mut SeededRng::new(7));
+        assert_eq!(&counts.data()[..3], [1.0, 3.0, 0.0]);
+        assert!(
+            counts.data()[3..]
+                .iter()
+                .all(|&c| c == c.round() && c >= 0.0)
+        );
+        assert!(counts.data()[3..].iter().sum::<f64>() < 100.0);
+    }
+}
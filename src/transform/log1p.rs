@@ -0,0 +1,150 @@
+use crate::traits::{FloatOps, FloatOpsTS};
+use crate::utils::parallel::for_each_chunk_mut;
+#[cfg(feature = "ndarray")]
+use ndarray::{ArrayBase, DataMut, Ix2};
+
+/// Number of values processed per parallel task.
+const CHUNK_LEN: usize = 1 << 14;
+
+/// Replaces every value `x` by `ln(1 + x)`, the usual log transform of normalized counts.
+///
+/// Maps zero to zero, so it can be applied to the stored values of a sparse matrix,
+/// e.g. `CsrData::data_mut`, without changing its sparsity. Chunks of the values are
+/// processed in parallel when the `rayon` feature is enabled, and `f32` values are
+/// vectorized when the `simd` feature is enabled.
+///
+/// # Arguments
+/// * `data` - The values to transform
+pub fn log1p_in_place<T: FloatOpsTS + 'static>(data: &mut [T]) {
+    for_each_chunk_mut(data, CHUNK_LEN, log1p_chunk);
+}
+
+/// Replaces every value `x` by `exp(x) - 1`, the inverse of `log1p_in_place`.
+///
+/// Maps zero to zero, so it can be applied to the stored values of a sparse matrix.
+/// Parallelized and vectorized like `log1p_in_place`.
+///
+/// # Arguments
+/// * `data` - The values to transform
+pub fn expm1_in_place<T: FloatOpsTS + 'static>(data: &mut [T]) {
+    for_each_chunk_mut(data, CHUNK_LEN, expm1_chunk);
+}
+
+/// Applies `log1p_in_place` to all values of a dense matrix.
+///
+/// Matrices that are not contiguous in memory are transformed value by value.
+#[cfg(feature = "ndarray")]
+pub fn log1p_matrix_in_place<T, S>(matrix: &mut ArrayBase<S, Ix2>)
+where
+    T: FloatOpsTS + 'static,
+    S: DataMut<Elem = T>,
+{
+    match matrix.as_slice_memory_order_mut() {
+        Some(values) => log1p_in_place(values),
+        None => matrix.map_inplace(|x| *x = num_traits::Float::ln_1p(*x)),
+    }
+}
+
+/// Applies `expm1_in_place` to all values of a dense matrix.
+///
+/// Matrices that are not contiguous in memory are transformed value by value.
+#[cfg(feature = "ndarray")]
+pub fn expm1_matrix_in_place<T, S>(matrix: &mut ArrayBase<S, Ix2>)
+where
+    T: FloatOpsTS + 'static,
+    S: DataMut<Elem = T>,
+{
+    match matrix.as_slice_memory_order_mut() {
+        Some(values) => expm1_in_place(values),
+        None => matrix.map_inplace(|x| *x = num_traits::Float::exp_m1(*x)),
+    }
+}
+
+fn log1p_chunk<T: FloatOps + 'static>(values: &mut [T]) {
+    #[cfg(feature = "simd")]
+    {
+        if let Some(values) = simd::as_primitive::<T, f32>(values) {
+            return simd::log1p_f32(values);
+        }
+    }
+    values
+        .iter_mut()
+        .for_each(|x| *x = num_traits::Float::ln_1p(*x));
+}
+
+fn expm1_chunk<T: FloatOps + 'static>(values: &mut [T]) {
+    #[cfg(feature = "simd")]
+    {
+        if let Some(values) = simd::as_primitive::<T, f32>(values) {
+            return simd::expm1_f32(values);
+        }
+    }
+    values
+        .iter_mut()
+        .for_each(|x| *x = num_traits::Float::exp_m1(*x));
+}
+
+#[cfg(feature = "simd")]
+mod simd {
+    use std::any::TypeId;
+    use wide::{CmpEq, CmpGt, CmpLe, f32x8};
+
+    /// Reinterprets `values` as a slice of `P` if `T` is `P`.
+    pub(super) fn as_primitive<T: 'static, P: 'static>(values: &mut [T]) -> Option<&mut [P]> {
+        (TypeId::of::<T>() == TypeId::of::<P>()).then(|| {
+            // SAFETY: `T` and `P` are the same type.
+            unsafe { std::slice::from_raw_parts_mut(values.as_mut_ptr().cast::<P>(), values.len()) }
+        })
+    }
+
+    // Vector `ln` and `exp` lose the accuracy of `ln_1p` and `exp_m1` near zero. The
+    // kernels restore it with the compensations of Goldberg and Kahan, which divide out
+    // the rounding error of `1 + x` and `exp(x) - 1` respectively. Chunks with values
+    // outside the range where these are exact fall back to the scalar functions. Only
+    // `f32` is vectorized, as the `f64` functions of `wide` fall short of double precision.
+    macro_rules! kernels {
+        ($log1p:ident, $expm1:ident, $float:ty, $vector:ty, $lanes:expr, $exp_limit:expr) => {
+            pub(super) fn $log1p(values: &mut [$float]) {
+                let (zero, one) = (<$vector>::splat(0.0), <$vector>::splat(1.0));
+                let minus_one = <$vector>::splat(-1.0);
+                let mut chunks = values.chunks_exact_mut($lanes);
+                for chunk in &mut chunks {
+                    let x = <$vector>::new(chunk.try_into().unwrap());
+                    if !(x.cmp_gt(minus_one) & x.is_finite()).all() {
+                        chunk.iter_mut().for_each(|v| *v = v.ln_1p());
+                        continue;
+                    }
+                    let u = x + one;
+                    let d = u - one;
+                    let y = d.cmp_eq(zero).blend(x, u.ln() * (x / d));
+                    chunk.copy_from_slice(&y.to_array());
+                }
+                chunks
+                    .into_remainder()
+                    .iter_mut()
+                    .for_each(|v| *v = v.ln_1p());
+            }
+
+            pub(super) fn $expm1(values: &mut [$float]) {
+                let (one, limit) = (<$vector>::splat(1.0), <$vector>::splat($exp_limit));
+                let mut chunks = values.chunks_exact_mut($lanes);
+                for chunk in &mut chunks {
+                    let x = <$vector>::new(chunk.try_into().unwrap());
+                    if !x.abs().cmp_le(limit).all() {
+                        chunk.iter_mut().for_each(|v| *v = v.exp_m1());
+                        continue;
+                    }
+                    let u = x.exp();
+                    let y = u.cmp_eq(one).blend(x, (u - one) * (x / u.ln()));
+                    chunk.copy_from_slice(&y.to_array());
+                }
+                chunks
+                    .into_remainder()
+                    .iter_mut()
+                    .for_each(|v| *v = v.exp_m1());
+            }
+        };
+    }
+
+    kernels!(log1p_f32, expm1_f32, f32, f32x8, 8, 80.0);
+}
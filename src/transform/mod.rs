@@ -0,0 +1,88 @@
+//! Element-wise and per-lane transforms of slices and dense matrices.
+//!
+//! Matrix variants take a `Direction` selecting the lanes a transform is applied to:
+//! `Direction::ROW` transforms every row and `Direction::COLUMN` every column. They
+//! modify any mutable two-dimensional `ndarray` in place, or return a new dense
+//! matrix where they depend on all lanes at once, processing lanes in parallel when
+//! the `rayon` feature is enabled. Matrix variants require the `ndarray` feature;
+//! slice and sparse variants are always available.
+
+mod clip;
+mod clr;
+mod downsample;
+mod log1p;
+mod normalize;
+#[cfg(feature = "ndarray")]
+mod pearson;
+#[cfg(feature = "ndarray")]
+mod quantile_norm;
+#[cfg(feature = "ndarray")]
+mod scale;
+mod softmax;
+mod tfidf;
+
+pub use clip::{clip_in_place, winsorize_in_place};
+#[cfg(feature = "ndarray")]
+pub use clip::{clip_matrix_in_place, winsorize_axis_in_place};
+#[cfg(feature = "ndarray")]
+pub use clr::clr_axis_in_place;
+pub use clr::clr_in_place;
+#[cfg(feature = "ndarray")]
+pub use downsample::downsample_counts;
+pub use downsample::downsample_counts_sparse;
+pub use log1p::{expm1_in_place, log1p_in_place};
+#[cfg(feature = "ndarray")]
+pub use log1p::{expm1_matrix_in_place, log1p_matrix_in_place};
+#[cfg(feature = "ndarray")]
+pub use normalize::normalize_total;
+pub use normalize::normalize_total_sparse;
+#[cfg(feature = "ndarray")]
+pub use pearson::pearson_residuals;
+#[cfg(feature = "ndarray")]
+pub use quantile_norm::quantile_normalize;
+#[cfg(feature = "ndarray")]
+pub use scale::{
+    RobustSpread, ZeroSpread, scale_minmax, scale_minmax_in_place, scale_robust,
+    scale_robust_in_place, scale_zscore, scale_zscore_in_place,
+};
+#[cfg(feature = "ndarray")]
+pub use softmax::{log_softmax_axis_in_place, softmax_axis_in_place};
+pub use softmax::{log_softmax_in_place, softmax_in_place};
+pub use tfidf::{TfIdfLog, tfidf};
+
+#[cfg(feature = "ndarray")]
+use crate::traits::FloatOpsTS;
+#[cfg(feature = "ndarray")]
+use crate::types::Direction;
+#[cfg(feature = "ndarray")]
+use crate::utils::parallel::map_vec;
+#[cfg(feature = "ndarray")]
+use ndarray::{ArrayBase, DataMut, Ix2};
+
+/// Applies `f` to the index and values of every lane of a dense matrix in place.
+///
+/// Lanes that are not contiguous in memory are copied into a buffer and written back.
+#[cfg(feature = "ndarray")]
+fn for_each_lane_mut<T, S, F>(matrix: &mut ArrayBase<S, Ix2>, direction: &Direction, f: F)
+where
+    T: FloatOpsTS,
+    S: DataMut<Elem = T>,
+    F: Fn(usize, &mut [T]) + Sync + Send,
+{
+    let lanes: Vec<_> = if direction.is_row() {
+        matrix.rows_mut().into_iter().collect()
+    } else {
+        matrix.columns_mut().into_iter().collect()
+    };
+    let lanes: Vec<_> = lanes.into_iter().enumerate().collect();
+    map_vec(lanes, |(idx, mut lane)| match lane.as_slice_mut() {
+        Some(values) => f(idx, values),
+        None => {
+            let mut values = lane.to_vec();
+            f(idx, &mut values);
+            lane.iter_mut()
+                .zip(values)
+                .for_each(|(dst, src)| *dst = src);
+        }
+    });
+}
@@ -0,0 +1,196 @@
+#[cfg(feature = "ndarray")]
+use super::for_each_lane_mut;
+use crate::stats::{lane_shape, median_in_place};
+use crate::traits::{FloatOpsTS, MatrixLike, UIndex};
+use crate::types::{CsrData, Direction};
+use crate::utils::parallel::{map_indices, map_vec};
+use anyhow::anyhow;
+#[cfg(feature = "ndarray")]
+use ndarray::{ArrayBase, DataMut, Ix2};
+
+/// Scales the counts of every observation of a dense matrix to a common total, as
+/// scanpy's `pp.normalize_total`.
+///
+/// Every observation is divided by its size factor, its total count divided by
+/// `target_sum`. Without a target the median total of the observations with non-zero
+/// counts is used, so a typical observation keeps its scale. Observations without
+/// counts get a size factor of one and are left unchanged.
+///
+/// # Arguments
+/// * `counts` - The count matrix, normalized in place
+/// * `direction` - Whether the observations are the rows or the columns, e.g.
+///   `Direction::ROW` for a cells × genes matrix
+/// * `target_sum` - The total every observation is scaled to, e.g. `1e4`; `None` uses
+///   the median total
+/// * `exclude_highly_expressed` - A fraction such that features making up more than
+///   it of the total of any observation are left out of all totals, e.g. `0.05`; the
+///   excluded features are still scaled
+///
+/// # Returns
+/// The size factor of every observation, or an error if `target_sum` is not positive
+/// and finite or the fraction lies outside `(0, 1]`
+#[cfg(feature = "ndarray")]
+pub fn normalize_total<T, S>(
+    counts: &mut ArrayBase<S, Ix2>,
+    direction: Direction,
+    target_sum: Option<T>,
+    exclude_highly_expressed: Option<f64>,
+) -> anyhow::Result<Vec<T>>
+where
+    T: FloatOpsTS,
+    S: DataMut<Elem = T>,
+{
+    let factors = size_factors(
+        &counts.view(),
+        &direction,
+        target_sum,
+        exclude_highly_expressed,
+    )?;
+    for_each_lane_mut(counts, &direction, |lane, values| {
+        let factor = T::from(factors[lane]).unwrap();
+        values.iter_mut().for_each(|v| *v = *v / factor);
+    });
+    Ok(factors.into_iter().map(|f| T::from(f).unwrap()).collect())
+}
+
+/// Scales the counts of every observation of a CSR matrix to a common total, see
+/// `normalize_total`.
+///
+/// Only the stored values are scaled, so the sparsity structure is kept.
+///
+/// # Arguments
+/// * `counts` - The count matrix, normalized in place
+/// * `direction` - Whether the observations are the rows or the columns
+/// * `target_sum` - The total every observation is scaled to; `None` uses the median total
+/// * `exclude_highly_expressed` - A fraction such that features making up more than
+///   it of the total of any observation are left out of all totals
+///
+/// # Returns
+/// The size factor of every observation, or an error if `target_sum` is not positive
+/// and finite or the fraction lies outside `(0, 1]`
+pub fn normalize_total_sparse<T, I>(
+    counts: &mut CsrData<T, I>,
+    direction: Direction,
+    target_sum: Option<T>,
+    exclude_highly_expressed: Option<f64>,
+) -> anyhow::Result<Vec<T>>
+where
+    T: FloatOpsTS,
+    I: UIndex + Send + Sync,
+{
+    let factors = size_factors(&*counts, &direction, target_sum, exclude_highly_expressed)?;
+    let factors: Vec<T> = factors.into_iter().map(|f| T::from(f).unwrap()).collect();
+    let rows: Vec<_> = counts.rows_mut().into_iter().enumerate().collect();
+    map_vec(rows, |(row, (indices, values))| {
+        if direction.is_row() {
+            values.iter_mut().for_each(|v| *v = *v / factors[row]);
+        } else {
+            for (v, col) in values.iter_mut().zip(indices) {
+                *v = *v / factors[col.to_index()];
+            }
+        }
+    });
+    Ok(factors)
+}
+
+/// Size factors of every observation, one for observations without counts.
+fn size_factors<T, M>(
+    matrix: &M,
+    direction: &Direction,
+    target_sum: Option<T>,
+    exclude_highly_expressed: Option<f64>,
+) -> anyhow::Result<Vec<f64>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let target_sum = match target_sum.map(|t| t.to_f64().unwrap()) {
+        Some(t) if t.is_finite() && t > 0.0 => Some(t),
+        Some(t) => return Err(anyhow!("Target sum {} must be positive and finite!", t)),
+        None => None,
+    };
+    let mut totals = lane_totals(matrix, direction, None);
+    if let Some(max_fraction) = exclude_highly_expressed {
+        if !(max_fraction > 0.0 && max_fraction <= 1.0) {
+            return Err(anyhow!(
+                "Maximum fraction {} must lie in (0, 1]!",
+                max_fraction
+            ));
+        }
+        let keep = moderately_expressed(matrix, direction, &totals, max_fraction);
+        totals = lane_totals(matrix, direction, Some(&keep));
+    }
+
+    let target = target_sum.unwrap_or_else(|| {
+        let mut positive: Vec<f64> = totals.iter().copied().filter(|&t| t > 0.0).collect();
+        median_in_place(&mut positive)
+    });
+    Ok(totals
+        .into_iter()
+        .map(|total| if total > 0.0 { total / target } else { 1.0 })
+        .collect())
+}
+
+/// Total count of every observation, summing only the features flagged in `keep`.
+fn lane_totals<T, M>(matrix: &M, direction: &Direction, keep: Option<&[bool]>) -> Vec<f64>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let kept = |feature: usize| keep.is_none_or(|keep| keep[feature]);
+    let (num_lanes, _) = lane_shape(matrix, direction);
+    if direction.is_row() {
+        map_indices(num_lanes, |row| {
+            let mut total = 0.0;
+            matrix.for_each_in_row(row, |col, val| {
+                if kept(col) {
+                    total += val.to_f64().unwrap();
+                }
+            });
+            total
+        })
+    } else {
+        let mut totals = vec![0.0; num_lanes];
+        for row in (0..matrix.nrows()).filter(|&row| kept(row)) {
+            matrix.for_each_in_row(row, |col, val| totals[col] += val.to_f64().unwrap());
+        }
+        totals
+    }
+}
+
+/// Flags the features that make up at most `max_fraction` of the total of every
+/// observation.
+fn moderately_expressed<T, M>(
+    matrix: &M,
+    direction: &Direction,
+    totals: &[f64],
+    max_fraction: f64,
+) -> Vec<bool>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let (_, num_features) = lane_shape(matrix, direction);
+    let exceeds =
+        |val: T, observation: usize| val.to_f64().unwrap() > max_fraction * totals[observation];
+    if direction.is_row() {
+        let high = map_indices(matrix.nrows(), |row| {
+            let mut high = Vec::new();
+            matrix.for_each_in_row(row, |col, val| {
+                if exceeds(val, row) {
+                    high.push(col);
+                }
+            });
+            high
+        });
+        let mut keep = vec![true; num_features];
+        high.into_iter().flatten().for_each(|col| keep[col] = false);
+        keep
+    } else {
+        map_indices(num_features, |row| {
+            let mut keep = true;
+            matrix.for_each_in_row(row, |col, val| keep &= !exceeds(val, col));
+            keep
+        })
+    }
+}
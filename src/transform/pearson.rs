@@ -0,0 +1,68 @@
+use crate::stats::sum_axis;
+use crate::traits::{FloatOpsTS, MatrixLike};
+use crate::types::Direction;
+use crate::utils::parallel::{concat, map_indices};
+use anyhow::anyhow;
+use ndarray::Array2;
+
+/// Computes the analytic Pearson residuals of a count matrix under a negative
+/// binomial model, following Lause, Berens & Kobak (2021).
+///
+/// The expected count of observation `c` and feature `g` is
+/// `mu = n_c * n_g / n`, from the observation totals `n_c`, the feature totals `n_g`
+/// and the grand total `n`, and the residual is `(x - mu) / sqrt(mu + mu² / theta)`,
+/// clipped to `[-clip, clip]`. Totals are computed once; every row of the result is
+/// then computed independently, in parallel when the `rayon` feature is enabled.
+/// Features without counts get residuals of zero.
+///
+/// # Arguments
+/// * `counts` - An observations × features count matrix, dense or sparse
+/// * `theta` - The overdispersion parameter, e.g. `100.0`; infinity gives Poisson
+///   residuals
+/// * `clip` - The largest absolute residual kept; `None` uses the square root of the
+///   number of observations
+///
+/// # Returns
+/// A dense matrix of the shape of `counts` holding the residuals, or an error if
+/// `theta` is not positive or `clip` is negative
+pub fn pearson_residuals<T, M>(
+    counts: &M,
+    theta: f64,
+    clip: Option<f64>,
+) -> anyhow::Result<Array2<T>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    if theta.is_nan() || theta <= 0.0 {
+        return Err(anyhow!("Overdispersion {} must be positive!", theta));
+    }
+    let (nrows, ncols) = (counts.nrows(), counts.ncols());
+    let clip = clip.unwrap_or((nrows as f64).sqrt());
+    if clip.is_nan() || clip < 0.0 {
+        return Err(anyhow!("Clipping bound {} must not be negative!", clip));
+    }
+
+    let to_f64 =
+        |sums: Vec<T>| -> Vec<f64> { sums.into_iter().map(|s| s.to_f64().unwrap()).collect() };
+    let row_sums = to_f64(sum_axis(counts, Direction::ROW));
+    let col_sums = to_f64(sum_axis(counts, Direction::COLUMN));
+    let total: f64 = row_sums.iter().sum();
+
+    let rows = map_indices(nrows, |row| {
+        let mut values = vec![T::zero(); ncols];
+        counts.row_into(row, &mut values);
+        for (value, &col_sum) in values.iter_mut().zip(&col_sums) {
+            let mu = row_sums[row] * col_sum / total;
+            let residual = if mu > 0.0 {
+                let x = value.to_f64().unwrap();
+                ((x - mu) / (mu + mu * mu / theta).sqrt()).clamp(-clip, clip)
+            } else {
+                0.0
+            };
+            *value = T::from(residual).unwrap();
+        }
+        values
+    });
+    Ok(Array2::from_shape_vec((nrows, ncols), concat(&rows)).expect("rows have equal lengths"))
+}
@@ -0,0 +1,72 @@
+use crate::stats::{lane_shape, map_lanes};
+use crate::traits::{FloatOpsTS, MatrixLike};
+use crate::types::Direction;
+use crate::utils::parallel::{concat, map_vec};
+use ndarray::Array2;
+
+/// Quantile-normalizes the lanes of a matrix so that all share the same distribution.
+///
+/// Every lane is mapped onto the reference profile obtained by averaging the sorted
+/// values of all lanes position by position: the value of rank `r` is replaced by the
+/// `r`-th value of the profile. Tied values receive their average rank, interpolating
+/// linearly between neighbouring profile values. Implicit zeros of sparse inputs are
+/// part of every lane, and values are expected to be free of NaN.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether the rows or the columns are normalized, e.g.
+///   `Direction::COLUMN` for a genes × samples matrix
+///
+/// # Returns
+/// A dense matrix of the shape of `matrix` holding the normalized values
+pub fn quantile_normalize<T, M>(matrix: &M, direction: Direction) -> Array2<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let (num_lanes, len) = lane_shape(matrix, &direction);
+    let sorted = map_lanes(matrix, &direction, |lane| {
+        let mut order: Vec<usize> = (0..lane.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a_val, b_val) = (lane[a].to_f64().unwrap(), lane[b].to_f64().unwrap());
+            a_val.total_cmp(&b_val)
+        });
+        let values: Vec<f64> = order.iter().map(|&i| lane[i].to_f64().unwrap()).collect();
+        (order, values)
+    });
+
+    let mut profile = vec![0.0f64; len];
+    for (_, values) in &sorted {
+        profile.iter_mut().zip(values).for_each(|(p, v)| *p += v);
+    }
+    profile.iter_mut().for_each(|p| *p /= num_lanes as f64);
+
+    let lanes = map_vec(sorted, |(order, values)| {
+        let mut normalized = vec![T::zero(); len];
+        let mut run_start = 0;
+        while run_start < len {
+            let mut run_end = run_start + 1;
+            while run_end < len && values[run_end] == values[run_start] {
+                run_end += 1;
+            }
+            // the average zero-based rank of the run lies halfway between its ends
+            let position = (run_start + run_end - 1) as f64 / 2.0;
+            let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+            let fraction = position - lower as f64;
+            let value = profile[lower] + (profile[upper] - profile[lower]) * fraction;
+            for &pos in &order[run_start..run_end] {
+                normalized[pos] = T::from_f64(value).unwrap();
+            }
+            run_start = run_end;
+        }
+        normalized
+    });
+
+    let result =
+        Array2::from_shape_vec((num_lanes, len), concat(&lanes)).expect("lanes have equal lengths");
+    if direction.is_row() {
+        result
+    } else {
+        result.reversed_axes().as_standard_layout().into_owned()
+    }
+}
@@ -0,0 +1,402 @@
+use super::clip::clip_slice;
+use super::for_each_lane_mut;
+use crate::stats::{
+    Interpolation, lane_shape, mad_in_place, map_lanes, mean, mean_axis, median_in_place,
+    quantiles_in_place, std, variance_axis,
+};
+use crate::traits::{FloatOpsTS, MatrixLike};
+use crate::types::Direction;
+use crate::utils::parallel::{concat, map_indices};
+use anyhow::anyhow;
+use ndarray::{Array2, ArrayBase, DataMut, Ix2};
+
+/// How the scalers treat lanes whose spread is zero, e.g. genes that are not
+/// detected in any cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZeroSpread {
+    /// Shift the lane like any other but leave it unscaled, as if its spread were one;
+    /// standardizing then maps all values of a constant lane to zero and min–max
+    /// scaling to the lower end of the range
+    UnitScale,
+    /// Set all values of the lane to NaN
+    Nan,
+}
+
+/// Measure of spread used by `scale_robust`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RobustSpread {
+    /// Median absolute deviation multiplied by `MAD_NORMAL_SCALE`, which matches the
+    /// standard deviation of normally distributed data
+    Mad,
+    /// Interquartile range between the linearly interpolated 25% and 75% quantiles,
+    /// as in scikit-learn's `RobustScaler`
+    Iqr,
+}
+
+/// Standardizes every lane of a matrix to zero mean and unit variance, clipping the
+/// results to `[-max_value, max_value]`.
+///
+/// Equivalent to scanpy's `pp.scale`: lanes are centred by their mean and divided by
+/// their standard deviation with one delta degree of freedom. Means and deviations of
+/// sparse inputs are computed from the stored entries, and implicit zeros are mapped
+/// to the shifted value `-mean / std` of their lane without being visited. Rows of the
+/// result are computed in parallel when the `rayon` feature is enabled.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether every row or every column is standardized, e.g.
+///   `Direction::COLUMN` for the genes of a cells × genes matrix
+/// * `max_value` - The largest absolute value kept, e.g. `10.0`; `None` disables clipping
+/// * `zero_spread` - How lanes with a standard deviation of zero are treated
+///
+/// # Returns
+/// A dense matrix of the shape of `matrix` holding the standardized values, or an
+/// error if `max_value` is not positive
+pub fn scale_zscore<T, M>(
+    matrix: &M,
+    direction: Direction,
+    max_value: Option<T>,
+    zero_spread: ZeroSpread,
+) -> anyhow::Result<Array2<T>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let bounds = symmetric_bounds(max_value)?;
+    let means = mean_axis(matrix, direction.clone());
+    let variances = variance_axis(matrix, direction.clone(), 1);
+    let coefs: Vec<(T, T)> = means
+        .into_iter()
+        .zip(variances)
+        .map(|(mean, var)| lane_coefs(mean, var.sqrt(), T::one(), T::zero(), zero_spread))
+        .collect();
+    Ok(affine_lanes(matrix, &direction, &coefs, bounds))
+}
+
+/// Standardizes every lane of a dense matrix in place, see `scale_zscore`.
+///
+/// # Arguments
+/// * `matrix` - The values to standardize
+/// * `direction` - Whether every row or every column is standardized
+/// * `max_value` - The largest absolute value kept; `None` disables clipping
+/// * `zero_spread` - How lanes with a standard deviation of zero are treated
+///
+/// # Returns
+/// An error if `max_value` is not positive
+pub fn scale_zscore_in_place<T, S>(
+    matrix: &mut ArrayBase<S, Ix2>,
+    direction: Direction,
+    max_value: Option<T>,
+    zero_spread: ZeroSpread,
+) -> anyhow::Result<()>
+where
+    T: FloatOpsTS,
+    S: DataMut<Elem = T>,
+{
+    let bounds = symmetric_bounds(max_value)?;
+    affine_lanes_in_place(
+        matrix,
+        &direction,
+        |lane| lane_coefs(mean(lane), std(lane, 1), T::one(), T::zero(), zero_spread),
+        bounds,
+    );
+    Ok(())
+}
+
+/// Rescales every lane of a matrix linearly onto `range`, mapping the smallest value
+/// of the lane to its lower and the largest value to its upper end.
+///
+/// Implicit zeros of sparse inputs take part in the extremes of their lane and are
+/// mapped without being visited. NaN values are ignored when finding the extremes
+/// and stay NaN. Rows of the result are computed in parallel when the `rayon`
+/// feature is enabled.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether every row or every column is rescaled
+/// * `range` - The target interval `(lower, upper)`, e.g. `(0.0, 1.0)`
+/// * `zero_spread` - How constant lanes are treated
+///
+/// # Returns
+/// A dense matrix of the shape of `matrix` holding the rescaled values, or an error
+/// if the lower end of `range` is not below the upper end
+pub fn scale_minmax<T, M>(
+    matrix: &M,
+    direction: Direction,
+    range: (T, T),
+    zero_spread: ZeroSpread,
+) -> anyhow::Result<Array2<T>>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    check_range(range)?;
+    let coefs: Vec<(T, T)> = lane_extrema(matrix, &direction)
+        .into_iter()
+        .map(|(min, max)| lane_coefs(min, max - min, range.1 - range.0, range.0, zero_spread))
+        .collect();
+    Ok(affine_lanes(matrix, &direction, &coefs, None))
+}
+
+/// Rescales every lane of a dense matrix onto `range` in place, see `scale_minmax`.
+///
+/// # Arguments
+/// * `matrix` - The values to rescale
+/// * `direction` - Whether every row or every column is rescaled
+/// * `range` - The target interval `(lower, upper)`
+/// * `zero_spread` - How constant lanes are treated
+///
+/// # Returns
+/// An error if the lower end of `range` is not below the upper end
+pub fn scale_minmax_in_place<T, S>(
+    matrix: &mut ArrayBase<S, Ix2>,
+    direction: Direction,
+    range: (T, T),
+    zero_spread: ZeroSpread,
+) -> anyhow::Result<()>
+where
+    T: FloatOpsTS,
+    S: DataMut<Elem = T>,
+{
+    check_range(range)?;
+    affine_lanes_in_place(
+        matrix,
+        &direction,
+        |lane| {
+            let (min, max) = extrema(lane.iter().copied());
+            lane_coefs(min, max - min, range.1 - range.0, range.0, zero_spread)
+        },
+        None,
+    );
+    Ok(())
+}
+
+/// Centers every lane of a matrix by its median and divides it by a robust measure
+/// of spread, so that outlying cells or genes barely affect the scaling of the rest.
+///
+/// The median and spread of a lane are computed from a dense copy of it, including
+/// the implicit zeros of sparse inputs. Lanes containing NaN become NaN. Lanes are processed in parallel when the `rayon`
+/// feature is enabled.
+///
+/// # Arguments
+/// * `matrix` - A dense or sparse matrix implementing `MatrixLike`
+/// * `direction` - Whether every row or every column is scaled
+/// * `spread` - The measure of spread every lane is divided by
+/// * `zero_spread` - How lanes with a spread of zero are treated, e.g. genes detected
+///   in less than half of the cells when using the MAD
+///
+/// # Returns
+/// A dense matrix of the shape of `matrix` holding the scaled values
+pub fn scale_robust<T, M>(
+    matrix: &M,
+    direction: Direction,
+    spread: RobustSpread,
+    zero_spread: ZeroSpread,
+) -> Array2<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let coefs = map_lanes(matrix, &direction, |lane| {
+        let (center, spread) = robust_center_spread(lane, spread);
+        lane_coefs(center, spread, T::one(), T::zero(), zero_spread)
+    });
+    affine_lanes(matrix, &direction, &coefs, None)
+}
+
+/// Scales every lane of a dense matrix by its median and spread in place, see
+/// `scale_robust`.
+///
+/// # Arguments
+/// * `matrix` - The values to scale
+/// * `direction` - Whether every row or every column is scaled
+/// * `spread` - The measure of spread every lane is divided by
+/// * `zero_spread` - How lanes with a spread of zero are treated
+pub fn scale_robust_in_place<T, S>(
+    matrix: &mut ArrayBase<S, Ix2>,
+    direction: Direction,
+    spread: RobustSpread,
+    zero_spread: ZeroSpread,
+) where
+    T: FloatOpsTS,
+    S: DataMut<Elem = T>,
+{
+    affine_lanes_in_place(
+        matrix,
+        &direction,
+        |lane| {
+            let (center, spread) = robust_center_spread(&mut lane.to_vec(), spread);
+            lane_coefs(center, spread, T::one(), T::zero(), zero_spread)
+        },
+        None,
+    );
+}
+
+/// Median and spread of `values`, reordering or overwriting them.
+fn robust_center_spread<T: FloatOpsTS>(values: &mut [T], spread: RobustSpread) -> (T, T) {
+    if values.iter().any(|v| num_traits::Float::is_nan(*v)) {
+        return (num_traits::Float::nan(), num_traits::Float::nan());
+    }
+    match spread {
+        RobustSpread::Mad => {
+            let center = median_in_place(values);
+            (center, mad_in_place(values, true))
+        }
+        RobustSpread::Iqr => {
+            let q = quantiles_in_place(values, &[0.25, 0.5, 0.75], Interpolation::Linear);
+            (q[1], q[2] - q[0])
+        }
+    }
+}
+
+fn check_range<T: FloatOpsTS>(range: (T, T)) -> anyhow::Result<()> {
+    if range.0 < range.1 {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "Lower end {:?} of the range must be below its upper end {:?}!",
+            range.0,
+            range.1
+        ))
+    }
+}
+
+/// Smallest and largest value, ignoring NaN; `(inf, -inf)` if there is none.
+fn extrema<T: FloatOpsTS>(values: impl Iterator<Item = T>) -> (T, T) {
+    let init = (
+        num_traits::Float::infinity(),
+        num_traits::Float::neg_infinity(),
+    );
+    values.fold(init, widen)
+}
+
+/// Extends the extremes `(min, max)` to include `v`, ignoring NaN.
+fn widen<T: FloatOpsTS>((min, max): (T, T), v: T) -> (T, T) {
+    (if v < min { v } else { min }, if v > max { v } else { max })
+}
+
+/// Smallest and largest value of every lane of a matrix, including the implicit
+/// zeros of sparse inputs.
+fn lane_extrema<T, M>(matrix: &M, direction: &Direction) -> Vec<(T, T)>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let (num_lanes, len) = lane_shape(matrix, direction);
+    let with_zeros = |(min, max): (T, T), stored: usize| {
+        if stored < len {
+            widen((min, max), T::zero())
+        } else {
+            (min, max)
+        }
+    };
+
+    if direction.is_row() {
+        map_indices(num_lanes, |row| {
+            let mut lane = extrema(std::iter::empty());
+            let mut stored = 0usize;
+            matrix.for_each_in_row(row, |_, val| {
+                lane = widen(lane, val);
+                stored += 1;
+            });
+            with_zeros(lane, stored)
+        })
+    } else {
+        let mut lanes = vec![extrema(std::iter::empty()); num_lanes];
+        let mut stored = vec![0usize; num_lanes];
+        for row in 0..matrix.nrows() {
+            matrix.for_each_in_row(row, |col, val| {
+                lanes[col] = widen(lanes[col], val);
+                stored[col] += 1;
+            });
+        }
+        lanes
+            .into_iter()
+            .zip(stored)
+            .map(|(lane, stored)| with_zeros(lane, stored))
+            .collect()
+    }
+}
+
+fn symmetric_bounds<T: FloatOpsTS>(max_value: Option<T>) -> anyhow::Result<Option<(T, T)>> {
+    match max_value {
+        Some(max) if max > T::zero() => Ok(Some((-max, max))),
+        Some(max) => Err(anyhow!("Maximum value {:?} must be positive!", max)),
+        None => Ok(None),
+    }
+}
+
+/// Returns the `(factor, offset)` of the affine map `x * factor + offset` that takes
+/// `center` to `shift` and a difference of `spread` to one of `scale`.
+fn lane_coefs<T: FloatOpsTS>(
+    center: T,
+    spread: T,
+    scale: T,
+    shift: T,
+    zero_spread: ZeroSpread,
+) -> (T, T) {
+    let spread = if spread > T::zero() {
+        spread
+    } else {
+        match zero_spread {
+            ZeroSpread::UnitScale => T::one(),
+            ZeroSpread::Nan => return (num_traits::Float::nan(), num_traits::Float::nan()),
+        }
+    };
+    let factor = scale / spread;
+    (factor, shift - center * factor)
+}
+
+/// Maps every value of a matrix through the affine map `x * factor + offset` of its
+/// lane, clipping the results to `bounds` if given.
+///
+/// Implicit zeros of sparse inputs take the offset of their lane.
+fn affine_lanes<T, M>(
+    matrix: &M,
+    direction: &Direction,
+    coefs: &[(T, T)],
+    bounds: Option<(T, T)>,
+) -> Array2<T>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+{
+    let (nrows, ncols) = (matrix.nrows(), matrix.ncols());
+    let rows = map_indices(nrows, |row| {
+        let mut values: Vec<T> = if direction.is_row() {
+            vec![coefs[row].1; ncols]
+        } else {
+            coefs.iter().map(|&(_, offset)| offset).collect()
+        };
+        matrix.for_each_in_row(row, |col, val| {
+            let (factor, offset) = coefs[if direction.is_row() { row } else { col }];
+            values[col] = val * factor + offset;
+        });
+        if let Some((min, max)) = bounds {
+            clip_slice(&mut values, min, max);
+        }
+        values
+    });
+    Array2::from_shape_vec((nrows, ncols), concat(&rows)).expect("rows have equal lengths")
+}
+
+/// Maps every lane of a dense matrix in place through the affine map returned by
+/// `coefs` for it, clipping the results to `bounds` if given.
+fn affine_lanes_in_place<T, S, F>(
+    matrix: &mut ArrayBase<S, Ix2>,
+    direction: &Direction,
+    coefs: F,
+    bounds: Option<(T, T)>,
+) where
+    T: FloatOpsTS,
+    S: DataMut<Elem = T>,
+    F: Fn(&[T]) -> (T, T) + Sync + Send,
+{
+    for_each_lane_mut(matrix, direction, |_, lane| {
+        let (factor, offset) = coefs(lane);
+        lane.iter_mut().for_each(|x| *x = *x * factor + offset);
+        if let Some((min, max)) = bounds {
+            clip_slice(lane, min, max);
+        }
+    });
+}
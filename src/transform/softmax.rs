@@ -0,0 +1,88 @@
+#[cfg(feature = "ndarray")]
+use super::for_each_lane_mut;
+use crate::traits::FloatOps;
+#[cfg(feature = "ndarray")]
+use crate::traits::FloatOpsTS;
+#[cfg(feature = "ndarray")]
+use crate::types::Direction;
+#[cfg(feature = "ndarray")]
+use ndarray::{ArrayBase, DataMut, Ix2};
+
+/// Replaces the values with their softmax, `exp(x_i) / Σ exp(x_j)`.
+///
+/// The largest value is subtracted before exponentiating, so large scores do not
+/// overflow. If any value is NaN or the largest value is infinite, all values become
+/// NaN.
+///
+/// # Arguments
+/// * `data` - The scores, overwritten by probabilities summing to 1
+pub fn softmax_in_place<T: FloatOps>(data: &mut [T]) {
+    let Some(max) = stable_max(data) else {
+        return;
+    };
+    let mut total = T::zero();
+    for x in data.iter_mut() {
+        *x = (*x - max).exp();
+        total += *x;
+    }
+    data.iter_mut().for_each(|x| *x = *x / total);
+}
+
+/// Replaces the values with their log-softmax, `x_i - ln Σ exp(x_j)`.
+///
+/// Computed with the log-sum-exp trick, so it stays finite where taking the logarithm
+/// of `softmax_in_place` would underflow. If any value is NaN or the largest value is
+/// infinite, all values become NaN.
+///
+/// # Arguments
+/// * `data` - The scores, overwritten by log-probabilities
+pub fn log_softmax_in_place<T: FloatOps>(data: &mut [T]) {
+    let Some(max) = stable_max(data) else {
+        return;
+    };
+    let total = data.iter().fold(T::zero(), |acc, &x| acc + (x - max).exp());
+    let log_total = max + total.ln();
+    data.iter_mut().for_each(|x| *x -= log_total);
+}
+
+/// Applies `softmax_in_place` to every lane of a dense matrix.
+///
+/// For a cells × classes score matrix, `Direction::ROW` turns the scores of every
+/// cell into class probabilities.
+///
+/// # Arguments
+/// * `matrix` - The scores, overwritten by probabilities
+/// * `direction` - Whether every row or every column is normalized
+#[cfg(feature = "ndarray")]
+pub fn softmax_axis_in_place<T, S>(matrix: &mut ArrayBase<S, Ix2>, direction: Direction)
+where
+    T: FloatOpsTS,
+    S: DataMut<Elem = T>,
+{
+    for_each_lane_mut(matrix, &direction, |_, lane| softmax_in_place(lane));
+}
+
+/// Applies `log_softmax_in_place` to every lane of a dense matrix.
+///
+/// # Arguments
+/// * `matrix` - The scores, overwritten by log-probabilities
+/// * `direction` - Whether every row or every column is normalized
+#[cfg(feature = "ndarray")]
+pub fn log_softmax_axis_in_place<T, S>(matrix: &mut ArrayBase<S, Ix2>, direction: Direction)
+where
+    T: FloatOpsTS,
+    S: DataMut<Elem = T>,
+{
+    for_each_lane_mut(matrix, &direction, |_, lane| log_softmax_in_place(lane));
+}
+
+/// Returns the largest value, NaN if any value is NaN and `None` for an empty slice.
+fn stable_max<T: FloatOps>(data: &[T]) -> Option<T> {
+    data.iter().copied().reduce(|a, b| {
+        if b > a || num_traits::Float::is_nan(b) {
+            b
+        } else {
+            a
+        }
+    })
+}
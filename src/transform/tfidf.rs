@@ -0,0 +1,73 @@
+use crate::stats::sum_axis;
+use crate::traits::{FloatOpsTS, MatrixLike, UIndex};
+use crate::types::{CsrData, Direction};
+use crate::utils::parallel::map_vec;
+use anyhow::anyhow;
+
+/// Where `tfidf` applies logarithms, following the methods of Signac's `RunTFIDF`.
+///
+/// `tf` is the count of a feature divided by the total of its observation and `idf`
+/// the number of observations divided by the total of the feature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TfIdfLog {
+    /// `ln(1 + scale_factor * tf * idf)`, Signac's default
+    LogTfIdf,
+    /// `tf * ln(1 + idf)`, ignoring the scale factor
+    LogIdf,
+    /// `ln(1 + scale_factor * tf) * ln(1 + idf)`
+    LogTfLogIdf,
+    /// `tf * idf` without logarithms, ignoring the scale factor
+    Plain,
+}
+
+/// Applies the TF-IDF transform of latent semantic indexing to the stored values of an
+/// observations × features count matrix, as used for scATAC-seq peak matrices.
+///
+/// Term frequencies scale every observation by its total count, inverse document
+/// frequencies down-weight features that are common across observations. Zero counts
+/// stay zero, so only the stored values are transformed and the sparsity structure is
+/// kept. Totals are computed once and rows are transformed in parallel when the
+/// `rayon` feature is enabled.
+///
+/// # Arguments
+/// * `counts` - A cells × peaks count or binary matrix, transformed in place
+/// * `scale_factor` - The factor term frequencies are multiplied with before taking
+///   logarithms, e.g. `1e4`
+/// * `log` - Where logarithms are applied
+///
+/// # Returns
+/// An error if `scale_factor` is not positive and finite
+pub fn tfidf<T, I>(counts: &mut CsrData<T, I>, scale_factor: T, log: TfIdfLog) -> anyhow::Result<()>
+where
+    T: FloatOpsTS,
+    I: UIndex + Send + Sync,
+{
+    let scale = scale_factor.to_f64().unwrap();
+    if !(scale.is_finite() && scale > 0.0) {
+        return Err(anyhow!(
+            "Scale factor {} must be positive and finite!",
+            scale
+        ));
+    }
+    let num_observations = counts.nrows() as f64;
+    let idf: Vec<f64> = sum_axis(&*counts, Direction::COLUMN)
+        .into_iter()
+        .map(|total| num_observations / total.to_f64().unwrap())
+        .collect();
+
+    map_vec(counts.rows_mut(), |(indices, values)| {
+        let total: f64 = values.iter().map(|v| v.to_f64().unwrap()).sum();
+        for (value, col) in values.iter_mut().zip(indices) {
+            let tf = value.to_f64().unwrap() / total;
+            let idf = idf[col.to_index()];
+            let score = match log {
+                TfIdfLog::LogTfIdf => (scale * tf * idf).ln_1p(),
+                TfIdfLog::LogIdf => tf * idf.ln_1p(),
+                TfIdfLog::LogTfLogIdf => (scale * tf).ln_1p() * idf.ln_1p(),
+                TfIdfLog::Plain => tf * idf,
+            };
+            *value = T::from(score).unwrap();
+        }
+    });
+    Ok(())
+}
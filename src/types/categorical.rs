@@ -0,0 +1,325 @@
+use anyhow::anyhow;
+use std::collections::HashMap;
+
+/// A dictionary-encoded column of string values.
+///
+/// Each observation is stored as a `u32` code pointing into a table of unique
+/// categories. This mirrors how categorical `obs`/`var` annotations are stored
+/// and avoids keeping one heap-allocated `String` per observation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CategoricalVector {
+    codes: Vec<u32>,
+    categories: Vec<String>,
+    lookup: HashMap<String, u32>,
+}
+
+impl CategoricalVector {
+    /// Creates an empty categorical vector without any categories.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty categorical vector with a predefined category table.
+    ///
+    /// # Arguments
+    /// * `categories` - The categories in the order their codes should be assigned
+    ///
+    /// # Returns
+    /// An error if the category table contains duplicates
+    pub fn with_categories(categories: Vec<String>) -> anyhow::Result<Self> {
+        let lookup = Self::build_lookup(&categories)?;
+        Ok(Self {
+            codes: Vec::new(),
+            categories,
+            lookup,
+        })
+    }
+
+    /// Creates a categorical vector from raw codes and their category table.
+    ///
+    /// # Arguments
+    /// * `codes` - One code per observation, each indexing into `categories`
+    /// * `categories` - The unique category names
+    ///
+    /// # Returns
+    /// An error if the categories contain duplicates or a code is out of range
+    pub fn from_codes(codes: Vec<u32>, categories: Vec<String>) -> anyhow::Result<Self> {
+        let lookup = Self::build_lookup(&categories)?;
+        if let Some(pos) = codes.iter().position(|&c| c as usize >= categories.len()) {
+            return Err(anyhow!(
+                "Code {} at position {} is out of range for {} categories!",
+                codes[pos],
+                pos,
+                categories.len()
+            ));
+        }
+        Ok(Self {
+            codes,
+            categories,
+            lookup,
+        })
+    }
+
+    fn build_lookup(categories: &[String]) -> anyhow::Result<HashMap<String, u32>> {
+        let mut lookup = HashMap::with_capacity(categories.len());
+        for (idx, cat) in categories.iter().enumerate() {
+            if lookup.insert(cat.clone(), Self::to_code(idx)).is_some() {
+                return Err(anyhow!("Category '{}' is defined more than once!", cat));
+            }
+        }
+        Ok(lookup)
+    }
+
+    fn to_code(idx: usize) -> u32 {
+        u32::try_from(idx).expect("Number of categories exceeds u32::MAX")
+    }
+
+    /// Appends a value, registering it as a new category if it has not been seen before.
+    ///
+    /// # Returns
+    /// The code assigned to `value`
+    pub fn push(&mut self, value: &str) -> u32 {
+        let code = self.get_or_insert_category(value);
+        self.codes.push(code);
+        code
+    }
+
+    fn get_or_insert_category(&mut self, value: &str) -> u32 {
+        match self.lookup.get(value) {
+            Some(&code) => code,
+            None => {
+                let code = Self::to_code(self.categories.len());
+                self.categories.push(value.to_string());
+                self.lookup.insert(value.to_string(), code);
+                code
+            }
+        }
+    }
+
+    /// Returns the category of the observation at `idx`, or `None` if out of bounds.
+    pub fn get(&self, idx: usize) -> Option<&str> {
+        self.codes
+            .get(idx)
+            .map(|&c| self.categories[c as usize].as_str())
+    }
+
+    /// Returns the code of the observation at `idx`, or `None` if out of bounds.
+    pub fn get_code(&self, idx: usize) -> Option<u32> {
+        self.codes.get(idx).copied()
+    }
+
+    /// Returns the code assigned to a category name, if the category exists.
+    pub fn category_code(&self, category: &str) -> Option<u32> {
+        self.lookup.get(category).copied()
+    }
+
+    pub fn codes(&self) -> &[u32] {
+        &self.codes
+    }
+
+    pub fn categories(&self) -> &[String] {
+        &self.categories
+    }
+
+    pub fn num_categories(&self) -> usize {
+        self.categories.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.codes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.codes.is_empty()
+    }
+
+    /// Iterates over the category of every observation in order.
+    pub fn iter(&self) -> impl Iterator<Item = &str> + '_ {
+        self.codes
+            .iter()
+            .map(move |&c| self.categories[c as usize].as_str())
+    }
+
+    /// Renames categories according to `mapping`.
+    ///
+    /// Categories missing from the mapping keep their name. If several categories
+    /// are mapped onto the same name they are merged into a single category, which
+    /// keeps the position of the first category that maps onto it.
+    ///
+    /// # Arguments
+    /// * `mapping` - A map from old category names to new category names
+    pub fn recode(&mut self, mapping: &HashMap<String, String>) {
+        let mut categories: Vec<String> = Vec::with_capacity(self.categories.len());
+        let mut lookup: HashMap<String, u32> = HashMap::with_capacity(self.categories.len());
+        let remap: Vec<u32> = self
+            .categories
+            .iter()
+            .map(|cat| {
+                let new_name = mapping.get(cat).unwrap_or(cat);
+                *lookup.entry(new_name.clone()).or_insert_with(|| {
+                    categories.push(new_name.clone());
+                    Self::to_code(categories.len() - 1)
+                })
+            })
+            .collect();
+
+        for code in self.codes.iter_mut() {
+            *code = remap[*code as usize];
+        }
+        self.categories = categories;
+        self.lookup = lookup;
+    }
+
+    /// Merges several categories into a single category named `into`.
+    ///
+    /// # Arguments
+    /// * `categories` - The categories to merge
+    /// * `into` - The name of the merged category, which may be new or existing
+    ///
+    /// # Returns
+    /// An error if one of the categories to merge does not exist
+    pub fn merge_categories(&mut self, categories: &[&str], into: &str) -> anyhow::Result<()> {
+        let mut mapping = HashMap::with_capacity(categories.len());
+        for &cat in categories {
+            if !self.lookup.contains_key(cat) {
+                return Err(anyhow!("Category '{}' does not exist!", cat));
+            }
+            mapping.insert(cat.to_string(), into.to_string());
+        }
+        self.recode(&mapping);
+        Ok(())
+    }
+
+    /// Drops categories that are not referenced by any observation and
+    /// re-assigns codes so they stay contiguous.
+    pub fn remove_unused_categories(&mut self) {
+        let mut used = vec![false; self.categories.len()];
+        for &code in &self.codes {
+            used[code as usize] = true;
+        }
+        if used.iter().all(|&u| u) {
+            return;
+        }
+
+        let mut remap = vec![0u32; self.categories.len()];
+        let mut categories = Vec::with_capacity(self.categories.len());
+        for (old, cat) in std::mem::take(&mut self.categories).into_iter().enumerate() {
+            if used[old] {
+                remap[old] = Self::to_code(categories.len());
+                categories.push(cat);
+            }
+        }
+        for code in self.codes.iter_mut() {
+            *code = remap[*code as usize];
+        }
+        self.lookup = Self::build_lookup(&categories).expect("categories are unique");
+        self.categories = categories;
+    }
+
+    /// Counts the observations per category, indexed by code.
+    pub fn category_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0usize; self.categories.len()];
+        for &code in &self.codes {
+            counts[code as usize] += 1;
+        }
+        counts
+    }
+
+    /// Groups observation indices by category, indexed by code.
+    pub fn group_indices(&self) -> Vec<Vec<usize>> {
+        let mut groups: Vec<Vec<usize>> = self
+            .category_counts()
+            .into_iter()
+            .map(Vec::with_capacity)
+            .collect();
+        for (idx, &code) in self.codes.iter().enumerate() {
+            groups[code as usize].push(idx);
+        }
+        groups
+    }
+
+    /// Groups observation indices by category name.
+    ///
+    /// The keys are `&str`, which implements `BatchIdentifier`, so the result can be
+    /// used wherever batch-keyed groupings are expected. Unused categories are omitted.
+    pub fn groups(&self) -> HashMap<&str, Vec<usize>> {
+        self.group_indices()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, members)| !members.is_empty())
+            .map(|(code, members)| (self.categories[code].as_str(), members))
+            .collect()
+    }
+
+    /// Expands the vector back into one `String` per observation.
+    pub fn to_strings(&self) -> Vec<String> {
+        self.iter().map(str::to_string).collect()
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for CategoricalVector {
+    fn from_iter<T: IntoIterator<Item = S>>(iter: T) -> Self {
+        let mut out = Self::new();
+        for value in iter {
+            out.push(value.as_ref());
+        }
+        out
+    }
+}
+
+impl From<Vec<String>> for CategoricalVector {
+    fn from(values: Vec<String>) -> Self {
+        values.iter().collect()
+    }
+}
+
+impl From<&[&str]> for CategoricalVector {
+    fn from(values: &[&str]) -> Self {
+        values.iter().collect()
+    }
+}
+
+impl From<CategoricalVector> for Vec<String> {
+    fn from(values: CategoricalVector) -> Self {
+        values.to_strings()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_strings() {
+        let values = ["B", "A", "B", "C"];
+        let vector = CategoricalVector::from(&values[..]);
+        assert_eq!(vector.codes(), [0, 1, 0, 2]);
+        assert_eq!(vector.categories(), ["B", "A", "C"]);
+        assert_eq!(vector.category_code("C"), Some(2));
+        assert_eq!(Vec::<String>::from(vector), values);
+    }
+
+    #[test]
+    fn recode_merges_categories_in_order_of_first_occurrence() {
+        let mut vector = CategoricalVector::from(&["T", "NK", "B", "NK", "T"][..]);
+        vector.merge_categories(&["T", "NK"], "lymphoid").unwrap();
+        assert_eq!(vector.categories(), ["lymphoid", "B"]);
+        assert_eq!(vector.codes(), [0, 0, 1, 0, 0]);
+        assert_eq!(vector.category_counts(), [4, 1]);
+        assert_eq!(vector.groups()["B"], [2]);
+        assert!(vector.merge_categories(&["DC"], "myeloid").is_err());
+    }
+
+    #[test]
+    fn unused_categories_are_removed_and_codes_stay_contiguous() {
+        let categories = ["x", "y", "z"].map(String::from).to_vec();
+        let mut vector = CategoricalVector::from_codes(vec![2, 0, 2], categories).unwrap();
+        vector.remove_unused_categories();
+        assert_eq!(vector.categories(), ["x", "z"]);
+        assert_eq!(vector.codes(), [1, 0, 1]);
+        assert_eq!(vector.push("y"), 2);
+
+        assert!(CategoricalVector::from_codes(vec![3], vec!["x".to_string()]).is_err());
+        assert!(CategoricalVector::with_categories(vec!["x".to_string(); 2]).is_err());
+    }
+}
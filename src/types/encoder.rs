@@ -0,0 +1,156 @@
+use crate::types::BatchIdentifier;
+use std::collections::HashMap;
+
+/// Maps batch identifiers to dense integer codes.
+///
+/// Codes are assigned in order of first occurrence, so encoding the same label
+/// sequence always yields the same codes. The encoder can be used to translate
+/// between labels and group indices of aggregated results.
+#[derive(Debug, Clone)]
+pub struct BatchEncoder<B: BatchIdentifier> {
+    labels: Vec<B>,
+    lookup: HashMap<B, usize>,
+}
+
+impl<B: BatchIdentifier> Default for BatchEncoder<B> {
+    fn default() -> Self {
+        Self {
+            labels: Vec::new(),
+            lookup: HashMap::new(),
+        }
+    }
+}
+
+impl<B: BatchIdentifier> BatchEncoder<B> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an encoder from the unique labels of a slice in first-occurrence order.
+    pub fn fit(labels: &[B]) -> Self {
+        let mut encoder = Self::new();
+        for label in labels {
+            encoder.encode(label);
+        }
+        encoder
+    }
+
+    /// Fits an encoder to `labels` and returns it together with the code of every label.
+    pub fn fit_transform(labels: &[B]) -> (Self, Vec<usize>) {
+        let mut encoder = Self::new();
+        let codes = labels.iter().map(|label| encoder.encode(label)).collect();
+        (encoder, codes)
+    }
+
+    /// Returns the code of `label`, registering it if it has not been seen before.
+    pub fn encode(&mut self, label: &B) -> usize {
+        if let Some(&code) = self.lookup.get(label) {
+            return code;
+        }
+        let code = self.labels.len();
+        self.labels.push(label.clone());
+        self.lookup.insert(label.clone(), code);
+        code
+    }
+
+    /// Returns the code of `label`, or `None` if it is unknown.
+    pub fn code(&self, label: &B) -> Option<usize> {
+        self.lookup.get(label).copied()
+    }
+
+    /// Returns the label belonging to `code`, or `None` if it is out of range.
+    pub fn decode(&self, code: usize) -> Option<&B> {
+        self.labels.get(code)
+    }
+
+    /// Encodes all labels without registering new ones.
+    ///
+    /// # Returns
+    /// `None` if any label is unknown to the encoder
+    pub fn transform(&self, labels: &[B]) -> Option<Vec<usize>> {
+        labels.iter().map(|label| self.code(label)).collect()
+    }
+
+    /// Returns the known labels, indexed by code.
+    pub fn labels(&self) -> &[B] {
+        &self.labels
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
+
+/// Encodes the observed combinations of two label columns.
+///
+/// Each column is encoded separately and combinations are keyed by the pair of
+/// column codes, so no concatenated strings are allocated. Composite codes are
+/// assigned in order of first occurrence of each combination.
+#[derive(Debug, Clone)]
+pub struct CompositeEncoder<A: BatchIdentifier, B: BatchIdentifier> {
+    first: BatchEncoder<A>,
+    second: BatchEncoder<B>,
+    combinations: BatchEncoder<(usize, usize)>,
+}
+
+impl<A: BatchIdentifier, B: BatchIdentifier> Default for CompositeEncoder<A, B> {
+    fn default() -> Self {
+        Self {
+            first: BatchEncoder::new(),
+            second: BatchEncoder::new(),
+            combinations: BatchEncoder::new(),
+        }
+    }
+}
+
+impl<A: BatchIdentifier, B: BatchIdentifier> CompositeEncoder<A, B> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the composite code of `(first, second)`, registering new labels and combinations.
+    pub fn encode(&mut self, first: &A, second: &B) -> usize {
+        let key = (self.first.encode(first), self.second.encode(second));
+        self.combinations.encode(&key)
+    }
+
+    /// Returns the composite code of `(first, second)`, or `None` if the combination is unknown.
+    pub fn code(&self, first: &A, second: &B) -> Option<usize> {
+        let key = (self.first.code(first)?, self.second.code(second)?);
+        self.combinations.code(&key)
+    }
+
+    /// Returns the pair of labels belonging to a composite code.
+    pub fn decode(&self, code: usize) -> Option<(&A, &B)> {
+        let &(a, b) = self.combinations.decode(code)?;
+        Some((self.first.labels().get(a)?, self.second.labels().get(b)?))
+    }
+
+    /// Returns the pair of per-column codes belonging to a composite code.
+    pub fn decode_codes(&self, code: usize) -> Option<(usize, usize)> {
+        self.combinations.decode(code).copied()
+    }
+
+    /// Returns the encoder of the first label column.
+    pub fn first(&self) -> &BatchEncoder<A> {
+        &self.first
+    }
+
+    /// Returns the encoder of the second label column.
+    pub fn second(&self) -> &BatchEncoder<B> {
+        &self.second
+    }
+
+    /// Returns the number of observed combinations.
+    pub fn len(&self) -> usize {
+        self.combinations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combinations.is_empty()
+    }
+}
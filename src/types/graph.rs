@@ -0,0 +1,450 @@
+use crate::traits::{FloatOps, FloatOpsTS};
+use crate::types::MissingFeaturePolicy;
+use crate::types::pathway::feature_lookup;
+use crate::utils::parallel::map_indices;
+use crate::utils::{ValidationOptions, ValidationReport, validate_net};
+use anyhow::anyhow;
+use std::collections::HashMap;
+
+/// Direction of the edges considered by neighbor and degree queries on a `Graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeDirection {
+    /// Edges leaving the node
+    Outgoing,
+    /// Edges entering the node
+    Incoming,
+}
+
+/// Degree distribution of the nodes of a `Graph` in one edge direction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DegreeStats {
+    /// Smallest degree
+    pub min: usize,
+    /// Mean degree
+    pub mean: f64,
+    /// Largest degree
+    pub max: usize,
+    /// Number of nodes with degree 0
+    pub zero_degree: usize,
+}
+
+/// A directed weighted graph over a feature space, e.g. a gene regulatory network.
+///
+/// Nodes are the feature indices `0..num_nodes` and can carry an attribute of type
+/// `N`. Edges are stored as adjacency lists in both directions, so successors and
+/// predecessors of a node are available without scanning the graph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Graph<N = (), W = f32> {
+    outgoing: Vec<Vec<(usize, W)>>, // successors and edge weights per node
+    incoming: Vec<Vec<(usize, W)>>, // predecessors and edge weights per node
+    attributes: Vec<Option<N>>,     // attribute per node, if set
+    node_names: Option<Vec<String>>, // names of the nodes, if known
+    name_index: Option<HashMap<String, usize>>, // node index by name
+}
+
+impl<N, W: FloatOps> Graph<N, W> {
+    /// Creates a graph with `num_nodes` nodes and no edges.
+    pub fn new(num_nodes: usize) -> Self {
+        Self {
+            outgoing: (0..num_nodes).map(|_| Vec::new()).collect(),
+            incoming: (0..num_nodes).map(|_| Vec::new()).collect(),
+            attributes: (0..num_nodes).map(|_| None).collect(),
+            node_names: None,
+            name_index: None,
+        }
+    }
+
+    /// Adds a directed edge from `source` to `target`.
+    ///
+    /// Parallel edges are not merged.
+    ///
+    /// # Panics
+    /// Panics if either node is not part of the graph.
+    pub fn add_edge(&mut self, source: usize, target: usize, weight: W) {
+        assert!(
+            source < self.num_nodes() && target < self.num_nodes(),
+            "edge {} -> {} lies outside the graph of {} nodes",
+            source,
+            target,
+            self.num_nodes()
+        );
+        self.outgoing[source].push((target, weight));
+        self.incoming[target].push((source, weight));
+    }
+
+    /// Returns the number of nodes.
+    pub fn num_nodes(&self) -> usize {
+        self.outgoing.len()
+    }
+
+    /// Returns the number of edges.
+    pub fn num_edges(&self) -> usize {
+        self.outgoing.iter().map(Vec::len).sum()
+    }
+
+    /// Returns the neighbors of `node` in the given direction along with the edge weights.
+    ///
+    /// # Panics
+    /// Panics if `node` is not part of the graph.
+    pub fn neighbors(&self, node: usize, direction: EdgeDirection) -> &[(usize, W)] {
+        match direction {
+            EdgeDirection::Outgoing => &self.outgoing[node],
+            EdgeDirection::Incoming => &self.incoming[node],
+        }
+    }
+
+    /// Returns the targets of the edges leaving `node` along with the edge weights.
+    pub fn successors(&self, node: usize) -> &[(usize, W)] {
+        self.neighbors(node, EdgeDirection::Outgoing)
+    }
+
+    /// Returns the sources of the edges entering `node` along with the edge weights.
+    pub fn predecessors(&self, node: usize) -> &[(usize, W)] {
+        self.neighbors(node, EdgeDirection::Incoming)
+    }
+
+    /// Returns the weight of the edge from `source` to `target`, if present.
+    pub fn edge_weight(&self, source: usize, target: usize) -> Option<W> {
+        self.outgoing
+            .get(source)?
+            .iter()
+            .find(|&&(t, _)| t == target)
+            .map(|&(_, w)| w)
+    }
+
+    /// Returns the number of edges of `node` in the given direction.
+    pub fn degree(&self, node: usize, direction: EdgeDirection) -> usize {
+        self.neighbors(node, direction).len()
+    }
+
+    /// Returns the degree of every node in the given direction.
+    pub fn degrees(&self, direction: EdgeDirection) -> Vec<usize> {
+        (0..self.num_nodes())
+            .map(|node| self.degree(node, direction))
+            .collect()
+    }
+
+    /// Summarizes the degree distribution in the given direction.
+    ///
+    /// All statistics are 0 for a graph without nodes.
+    pub fn degree_stats(&self, direction: EdgeDirection) -> DegreeStats {
+        let degrees = self.degrees(direction);
+        DegreeStats {
+            min: degrees.iter().copied().min().unwrap_or(0),
+            mean: if degrees.is_empty() {
+                0.0
+            } else {
+                degrees.iter().sum::<usize>() as f64 / degrees.len() as f64
+            },
+            max: degrees.iter().copied().max().unwrap_or(0),
+            zero_degree: degrees.iter().filter(|&&d| d == 0).count(),
+        }
+    }
+
+    /// Sets the attribute of `node`, returning the previous one.
+    ///
+    /// # Panics
+    /// Panics if `node` is not part of the graph.
+    pub fn set_attribute(&mut self, node: usize, attribute: N) -> Option<N> {
+        self.attributes[node].replace(attribute)
+    }
+
+    /// Returns the attribute of `node`, if set.
+    pub fn attribute(&self, node: usize) -> Option<&N> {
+        self.attributes.get(node)?.as_ref()
+    }
+
+    /// Returns the names of the nodes, if the graph was built from named edges.
+    pub fn node_names(&self) -> Option<&[String]> {
+        self.node_names.as_deref()
+    }
+
+    /// Returns the index of the node called `name`, if the graph has node names.
+    pub fn node_index(&self, name: &str) -> Option<usize> {
+        self.name_index.as_ref()?.get(name).copied()
+    }
+}
+
+impl<N, W: FloatOpsTS> Graph<N, W> {
+    /// Builds a graph from an edge list of regulators and their targets.
+    ///
+    /// The edge list is cleaned by `validate_net` according to `options`, like the
+    /// pathway network constructors do, and both sources and targets are then mapped
+    /// onto their position in `features`, which become the nodes of the graph. The
+    /// feature names are matched after the same case normalization.
+    ///
+    /// # Arguments
+    /// * `sources` - The source node name of every edge
+    /// * `targets` - The target node name of every edge
+    /// * `weights` - The weight of every edge, or `None` to weight every edge with 1
+    /// * `features` - The feature names of the dataset; stored as node names
+    /// * `missing` - How edges with an endpoint missing from `features` are handled;
+    ///   `SkipAndReport` behaves like `Skip`
+    /// * `options` - How duplicates, symbols, weights and self-loops are handled
+    ///
+    /// # Returns
+    /// The graph and the report of the edge list validation, or an error if the edge
+    /// list is rejected by `options` or an endpoint is missing under
+    /// `MissingFeaturePolicy::Error`. Errors of the edge list validation are
+    /// `NetworkValidationError`s that can be recovered with `downcast_ref`.
+    pub fn from_edge_list(
+        sources: Vec<String>,
+        targets: Vec<String>,
+        weights: Option<Vec<W>>,
+        features: Vec<String>,
+        missing: MissingFeaturePolicy,
+        options: &ValidationOptions,
+    ) -> anyhow::Result<(Self, ValidationReport)> {
+        let (groups, report) = validate_net(&sources, &targets, weights.as_deref(), options)?;
+        drop((sources, targets, weights));
+
+        let mut graph = Self::new(features.len());
+        let lookup = feature_lookup(&features, options.case);
+        for (source, edges) in groups {
+            let source_idx = lookup.get(source.as_str()).copied();
+            for (target, weight) in edges {
+                match (source_idx, lookup.get(target.as_str())) {
+                    (Some(s), Some(&t)) => graph.add_edge(s, t, weight),
+                    _ if missing == MissingFeaturePolicy::Error => {
+                        let name = if source_idx.is_none() {
+                            &source
+                        } else {
+                            &target
+                        };
+                        return Err(anyhow!(
+                            "Node '{}' of edge '{}' -> '{}' is not part of the feature space!",
+                            name,
+                            source,
+                            target
+                        ));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        drop(lookup);
+        let name_index: HashMap<String, usize> = features
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (name.clone(), idx))
+            .collect();
+        graph.node_names = Some(features);
+        graph.name_index = Some(name_index);
+        Ok((graph, report))
+    }
+
+    /// Smooths per-node scores over the graph by a random walk with restart.
+    ///
+    /// Computes the fixed point of `p = r * seeds + (1 - r) * M p`, where `M` moves the
+    /// score of every node along its outgoing edges in proportion to their absolute
+    /// weights, i.e. the personalized PageRank of `seeds`. Score reaching a node
+    /// without outgoing edges is not passed on. Every iteration gathers the scores of
+    /// the predecessors of all nodes in parallel when the `rayon` feature is enabled.
+    ///
+    /// # Arguments
+    /// * `seeds` - The score of every node, e.g. a per-gene statistic
+    /// * `restart_prob` - Probability `r` of restarting at the seeds, in `(0, 1]`
+    /// * `tol` - Iteration stops once the L1 change of the scores drops below `tol`
+    /// * `max_iter` - The largest number of iterations, e.g. `1000`
+    ///
+    /// # Returns
+    /// The smoothed score of every node, or an error if `seeds` does not match the
+    /// number of nodes, a seed is not finite, a parameter is out of range or the scores
+    /// have not converged after `max_iter` iterations
+    pub fn propagate(
+        &self,
+        seeds: &[W],
+        restart_prob: f64,
+        tol: f64,
+        max_iter: usize,
+    ) -> anyhow::Result<Vec<W>>
+    where
+        N: Sync,
+    {
+        let n = self.num_nodes();
+        if seeds.len() != n {
+            return Err(anyhow!(
+                "Expected {} seed scores, one per node, got {}!",
+                n,
+                seeds.len()
+            ));
+        }
+        if restart_prob.is_nan() || restart_prob <= 0.0 || restart_prob > 1.0 {
+            return Err(anyhow!(
+                "Restart probability must lie in (0, 1], got {}!",
+                restart_prob
+            ));
+        }
+        if tol.is_nan() || tol <= 0.0 {
+            return Err(anyhow!("Tolerance must be positive, got {}!", tol));
+        }
+        if let Some(node) = seeds.iter().position(|s| !num_traits::Float::is_finite(*s)) {
+            return Err(anyhow!("Seed score of node {} is not finite!", node));
+        }
+
+        let out_weight: Vec<f64> = self
+            .outgoing
+            .iter()
+            .map(|edges| {
+                edges
+                    .iter()
+                    .map(|&(_, w)| num_traits::Float::abs(w.to_f64().unwrap()))
+                    .sum()
+            })
+            .collect();
+        let restart: Vec<f64> = seeds
+            .iter()
+            .map(|s| restart_prob * s.to_f64().unwrap())
+            .collect();
+
+        let mut scores: Vec<f64> = seeds.iter().map(|s| s.to_f64().unwrap()).collect();
+        let mut change = f64::INFINITY;
+        for _ in 0..max_iter {
+            let next = map_indices(n, |node| {
+                let walked: f64 = self.incoming[node]
+                    .iter()
+                    .filter(|&&(source, _)| out_weight[source] > 0.0)
+                    .map(|&(source, w)| {
+                        num_traits::Float::abs(w.to_f64().unwrap()) / out_weight[source]
+                            * scores[source]
+                    })
+                    .sum();
+                restart[node] + (1.0 - restart_prob) * walked
+            });
+            change = next.iter().zip(&scores).map(|(a, b)| (a - b).abs()).sum();
+            scores = next;
+            if change < tol {
+                break;
+            }
+        }
+        if change.is_nan() || change >= tol {
+            return Err(anyhow!(
+                "Propagation did not converge within {} iterations (change {})!",
+                max_iter,
+                change
+            ));
+        }
+
+        Ok(scores
+            .into_iter()
+            .map(|s| W::from_f64(s).unwrap())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain() -> Graph<(), f64> {
+        let mut graph = Graph::new(3);
+        graph.add_edge(0, 1, 1.0);
+        graph.add_edge(1, 2, -2.0);
+        graph
+    }
+
+    #[test]
+    fn from_edge_list_accepts_an_empty_edge_list() {
+        let features = vec!["g1".to_string(), "g2".to_string()];
+        let (graph, report) = Graph::<(), f64>::from_edge_list(
+            Vec::new(),
+            Vec::new(),
+            None,
+            features.clone(),
+            MissingFeaturePolicy::Error,
+            &ValidationOptions::default(),
+        )
+        .unwrap();
+        assert_eq!((graph.num_nodes(), graph.num_edges()), (2, 0));
+        assert_eq!(graph.node_names(), Some(&features[..]));
+        assert_eq!(report, ValidationReport::default());
+    }
+
+    #[test]
+    fn from_edge_list_applies_the_validation_options() {
+        use crate::utils::{NetworkValidationError, SymbolCase, WeightPolicy};
+
+        let edges = || {
+            (
+                vec!["tf1".to_string(), "TF1".to_string(), "TF1".to_string()],
+                vec!["g1".to_string(), "G2".to_string(), "G1".to_string()],
+                Some(vec![1.0, f64::NAN, 3.0]),
+            )
+        };
+        let features = vec!["TF1".to_string(), "G1".to_string(), "G2".to_string()];
+        let build = |options: &ValidationOptions| {
+            let (sources, targets, weights) = edges();
+            Graph::<(), f64>::from_edge_list(
+                sources,
+                targets,
+                weights,
+                features.clone(),
+                MissingFeaturePolicy::Error,
+                options,
+            )
+        };
+
+        let err = build(&ValidationOptions::default()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<NetworkValidationError>(),
+            Some(&NetworkValidationError::NonFiniteWeight { row: 1 })
+        );
+
+        let options = ValidationOptions {
+            case: SymbolCase::Upper,
+            weights: WeightPolicy::Drop,
+            ..ValidationOptions::default()
+        };
+        let (graph, report) = build(&options).unwrap();
+        assert_eq!(graph.successors(0), [(1, 3.0)]);
+        assert_eq!(graph.node_index("G2"), Some(2));
+        assert_eq!((report.non_finite_dropped, report.duplicate_edges), (1, 1));
+    }
+
+    #[test]
+    fn propagate_reaches_the_fixed_point() {
+        let scores = chain()
+            .propagate(&[1.0, 0.0, 0.0], 0.5, 1e-12, 100)
+            .unwrap();
+        for (score, expected) in scores.iter().zip([0.5, 0.25, 0.125]) {
+            assert!((score - expected).abs() < 1e-12, "{scores:?}");
+        }
+    }
+
+    #[test]
+    fn propagate_rejects_non_finite_seeds() {
+        let graph = chain();
+        assert!(
+            graph
+                .propagate(&[f64::NAN, 0.0, 0.0], 0.5, 1e-9, 100)
+                .is_err()
+        );
+        assert!(
+            graph
+                .propagate(&[0.0, f64::INFINITY, 0.0], 0.5, 1e-9, 100)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn propagate_rejects_non_positive_tolerance() {
+        let graph = chain();
+        assert!(graph.propagate(&[1.0, 0.0, 0.0], 0.5, 0.0, 100).is_err());
+        assert!(graph.propagate(&[1.0, 0.0, 0.0], 0.5, -1.0, 100).is_err());
+        assert!(
+            graph
+                .propagate(&[1.0, 0.0, 0.0], 0.5, f64::NAN, 100)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn propagate_fails_without_convergence() {
+        let mut cycle: Graph<(), f64> = Graph::new(2);
+        cycle.add_edge(0, 1, 1.0);
+        cycle.add_edge(1, 0, 1.0);
+        assert!(cycle.propagate(&[1.0, 0.0], 0.01, 1e-12, 5).is_err());
+        assert!(cycle.propagate(&[1.0, 0.0], 0.5, 1e-12, 0).is_err());
+        assert!(cycle.propagate(&[1.0, 0.0], 0.5, 1e-12, 200).is_ok());
+    }
+}
@@ -1,7 +1,32 @@
-use crate::utils::validate_net;
-use std::collections::HashMap;
+use crate::traits::FloatOps;
 use std::hash::Hash;
 
+mod categorical;
+mod encoder;
+mod graph;
+mod pathway;
+mod sparse;
+
+#[cfg(feature = "io")]
+pub use crate::utils::{ColumnSpec, validate_net_from_csv};
+pub use crate::utils::{
+    EdgeGroups, NetworkValidationError, SelfLoopPolicy, SymbolCase, ValidationOptions,
+    ValidationReport, WeightPolicy, validate_net,
+};
+pub use categorical::CategoricalVector;
+pub use encoder::{BatchEncoder, CompositeEncoder};
+pub use graph::{DegreeStats, EdgeDirection, Graph};
+pub use pathway::{
+    ConflictPolicy, ConstructionReport, DotGraph, DotOptions, DuplicatePolicy, EdgeSign, GeneIndex,
+    GenericPathwayNetwork, GseaResult, MissingFeaturePolicy, NetworkError, NetworkOptions,
+    NetworkSummary, OraResult, OverlapMetric, PathwayIntoIter, PathwayIter, PathwayNetwork,
+    PathwayNetworkBuilder, SignedTargets, SubsetReport, TieMethod, WeightCombination,
+    WeightNormalization,
+};
+#[cfg(feature = "ndarray")]
+pub use pathway::{ScoringMethod, ScoringOptions};
+pub use sparse::CsrData;
+
 /// Represents the direction of operations in matrix or array computations.
 ///
 /// This enum is used to specify whether operations should be performed
@@ -48,6 +73,7 @@ impl BatchIdentifier for &str {}
 impl BatchIdentifier for i32 {}
 impl BatchIdentifier for u32 {}
 impl BatchIdentifier for usize {}
+impl<A: BatchIdentifier, B: BatchIdentifier> BatchIdentifier for (A, B) {}
 
 /// Enumeration of distance metrics for mathematical computations.
 ///
@@ -64,119 +90,98 @@ pub enum DistanceMetric {
     Cosine,
 }
 
-pub struct PathwayNetwork {
-    names: Vec<String>,  // name of pathways
-    starts: Vec<usize>,  // start of pathway
-    offsets: Vec<usize>, // length of pathway
-    cnct: Vec<usize>,    // gene index of pathway
-    weights: Vec<f32>,   // weight of each gene in the pathway
-}
-
-impl PathwayNetwork {
-    pub fn new(
-        names: Vec<String>,
-        starts: Vec<usize>,
-        offsets: Vec<usize>,
-        cnct: Vec<usize>,
-        weights: Vec<f32>,
-    ) -> Self {
-        Self {
-            names,
-            starts,
-            offsets,
-            cnct,
-            weights,
-        }
-    }
-
-    pub fn new_wo_weights(
-        names: Vec<String>,
-        starts: Vec<usize>,
-        offsets: Vec<usize>,
-        cnct: Vec<usize>,
-    ) -> Self {
-        let weights = vec![1f32; cnct.len()];
-        Self {
-            names,
-            starts,
-            offsets,
-            cnct,
-            weights,
-        }
-    }
-
-    pub fn new_from_vec(
-        sources: Vec<String>,
-        targets: Vec<String>,
-        weights: Option<Vec<f32>>,
-        features: Vec<String>,
-        tmin: u32,
-    ) -> Self {
-        let res = validate_net(sources, targets, weights, false).unwrap();
-        let tmin = tmin as usize;
-        let filtered: HashMap<String, Vec<(String, f32)>> = res
-            .into_iter()
-            .filter_map(|(k, v)| if v.len() >= tmin { Some((k, v)) } else { None })
-            .collect();
-
-        let name_to_id: HashMap<String, usize> = features
-            .iter()
-            .enumerate()
-            .map(|(idx, name)| (name.clone(), idx))
-            .collect();
-
-        let total_lengths = filtered.values().fold(0usize, |v, a| v + a.len());
-        let num_pathways = filtered.len();
-
-        let mut names: Vec<String> = Vec::with_capacity(num_pathways);
-        let mut starts: Vec<usize> = Vec::with_capacity(num_pathways);
-        let mut offsets: Vec<usize> = Vec::with_capacity(num_pathways);
-        let mut cnct: Vec<usize> = Vec::with_capacity(total_lengths);
-        let mut weights_vec: Vec<f32> = Vec::with_capacity(total_lengths);
-
-        let mut i = 0usize;
-
-        for (k, v) in filtered.into_iter() {
-            let len = v.len();
-
-            for (g_name, g_weight) in v {
-                let g_idx = name_to_id.get(&g_name).unwrap();
-                cnct.push(*g_idx);
-                weights_vec.push(g_weight);
+impl DistanceMetric {
+    /// Computes the distance between two vectors.
+    ///
+    /// The cosine distance is `1 - cos(a, b)`, clamped to `[0, 2]`; like in scikit-learn,
+    /// a vector of zero norm is at distance 1 from every vector.
+    ///
+    /// # Arguments
+    /// * `a` - The first vector
+    /// * `b` - The second vector
+    ///
+    /// # Panics
+    /// Panics if the vectors differ in length.
+    pub fn distance<T: FloatOps>(&self, a: &[T], b: &[T]) -> T {
+        assert_eq!(a.len(), b.len(), "Vectors must have the same length");
+        let pairs = a.iter().zip(b);
+        match self {
+            Self::Euclidean => pairs
+                .fold(T::zero(), |acc, (&x, &y)| acc + (x - y) * (x - y))
+                .sqrt(),
+            Self::Manhattan => pairs.fold(T::zero(), |acc, (&x, &y)| {
+                acc + num_traits::Float::abs(x - y)
+            }),
+            Self::Cosine => {
+                let (dot, norm_a, norm_b) = pairs.fold(
+                    (T::zero(), T::zero(), T::zero()),
+                    |(dot, na, nb), (&x, &y)| (dot + x * y, na + x * x, nb + y * y),
+                );
+                let norms = (norm_a * norm_b).sqrt();
+                if norms > T::zero() {
+                    num_traits::Float::clamp(
+                        T::one() - dot / norms,
+                        T::zero(),
+                        T::from(2.0).unwrap(),
+                    )
+                } else {
+                    T::one()
+                }
             }
-
-            names.push(k);
-            starts.push(i);
-            offsets.push(len);
-            i += len;
-        }
-
-        Self {
-            names,
-            starts,
-            offsets,
-            cnct,
-            weights: weights_vec,
         }
     }
+}
 
-    pub fn get_pathway_name(&self, idx: usize) -> &str {
-        self.names[idx].as_str()
-    }
+/// Enumeration of methods used to aggregate the values of a group into one value.
+///
+/// Used when collapsing observations that share a batch identifier, e.g. when
+/// building pseudobulk profiles from single-cell measurements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregationMethod {
+    /// Sum of all values in the group
+    Sum,
+    /// Arithmetic mean of all values in the group
+    Mean,
+    /// Median of all values in the group, including implicit zeros of sparse inputs
+    Median,
+}
 
-    pub fn get_pathway_features(&self, idx: usize) -> &[usize] {
-        let srt = self.starts[idx];
-        let off = srt + self.offsets[idx];
-        &self.cnct[srt..off]
-    }
+/// Strategy used to decide how many observations of every group are kept when balancing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BalanceStrategy {
+    /// Downsample every group to the size of the smallest group
+    Smallest,
+    /// Downsample every group to at most the given size; smaller groups are kept entirely
+    Target(usize),
+}
 
-    pub fn get_pathway_features_and_weights(&self, idx: usize) -> (&[usize], &[f32]) {
-        let srt = self.starts[idx];
-        let off = srt + self.offsets[idx];
-        (&self.cnct[srt..off], &self.weights[srt..off])
-    }
+/// Ordering of the entries returned by value counting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountOrder {
+    /// Descending by count, ties broken by first occurrence
+    Count,
+    /// Ascending by key
+    Key,
+    /// In order of first occurrence
+    FirstOccurrence,
+}
 
-    pub fn get_num_pathways(&self) -> usize {
-        self.names.len()
-    }
+/// Direction in which values are sorted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Smallest value first
+    Ascending,
+    /// Largest value first
+    Descending,
+}
+
+/// Placement of NaN values when sorting or selecting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NanPolicy {
+    /// NaN values precede all other values, regardless of the sort order
+    First,
+    /// NaN values follow all other values, regardless of the sort order
+    Last,
+    /// NaN values are left out of the result
+    Omit,
 }
@@ -0,0 +1,145 @@
+use super::{DuplicatePolicy, GenericPathwayNetwork};
+use crate::traits::{FloatOps, UIndex};
+use crate::utils::dedup_entries;
+use anyhow::anyhow;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// Incrementally assembles a `PathwayNetwork`.
+///
+/// Pathways are created with `add_pathway` and filled with `add_gene`, or filled in
+/// any order with `add_edge`. Duplicate genes and size limits are resolved once in
+/// `build`, which also packs everything into the compact network layout using the
+/// index type `I`.
+#[derive(Debug, Clone)]
+pub struct PathwayNetworkBuilder<W = f32, I = usize> {
+    pathways: Vec<(String, Vec<(usize, W)>)>,
+    lookup: HashMap<String, usize>,
+    current: Option<usize>,
+    min_size: usize,
+    max_size: Option<usize>,
+    dedup_policy: DuplicatePolicy,
+    index: PhantomData<I>,
+}
+
+impl<W, I> Default for PathwayNetworkBuilder<W, I> {
+    fn default() -> Self {
+        Self {
+            pathways: Vec::new(),
+            lookup: HashMap::new(),
+            current: None,
+            min_size: 0,
+            max_size: None,
+            dedup_policy: DuplicatePolicy::First,
+            index: PhantomData,
+        }
+    }
+}
+
+impl<W: FloatOps, I: UIndex> PathwayNetworkBuilder<W, I> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new pathway, or continues an existing one with the same name.
+    ///
+    /// Subsequent calls to `add_gene` add genes to this pathway.
+    pub fn add_pathway(mut self, name: &str) -> Self {
+        let idx = self.pathway_index(name);
+        self.current = Some(idx);
+        self
+    }
+
+    /// Adds a gene to the pathway most recently started with `add_pathway`.
+    ///
+    /// # Panics
+    /// Panics if no pathway has been started yet.
+    pub fn add_gene(mut self, gene: usize, weight: W) -> Self {
+        let idx = self
+            .current
+            .expect("add_pathway must be called before add_gene");
+        self.pathways[idx].1.push((gene, weight));
+        self
+    }
+
+    /// Adds a gene to the pathway named `source`, creating the pathway if needed.
+    ///
+    /// Unlike `add_gene` this does not depend on the current pathway, so edges can
+    /// be added in any order.
+    pub fn add_edge(mut self, source: &str, target: usize, weight: W) -> Self {
+        let idx = self.pathway_index(source);
+        self.pathways[idx].1.push((target, weight));
+        self
+    }
+
+    /// Sets the minimum number of genes a pathway needs to be kept (default 0).
+    pub fn min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Sets the maximum number of genes a pathway may have to be kept (default unlimited).
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Sets how genes added more than once to the same pathway are resolved (default `First`).
+    pub fn dedup_policy(mut self, policy: DuplicatePolicy) -> Self {
+        self.dedup_policy = policy;
+        self
+    }
+
+    /// Returns the number of pathways added so far, before size filtering.
+    pub fn num_pathways(&self) -> usize {
+        self.pathways.len()
+    }
+
+    /// Resolves duplicate genes, applies the size limits and packs the network.
+    ///
+    /// Pathways are kept in the order they were first added.
+    ///
+    /// # Returns
+    /// The network, or an error if a duplicate gene is found under `DuplicatePolicy::Error`
+    /// or the network does not fit into the index type
+    pub fn build(self) -> anyhow::Result<GenericPathwayNetwork<W, I>> {
+        let mut names = Vec::with_capacity(self.pathways.len());
+        let mut starts = Vec::with_capacity(self.pathways.len());
+        let mut offsets = Vec::with_capacity(self.pathways.len());
+        let mut cnct = Vec::new();
+        let mut weights = Vec::new();
+
+        for (name, genes) in self.pathways {
+            let genes = dedup_entries(genes, self.dedup_policy).map_err(|gene| {
+                anyhow!(
+                    "Gene {} is listed more than once in pathway '{}'!",
+                    gene,
+                    name
+                )
+            })?;
+            let len = genes.len();
+            if len < self.min_size || self.max_size.is_some_and(|max| len > max) {
+                continue;
+            }
+            names.push(name);
+            starts.push(cnct.len());
+            offsets.push(len);
+            for (gene, weight) in genes {
+                cnct.push(gene);
+                weights.push(weight);
+            }
+        }
+
+        GenericPathwayNetwork::from_usize_parts(names, starts, offsets, cnct, weights)
+    }
+
+    fn pathway_index(&mut self, name: &str) -> usize {
+        if let Some(&idx) = self.lookup.get(name) {
+            return idx;
+        }
+        let idx = self.pathways.len();
+        self.pathways.push((name.to_string(), Vec::new()));
+        self.lookup.insert(name.to_string(), idx);
+        idx
+    }
+}
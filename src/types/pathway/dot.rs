@@ -0,0 +1,140 @@
+use super::{GenericPathwayNetwork, OverlapMetric};
+use crate::traits::{FloatOpsTS, UIndex};
+use std::io::Write;
+
+/// Graph rendered by `to_dot`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DotGraph {
+    /// Pathways and genes as the two node sets, connected by the weighted memberships
+    Bipartite,
+    /// Pathways as nodes, connected if their gene set similarity reaches `threshold`
+    Overlap {
+        /// The similarity measure
+        metric: OverlapMetric,
+        /// Minimum similarity for two pathways to be connected
+        threshold: f64,
+    },
+}
+
+/// Options controlling how `to_dot` renders a network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DotOptions {
+    /// The graph to render
+    pub graph: DotGraph,
+    /// Pen width of the edge with the largest absolute weight; other edges are scaled
+    /// linearly
+    pub max_pen_width: f64,
+    /// Color of edges with a negative weight; positive edges are drawn in black
+    pub negative_color: String,
+}
+
+impl Default for DotOptions {
+    fn default() -> Self {
+        Self {
+            graph: DotGraph::Bipartite,
+            max_pen_width: 3.0,
+            negative_color: "red".to_string(),
+        }
+    }
+}
+
+impl<W: FloatOpsTS, I: UIndex + Send + Sync> GenericPathwayNetwork<W, I> {
+    /// Writes the network as an undirected Graphviz DOT graph for visual inspection.
+    ///
+    /// Pathways are drawn as boxes labelled with their name. In the bipartite graph
+    /// genes are drawn as ellipses labelled with the stored feature names, or with
+    /// their index if the network has none. Edge widths are proportional to the
+    /// absolute weight or similarity.
+    ///
+    /// # Arguments
+    /// * `writer` - The destination of the DOT content
+    /// * `options` - The graph to render and its styling
+    pub fn to_dot<O: Write>(&self, mut writer: O, options: &DotOptions) -> anyhow::Result<()> {
+        writeln!(writer, "graph pathways {{")?;
+        for (idx, name) in self.names.iter().enumerate() {
+            writeln!(
+                writer,
+                "  p{} [shape=box, label=\"{}\"];",
+                idx,
+                escape(name)
+            )?;
+        }
+
+        match options.graph {
+            DotGraph::Bipartite => {
+                let index = self.build_gene_index();
+                for gene in (0..index.num_features()).filter(|&g| index.degree(g) > 0) {
+                    let label = match &self.feature_names {
+                        Some(names) => escape(&names[gene]),
+                        None => gene.to_string(),
+                    };
+                    writeln!(writer, "  g{} [shape=ellipse, label=\"{}\"];", gene, label)?;
+                }
+                let max_abs = self
+                    .weights
+                    .iter()
+                    .map(|w| num_traits::Float::abs(w.to_f64().unwrap()))
+                    .fold(0.0, f64::max);
+                for (idx, (genes, weights)) in (0..self.get_num_pathways())
+                    .map(|idx| self.get_pathway_features_and_weights(idx))
+                    .enumerate()
+                {
+                    for (gene, weight) in genes.iter().zip(weights) {
+                        let weight = weight.to_f64().unwrap();
+                        writeln!(
+                            writer,
+                            "  p{} -- g{} [{}];",
+                            idx,
+                            gene.to_index(),
+                            edge_style(weight, max_abs, options)
+                        )?;
+                    }
+                }
+            }
+            DotGraph::Overlap { metric, threshold } => {
+                let overlap = self.pathway_overlap(metric, threshold);
+                let max_abs = overlap.data().iter().copied().fold(0.0, f64::max);
+                let indptr = overlap.indptr();
+                for i in 0..self.get_num_pathways() {
+                    for k in indptr[i]..indptr[i + 1] {
+                        let j = overlap.indices()[k];
+                        if j > i {
+                            writeln!(
+                                writer,
+                                "  p{} -- p{} [{}];",
+                                i,
+                                j,
+                                edge_style(overlap.data()[k], max_abs, options)
+                            )?;
+                        }
+                    }
+                }
+            }
+        }
+
+        writeln!(writer, "}}")?;
+        Ok(())
+    }
+}
+
+fn edge_style(weight: f64, max_abs: f64, options: &DotOptions) -> String {
+    let width = if max_abs > 0.0 {
+        options.max_pen_width * weight.abs() / max_abs
+    } else {
+        options.max_pen_width
+    };
+    if weight < 0.0 {
+        format!(
+            "penwidth={:.3}, color=\"{}\"",
+            width,
+            escape(&options.negative_color)
+        )
+    } else {
+        format!("penwidth={:.3}", width)
+    }
+}
+
+/// Escapes a string for use inside a quoted DOT identifier.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
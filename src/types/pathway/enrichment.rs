@@ -0,0 +1,311 @@
+use super::GenericPathwayNetwork;
+use crate::random::{RngOps, sample_indices};
+use crate::stats::{AdjustmentMethod, adjust_pvalues, hypergeometric_sf};
+use crate::traits::{FloatOps, FloatOpsTS, UIndex};
+use crate::utils::parallel::map_indices;
+use anyhow::anyhow;
+use std::collections::HashSet;
+
+/// Over-representation result of a single pathway.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OraResult {
+    /// Index of the pathway in the network
+    pub pathway: usize,
+    /// Number of pathway genes inside the universe
+    pub pathway_size: usize,
+    /// Query genes contained in the pathway, in pathway order
+    pub overlap: Vec<usize>,
+    /// Odds ratio of the 2×2 contingency table; infinite if the pathway or the query
+    /// is fully contained in the other, and 1 if the table carries no information
+    /// because the pathway or the query is empty or spans the whole universe
+    pub odds_ratio: f64,
+    /// One-sided hypergeometric p-value for over-representation
+    pub pvalue: f64,
+    /// Benjamini–Hochberg adjusted p-value over all pathways
+    pub padj: f64,
+}
+
+/// Gene set enrichment result of a single pathway.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GseaResult {
+    /// Index of the pathway in the network
+    pub pathway: usize,
+    /// Number of pathway genes with a statistic
+    pub pathway_size: usize,
+    /// Enrichment score, the maximum deviation of the running sum from zero
+    pub es: f64,
+    /// Enrichment score divided by the mean magnitude of the same-signed null scores
+    pub nes: f64,
+    /// Permutation p-value against the same-signed null scores
+    pub pvalue: f64,
+    /// Benjamini–Hochberg adjusted p-value over all pathways
+    pub padj: f64,
+    /// Genes driving the score, in rank order: the hits up to the peak for positive
+    /// scores and from the peak onwards for negative ones
+    pub leading_edge: Vec<usize>,
+}
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Tests every pathway for over-representation of `query_genes` with the
+    /// hypergeometric test.
+    ///
+    /// Genes are gene indices; pathway genes at or above `universe_size` are ignored
+    /// and repeated genes are counted once.
+    ///
+    /// # Arguments
+    /// * `query_genes` - The genes of interest, e.g. the differentially expressed genes
+    /// * `universe_size` - The number of genes that could have been selected
+    ///
+    /// # Returns
+    /// One result per pathway in network order, or an error if a query gene is not
+    /// below `universe_size`
+    pub fn ora(
+        &self,
+        query_genes: &[usize],
+        universe_size: usize,
+    ) -> anyhow::Result<Vec<OraResult>> {
+        let mut in_query = vec![false; universe_size];
+        let mut n_query = 0usize;
+        for &gene in query_genes {
+            let slot = in_query.get_mut(gene).ok_or_else(|| {
+                anyhow!(
+                    "Query gene {} is outside the universe of {} genes!",
+                    gene,
+                    universe_size
+                )
+            })?;
+            if !*slot {
+                *slot = true;
+                n_query += 1;
+            }
+        }
+
+        let mut results: Vec<OraResult> = self
+            .iter()
+            .enumerate()
+            .map(|(idx, (_, genes, _))| {
+                let mut seen: HashSet<usize> = HashSet::with_capacity(genes.len());
+                let mut pathway_size = 0usize;
+                let mut overlap = Vec::new();
+                for gene in genes.iter().map(|g| g.to_index()) {
+                    if gene >= universe_size || !seen.insert(gene) {
+                        continue;
+                    }
+                    pathway_size += 1;
+                    if in_query[gene] {
+                        overlap.push(gene);
+                    }
+                }
+                let k = overlap.len();
+                let a = k as f64;
+                let b = (pathway_size - k) as f64;
+                let c = (n_query - k) as f64;
+                let d = (universe_size + k - pathway_size - n_query) as f64;
+                let odds_ratio = if a * d == 0.0 && b * c == 0.0 {
+                    1.0
+                } else {
+                    (a * d) / (b * c)
+                };
+                OraResult {
+                    pathway: idx,
+                    pathway_size,
+                    overlap,
+                    odds_ratio,
+                    pvalue: hypergeometric_sf(k, pathway_size, n_query, universe_size),
+                    padj: 1.0,
+                }
+            })
+            .collect();
+
+        let pvalues: Vec<f64> = results.iter().map(|r| r.pvalue).collect();
+        for (result, padj) in results.iter_mut().zip(adjust_pvalues(
+            &pvalues,
+            AdjustmentMethod::BenjaminiHochberg,
+        )) {
+            result.padj = padj;
+        }
+        Ok(results)
+    }
+}
+
+impl<W: FloatOpsTS, I: UIndex + Send + Sync> GenericPathwayNetwork<W, I> {
+    /// Scores every pathway with the weighted Kolmogorov–Smirnov running sum of GSEA.
+    ///
+    /// Genes are ranked by decreasing statistic. Hits step the running sum up by their
+    /// absolute statistic, misses step it down uniformly. Significance is assessed
+    /// against random gene sets of the same size (gene permutation), and the p-value
+    /// is `(b + 1) / (m + 1)` where `m` null scores share the sign of the observed
+    /// score and `b` of them are at least as extreme. Pathways are processed in
+    /// parallel when the `rayon` feature is enabled. Every pathway draws its random
+    /// gene sets from its own stream forked from `rng`, which is advanced by a single
+    /// draw, so results do not depend on the number of threads.
+    ///
+    /// # Arguments
+    /// * `ranked_stats` - One statistic per feature, e.g. signed fold changes
+    /// * `permutations` - Number of random gene sets per pathway; 0 skips the null
+    ///   and leaves `nes`, `pvalue` and `padj` as NaN
+    /// * `rng` - The random number generator
+    ///
+    /// # Returns
+    /// One result per pathway in network order. Pathways containing no or all ranked
+    /// genes get a NaN score.
+    pub fn gsea<R: RngOps + Sync>(
+        &self,
+        ranked_stats: &[W],
+        permutations: usize,
+        rng: &mut R,
+    ) -> Vec<GseaResult> {
+        let n = ranked_stats.len();
+        let stream = rng.next_u64();
+        let parent = rng.fork(stream);
+        let weights: Vec<f64> = ranked_stats
+            .iter()
+            .map(|s| num_traits::Float::abs(s.to_f64().unwrap()))
+            .collect();
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (
+                ranked_stats[a].to_f64().unwrap(),
+                ranked_stats[b].to_f64().unwrap(),
+            );
+            b.total_cmp(&a)
+        });
+        let mut rank_of = vec![0usize; n];
+        for (rank, &gene) in order.iter().enumerate() {
+            rank_of[gene] = rank;
+        }
+        let hits_of = |genes: &mut dyn Iterator<Item = usize>| -> Vec<(usize, f64)> {
+            let mut hits: Vec<(usize, f64)> = genes.map(|g| (rank_of[g], weights[g])).collect();
+            hits.sort_unstable_by_key(|&(rank, _)| rank);
+            hits
+        };
+
+        let mut results = map_indices(self.get_num_pathways(), |idx| {
+            let mut seen: HashSet<usize> = HashSet::new();
+            let hits = hits_of(
+                &mut self
+                    .get_pathway_features(idx)
+                    .iter()
+                    .map(|g| g.to_index())
+                    .filter(|&g| g < n && seen.insert(g)),
+            );
+            let k = hits.len();
+            let mut result = GseaResult {
+                pathway: idx,
+                pathway_size: k,
+                es: f64::NAN,
+                nes: f64::NAN,
+                pvalue: f64::NAN,
+                padj: f64::NAN,
+                leading_edge: Vec::new(),
+            };
+            if k == 0 || k == n {
+                return result;
+            }
+
+            let (es, split) = enrichment_score(&hits, n);
+            let edge = if es >= 0.0 {
+                &hits[..split]
+            } else {
+                &hits[split..]
+            };
+            result.es = es;
+            result.leading_edge = edge.iter().map(|&(rank, _)| order[rank]).collect();
+            if permutations == 0 {
+                return result;
+            }
+
+            let mut rng = parent.fork(idx as u64);
+            let mut same_sign = 0usize;
+            let mut extreme = 0usize;
+            let mut magnitude = 0.0;
+            for _ in 0..permutations {
+                let null_hits = hits_of(&mut sample_indices(&mut rng, n, k).into_iter());
+                let (null_es, _) = enrichment_score(&null_hits, n);
+                if (null_es >= 0.0) == (es >= 0.0) {
+                    same_sign += 1;
+                    magnitude += null_es.abs();
+                    if null_es.abs() >= es.abs() {
+                        extreme += 1;
+                    }
+                }
+            }
+            if same_sign > 0 {
+                result.nes = es / (magnitude / same_sign as f64);
+            }
+            result.pvalue = (extreme + 1) as f64 / (same_sign + 1) as f64;
+            result
+        });
+
+        let pvalues: Vec<f64> = results.iter().map(|r| r.pvalue).collect();
+        for (result, padj) in results.iter_mut().zip(adjust_pvalues(
+            &pvalues,
+            AdjustmentMethod::BenjaminiHochberg,
+        )) {
+            result.padj = padj;
+        }
+        results
+    }
+}
+
+/// Computes the GSEA enrichment score of a gene set.
+///
+/// `hits` holds the rank and weight of every gene of the set, sorted by rank, and
+/// `n` is the number of ranked genes. Returns the score and the position in `hits`
+/// at which the peak is reached: the score is attained after `hits[split - 1]` for
+/// positive scores and just before `hits[split]` for negative ones.
+fn enrichment_score(hits: &[(usize, f64)], n: usize) -> (f64, usize) {
+    let k = hits.len();
+    let total: f64 = hits.iter().map(|&(_, w)| w).sum();
+    let miss = 1.0 / (n - k) as f64;
+    let mut cum = 0.0;
+    let (mut max, mut max_at) = (0.0f64, 0usize);
+    let (mut min, mut min_at) = (0.0f64, 0usize);
+    for (i, &(rank, weight)) in hits.iter().enumerate() {
+        let misses = (rank - i) as f64 * miss;
+        if cum - misses < min {
+            min = cum - misses;
+            min_at = i;
+        }
+        cum += if total > 0.0 {
+            weight / total
+        } else {
+            1.0 / k as f64
+        };
+        if cum - misses > max {
+            max = cum - misses;
+            max_at = i + 1;
+        }
+    }
+    if max >= -min {
+        (max, max_at)
+    } else {
+        (min, min_at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::PathwayNetwork;
+
+    #[test]
+    fn ora_reports_degenerate_tables_as_uninformative() {
+        // P2 has no gene inside the universe of 4 genes, P3 spans all of it
+        let network = PathwayNetwork::new_wo_weights(
+            vec!["P1".to_string(), "P2".to_string(), "P3".to_string()],
+            vec![0, 2, 3],
+            vec![2, 1, 4],
+            vec![0, 1, 5, 0, 1, 2, 3],
+        );
+        let results = network.ora(&[0, 1], 4).unwrap();
+        assert_eq!(results[0].odds_ratio, f64::INFINITY);
+        for result in &results[1..] {
+            assert_eq!((result.odds_ratio, result.pvalue), (1.0, 1.0));
+        }
+        assert_eq!(results[1].pathway_size, 0);
+
+        for result in network.ora(&[], 4).unwrap() {
+            assert_eq!((result.odds_ratio, result.pvalue), (1.0, 1.0));
+        }
+    }
+}
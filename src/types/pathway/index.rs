@@ -0,0 +1,78 @@
+use super::GenericPathwayNetwork;
+use crate::traits::{FloatOps, UIndex};
+
+/// A reverse index from genes to the pathways containing them.
+///
+/// Memberships are stored in the same compressed layout as the network itself:
+/// the pathways of gene `g` occupy `indptr[g]..indptr[g + 1]`, sorted by pathway index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneIndex<W = f32> {
+    indptr: Vec<usize>,
+    pathways: Vec<usize>,
+    weights: Vec<W>,
+}
+
+impl<W> GeneIndex<W> {
+    /// Returns the indices of all pathways containing `gene`.
+    ///
+    /// Genes outside the indexed range belong to no pathway.
+    pub fn pathways_containing(&self, gene: usize) -> &[usize] {
+        self.pathways_and_weights_containing(gene).0
+    }
+
+    /// Returns the indices of all pathways containing `gene` along with the gene's weight in each.
+    pub fn pathways_and_weights_containing(&self, gene: usize) -> (&[usize], &[W]) {
+        if gene + 1 >= self.indptr.len() {
+            return (&[], &[]);
+        }
+        let range = self.indptr[gene]..self.indptr[gene + 1];
+        (&self.pathways[range.clone()], &self.weights[range])
+    }
+
+    /// Returns the number of pathways containing `gene`.
+    pub fn degree(&self, gene: usize) -> usize {
+        self.pathways_containing(gene).len()
+    }
+
+    /// Returns the size of the indexed gene space.
+    pub fn num_features(&self) -> usize {
+        self.indptr.len() - 1
+    }
+}
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Builds the reverse mapping from every gene to the pathways containing it.
+    ///
+    /// The indexed gene space spans `0..=max_gene`, where `max_gene` is the largest
+    /// gene index used by any pathway.
+    pub fn build_gene_index(&self) -> GeneIndex<W> {
+        let n_features = self.cnct.iter().max().map_or(0, |&m| m.to_index() + 1);
+        let mut indptr = vec![0usize; n_features + 1];
+        for &gene in &self.cnct {
+            indptr[gene.to_index() + 1] += 1;
+        }
+        for i in 0..n_features {
+            indptr[i + 1] += indptr[i];
+        }
+
+        let mut fill = indptr.clone();
+        let mut pathways = vec![0usize; self.cnct.len()];
+        let mut weights = vec![W::zero(); self.cnct.len()];
+        for idx in 0..self.get_num_pathways() {
+            let (genes, gene_weights) = self.get_pathway_features_and_weights(idx);
+            for (&gene, &weight) in genes.iter().zip(gene_weights) {
+                let gene = gene.to_index();
+                let pos = fill[gene];
+                pathways[pos] = idx;
+                weights[pos] = weight;
+                fill[gene] += 1;
+            }
+        }
+
+        GeneIndex {
+            indptr,
+            pathways,
+            weights,
+        }
+    }
+}
@@ -0,0 +1,633 @@
+#[cfg(feature = "io")]
+use super::ConstructionReport;
+use super::{EdgeSign, GenericPathwayNetwork, NetworkOptions};
+use crate::traits::{FloatOps, FloatOpsTS, UIndex};
+#[cfg(feature = "io")]
+use crate::utils::{ColumnSpec, validate_net_from_csv};
+use anyhow::anyhow;
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Magic bytes identifying the binary network format.
+const BINARY_MAGIC: &[u8; 4] = b"SUPN";
+/// Version of the binary network format written by `write_binary`.
+const BINARY_VERSION: u32 = 2;
+/// Largest number of bytes or elements allocated ahead of reading them, so corrupt
+/// length fields cannot trigger huge allocations.
+const READ_CHUNK: usize = 1 << 16;
+
+impl<W: FloatOpsTS, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Parses a pathway collection in the GMT format used by MSigDB.
+    ///
+    /// Every non-empty line holds a tab-separated pathway name, a description and the
+    /// gene symbols of the pathway. Genes are weighted with 1, unless they are written
+    /// as `GENE,weight` as produced by `to_gmt_weighted`. The pathways are validated
+    /// as an edge list from pathway to gene like `try_new_from_vec` does, so names are
+    /// normalized, genes listed more than once in a pathway are resolved and
+    /// non-finite weights are handled according to `options.validation`. Lines sharing
+    /// a pathway name are merged and pathways without genes are dropped.
+    ///
+    /// # Arguments
+    /// * `reader` - The source of the GMT content
+    /// * `features` - The feature names defining the gene index space
+    /// * `options` - Size filter, missing-feature policy and edge list validation
+    ///
+    /// # Returns
+    /// The parsed network, or an error if a line is malformed, the edge list is
+    /// rejected by `options.validation` or a gene is missing under
+    /// `MissingFeaturePolicy::Error`
+    pub fn from_gmt<R: BufRead>(
+        reader: R,
+        features: &[String],
+        options: &NetworkOptions,
+    ) -> anyhow::Result<Self> {
+        let mut sources: Vec<String> = Vec::new();
+        let mut targets: Vec<String> = Vec::new();
+        let mut weights: Vec<W> = Vec::new();
+        for (line_no, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim_end_matches(['\r', '\n']);
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut fields = line.split('\t');
+            let name = fields.next().unwrap_or_default().trim();
+            if name.is_empty() || fields.next().is_none() {
+                return Err(anyhow!(
+                    "Malformed GMT line {}: expected a pathway name and a description!",
+                    line_no + 1
+                ));
+            }
+            for (gene, weight) in fields
+                .map(str::trim)
+                .filter(|gene| !gene.is_empty())
+                .map(parse_gmt_gene)
+            {
+                sources.push(name.to_string());
+                targets.push(gene);
+                weights.push(weight);
+            }
+        }
+        let (network, _) =
+            Self::try_new_from_vec(sources, targets, Some(weights), features.to_vec(), options)?;
+        Ok(network)
+    }
+}
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Writes the network in the GMT format.
+    ///
+    /// Every pathway is written as one line holding its name, the description `na`
+    /// and the names of its genes. Weights are not written; use `to_gmt_weighted`
+    /// to preserve them.
+    ///
+    /// # Arguments
+    /// * `writer` - The destination of the GMT content
+    /// * `feature_names` - The feature names used to translate gene indices back to symbols
+    pub fn to_gmt<O: Write>(&self, writer: O, feature_names: &[String]) -> anyhow::Result<()>
+    where
+        W: Display,
+    {
+        self.write_gmt(writer, feature_names, false)
+    }
+
+    /// Writes the network in the GMT format, appending the weight to every gene as `GENE,weight`.
+    ///
+    /// The output can be read back with `from_gmt` without losing the weights.
+    ///
+    /// # Arguments
+    /// * `writer` - The destination of the GMT content
+    /// * `feature_names` - The feature names used to translate gene indices back to symbols
+    pub fn to_gmt_weighted<O: Write>(
+        &self,
+        writer: O,
+        feature_names: &[String],
+    ) -> anyhow::Result<()>
+    where
+        W: Display,
+    {
+        self.write_gmt(writer, feature_names, true)
+    }
+
+    fn write_gmt<O: Write>(
+        &self,
+        mut writer: O,
+        feature_names: &[String],
+        weighted: bool,
+    ) -> anyhow::Result<()>
+    where
+        W: Display,
+    {
+        for idx in 0..self.get_num_pathways() {
+            write!(writer, "{}\tna", self.get_pathway_name(idx))?;
+            let (genes, weights) = self.get_pathway_features_and_weights(idx);
+            for (&gene, &weight) in genes.iter().zip(weights) {
+                let name = feature_names.get(gene.to_index()).ok_or_else(|| {
+                    anyhow!(
+                        "Gene index {:?} of pathway '{}' has no feature name!",
+                        gene,
+                        self.get_pathway_name(idx)
+                    )
+                })?;
+                if weighted {
+                    write!(writer, "\t{},{}", name, weight)?;
+                } else {
+                    write!(writer, "\t{}", name)?;
+                }
+            }
+            writeln!(writer)?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Saves the network to a file in a compact binary format.
+    ///
+    /// See `write_binary` for the layout.
+    pub fn save_binary<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        let file = File::create(path)?;
+        self.write_binary(BufWriter::new(file))
+    }
+
+    /// Loads a network previously written with `save_binary`.
+    pub fn load_binary<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let file = File::open(path)?;
+        Self::read_binary(BufReader::new(file))
+    }
+
+    /// Writes the network in a compact binary format.
+    ///
+    /// The format consists of the magic bytes `SUPN`, a `u32` format version, the
+    /// byte width of the weights (4 or 8) as `u32`, the number of pathways and stored
+    /// genes as `u64`, the length-prefixed UTF-8 names and finally the raw `starts`,
+    /// `offsets` and `cnct` buffers as `u64`, the weights as `f32` or `f64`, the
+    /// feature names as a `u8` presence flag followed by their number as `u64` and the
+    /// length-prefixed names, and the recorded edge signs as a `u8` presence flag
+    /// followed by one `i8` per stored gene. All numbers are little-endian. Weights
+    /// are stored as `f64` if `W` is wider than four bytes.
+    pub fn write_binary<O: Write>(&self, mut writer: O) -> anyhow::Result<()> {
+        let weight_width: u32 = if std::mem::size_of::<W>() > 4 { 8 } else { 4 };
+        writer.write_all(BINARY_MAGIC)?;
+        writer.write_all(&BINARY_VERSION.to_le_bytes())?;
+        writer.write_all(&weight_width.to_le_bytes())?;
+        writer.write_all(&(self.names.len() as u64).to_le_bytes())?;
+        writer.write_all(&(self.cnct.len() as u64).to_le_bytes())?;
+        for name in &self.names {
+            write_string(&mut writer, name)?;
+        }
+        for buffer in [&self.starts, &self.offsets, &self.cnct] {
+            let bytes: Vec<u8> = buffer
+                .iter()
+                .flat_map(|&v| (v.to_index() as u64).to_le_bytes())
+                .collect();
+            writer.write_all(&bytes)?;
+        }
+        let bytes: Vec<u8> = if weight_width == 8 {
+            self.weights
+                .iter()
+                .flat_map(|w| w.to_f64().unwrap().to_le_bytes())
+                .collect()
+        } else {
+            self.weights
+                .iter()
+                .flat_map(|w| w.to_f32().unwrap().to_le_bytes())
+                .collect()
+        };
+        writer.write_all(&bytes)?;
+        match &self.feature_names {
+            Some(feature_names) => {
+                writer.write_all(&[1])?;
+                writer.write_all(&(feature_names.len() as u64).to_le_bytes())?;
+                for name in feature_names {
+                    write_string(&mut writer, name)?;
+                }
+            }
+            None => writer.write_all(&[0])?,
+        }
+        match &self.signs {
+            Some(signs) => {
+                writer.write_all(&[1])?;
+                let bytes: Vec<u8> = signs.iter().map(|s| s.to_i8() as u8).collect();
+                writer.write_all(&bytes)?;
+            }
+            None => writer.write_all(&[0])?,
+        }
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Reads a network written with `write_binary`.
+    ///
+    /// Files written with version 1 of the format, which lacks the weight width, the
+    /// feature names and the edge signs and always stores `f32` weights, are still
+    /// accepted. Stored weights are converted to `W` if their width differs. Length
+    /// fields are not trusted: buffers only grow as data arrives, so truncated or
+    /// corrupt content fails with an error rather than exhausting memory.
+    ///
+    /// # Returns
+    /// The network, or an error if the content is not a network, was written with an
+    /// unsupported format version or is internally inconsistent
+    pub fn read_binary<R: Read>(mut reader: R) -> anyhow::Result<Self> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != BINARY_MAGIC {
+            return Err(anyhow!("Not a binary pathway network file!"));
+        }
+        let version = u32::from_le_bytes(read_array(&mut reader)?);
+        let weight_width = match version {
+            1 => 4,
+            BINARY_VERSION => u32::from_le_bytes(read_array(&mut reader)?),
+            _ => {
+                return Err(anyhow!(
+                    "Unsupported binary network format version {} (expected 1 or {})!",
+                    version,
+                    BINARY_VERSION
+                ));
+            }
+        };
+        if weight_width != 4 && weight_width != 8 {
+            return Err(anyhow!(
+                "Unsupported weight width of {} bytes!",
+                weight_width
+            ));
+        }
+        let n_pathways = read_len(&mut reader)?;
+        let n_entries = read_len(&mut reader)?;
+
+        let mut names = Vec::with_capacity(n_pathways.min(READ_CHUNK));
+        for _ in 0..n_pathways {
+            names.push(read_string(&mut reader)?);
+        }
+        let starts = read_usizes(&mut reader, n_pathways)?;
+        let offsets = read_usizes(&mut reader, n_pathways)?;
+        let cnct = read_usizes(&mut reader, n_entries)?;
+        let bytes = read_bytes(&mut reader, checked_size(n_entries, weight_width as usize)?)?;
+        let weights: Vec<W> = if weight_width == 8 {
+            bytes
+                .chunks_exact(8)
+                .map(|c| W::from_f64(f64::from_le_bytes(c.try_into().unwrap())).unwrap())
+                .collect()
+        } else {
+            bytes
+                .chunks_exact(4)
+                .map(|c| W::from_f32(f32::from_le_bytes(c.try_into().unwrap())).unwrap())
+                .collect()
+        };
+        let feature_names = match version {
+            BINARY_VERSION if read_array::<_, 1>(&mut reader)? != [0] => {
+                let n_features = read_len(&mut reader)?;
+                let mut feature_names = Vec::with_capacity(n_features.min(READ_CHUNK));
+                for _ in 0..n_features {
+                    feature_names.push(read_string(&mut reader)?);
+                }
+                Some(feature_names)
+            }
+            _ => None,
+        };
+        let signs = match version {
+            BINARY_VERSION if read_array::<_, 1>(&mut reader)? != [0] => Some(
+                read_bytes(&mut reader, n_entries)?
+                    .into_iter()
+                    .map(|b| {
+                        EdgeSign::from_i8(b as i8)
+                            .ok_or_else(|| anyhow!("Invalid edge sign {}!", b as i8))
+                    })
+                    .collect::<anyhow::Result<Vec<EdgeSign>>>()?,
+            ),
+            _ => None,
+        };
+
+        let mut network = Self::from_usize_parts(names, starts, offsets, cnct, weights)?;
+        if let Some(feature_names) = feature_names {
+            network.set_feature_names(feature_names)?;
+        }
+        network.signs = signs;
+        Ok(network)
+    }
+}
+
+#[cfg(feature = "io")]
+impl<W: FloatOpsTS, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Builds a network from a delimited edge list without loading its columns into memory.
+    ///
+    /// Rows are validated and grouped by source while they are read, as described for
+    /// `validate_net_from_csv`, and the groups are then mapped onto `features` like
+    /// `try_new_from_vec` does.
+    ///
+    /// # Arguments
+    /// * `reader` - The source of the delimited content, starting with a header row
+    /// * `spec` - Column names and delimiter of the edge list
+    /// * `features` - The feature names of the dataset; stored in the network
+    /// * `options` - Size filter, missing-feature policy and edge list validation
+    ///
+    /// # Returns
+    /// The network and a report of the dropped genes and pathways, or an error if the
+    /// content cannot be read, the edge list is malformed or a target is missing under
+    /// `MissingFeaturePolicy::Error`
+    pub fn try_from_csv<R: Read>(
+        reader: R,
+        spec: &ColumnSpec,
+        features: Vec<String>,
+        options: &NetworkOptions,
+    ) -> anyhow::Result<(Self, ConstructionReport)> {
+        let (groups, validation) = validate_net_from_csv(reader, spec, &options.validation)?;
+        Self::from_edge_groups(groups, validation, features, options)
+    }
+
+    /// Reads a network from a delimited source/target/weight edge list with a header row.
+    ///
+    /// A shorthand for `try_from_csv` that borrows the feature names and discards the
+    /// construction report. Edges are grouped by their source column in order of first
+    /// appearance, so the file does not need to be sorted, and additional columns
+    /// (e.g. `curation_effort`) may be present.
+    ///
+    /// # Arguments
+    /// * `reader` - The source of the tabular content
+    /// * `features` - The feature names defining the gene index space
+    /// * `spec` - Column names, sign column and delimiter of the edge list
+    /// * `options` - Size filter, missing-feature policy and edge list validation
+    ///
+    /// # Returns
+    /// The parsed network, or an error under the conditions of `try_from_csv`
+    pub fn from_net_tsv<R: Read>(
+        reader: R,
+        features: &[String],
+        spec: &ColumnSpec,
+        options: &NetworkOptions,
+    ) -> anyhow::Result<Self> {
+        let (network, _) = Self::try_from_csv(reader, spec, features.to_vec(), options)?;
+        Ok(network)
+    }
+}
+
+/// Parses a textual weight into the weight type of the network.
+fn parse_weight<W: FloatOps>(text: &str) -> Result<W, std::num::ParseFloatError> {
+    let value: f64 = text.trim().parse()?;
+    Ok(W::from_f64(value).unwrap_or_else(num_traits::Float::nan))
+}
+
+/// Splits a GMT gene entry into symbol and weight, accepting the `GENE,weight` form.
+fn parse_gmt_gene<W: FloatOps>(entry: &str) -> (String, W) {
+    if let Some((gene, weight)) = entry.rsplit_once(',')
+        && let Ok(weight) = parse_weight(weight)
+    {
+        return (gene.trim().to_string(), weight);
+    }
+    (entry.to_string(), W::one())
+}
+
+fn read_array<R: Read, const N: usize>(reader: &mut R) -> anyhow::Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn read_len<R: Read>(reader: &mut R) -> anyhow::Result<usize> {
+    let value = u64::from_le_bytes(read_array(reader)?);
+    usize::try_from(value).map_err(|_| anyhow!("Length {} does not fit into usize!", value))
+}
+
+fn write_string<O: Write>(writer: &mut O, value: &str) -> anyhow::Result<()> {
+    writer.write_all(&(value.len() as u64).to_le_bytes())?;
+    writer.write_all(value.as_bytes())?;
+    Ok(())
+}
+
+fn read_string<R: Read>(reader: &mut R) -> anyhow::Result<String> {
+    let len = read_len(reader)?;
+    Ok(String::from_utf8(read_bytes(reader, len)?)?)
+}
+
+/// Reads exactly `len` bytes, growing the buffer as data arrives instead of
+/// allocating `len` bytes up front.
+fn read_bytes<R: Read>(reader: &mut R, len: usize) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(len.min(READ_CHUNK));
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(anyhow!(
+            "Binary network ended after {} of {} expected bytes!",
+            bytes.len(),
+            len
+        ));
+    }
+    Ok(bytes)
+}
+
+/// Returns the byte size of `n` elements of `width` bytes.
+fn checked_size(n: usize, width: usize) -> anyhow::Result<usize> {
+    n.checked_mul(width)
+        .ok_or_else(|| anyhow!("Length {} exceeds the addressable memory!", n))
+}
+
+fn read_usizes<R: Read>(reader: &mut R, n: usize) -> anyhow::Result<Vec<usize>> {
+    let bytes = read_bytes(reader, checked_size(n, 8)?)?;
+    bytes
+        .chunks_exact(8)
+        .map(|c| {
+            let value = u64::from_le_bytes(c.try_into().unwrap());
+            usize::try_from(value).map_err(|_| anyhow!("Value {} does not fit into usize!", value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DuplicatePolicy, NetworkError, PathwayNetwork, WeightNormalization};
+    use crate::utils::{NetworkValidationError, ValidationOptions};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn from_gmt_resolves_duplicate_genes() {
+        let gmt = "A\tna\tG1,1\tG2,2\tG1,3\n";
+        let features = names(&["G1", "G2"]);
+        let err = PathwayNetwork::from_gmt(
+            gmt.as_bytes(),
+            &features,
+            &NetworkOptions {
+                validation: ValidationOptions {
+                    duplicates: DuplicatePolicy::Error,
+                    ..ValidationOptions::default()
+                },
+                ..NetworkOptions::default()
+            },
+        )
+        .unwrap_err();
+        assert!(err.downcast_ref::<NetworkValidationError>().is_some());
+
+        let network =
+            PathwayNetwork::from_gmt(gmt.as_bytes(), &features, &NetworkOptions::default())
+                .unwrap();
+        assert_eq!(
+            network.get_pathway_features_and_weights(0),
+            (&[0usize, 1][..], &[3.0f32, 2.0][..])
+        );
+    }
+
+    #[cfg(feature = "io")]
+    #[test]
+    fn from_net_tsv_validates_the_edge_list() {
+        use crate::utils::SymbolCase;
+
+        let table = "source\ttarget\tweight\tmor\nTF1\tg1\t2\t-1\ntf1\tG2\t0.5\t1\n";
+        let features = names(&["G1", "G2"]);
+        let spec = ColumnSpec {
+            sign: Some("mor".to_string()),
+            ..ColumnSpec::default()
+        };
+        let options = NetworkOptions {
+            validation: ValidationOptions {
+                case: SymbolCase::Upper,
+                ..ValidationOptions::default()
+            },
+            ..NetworkOptions::default()
+        };
+        let network =
+            PathwayNetwork::from_net_tsv(table.as_bytes(), &features, &spec, &options).unwrap();
+        assert_eq!(network.get_num_pathways(), 1);
+        assert_eq!(
+            network.get_pathway_features_and_weights(0),
+            (&[0usize, 1][..], &[-2.0f32, 0.5][..])
+        );
+
+        let non_finite = "source\ttarget\tweight\nTF1\tG1\tnan\n";
+        let err = PathwayNetwork::from_net_tsv(
+            non_finite.as_bytes(),
+            &features,
+            &ColumnSpec::default(),
+            &NetworkOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<NetworkValidationError>(),
+            Some(&NetworkValidationError::NonFiniteWeight { row: 0 })
+        );
+    }
+
+    #[test]
+    fn readers_accept_an_empty_edge_list() {
+        let features = names(&["G1", "G2"]);
+        let options = NetworkOptions::default();
+        let network = PathwayNetwork::from_gmt("\n".as_bytes(), &features, &options).unwrap();
+        assert_eq!(network.get_num_pathways(), 0);
+        assert_eq!(network.feature_names(), Some(&features[..]));
+
+        #[cfg(feature = "io")]
+        {
+            let header = "source\ttarget\tweight\n";
+            let spec = ColumnSpec::default();
+            let (network, report) =
+                PathwayNetwork::try_from_csv(header.as_bytes(), &spec, features.clone(), &options)
+                    .unwrap();
+            assert_eq!(network.get_num_pathways(), 0);
+            assert_eq!(report, ConstructionReport::default());
+            let network =
+                PathwayNetwork::from_net_tsv(header.as_bytes(), &features, &spec, &options)
+                    .unwrap();
+            assert_eq!(network.get_num_pathways(), 0);
+        }
+    }
+
+    #[test]
+    fn binary_round_trip_keeps_feature_names_and_signs() {
+        let mut network = PathwayNetwork::new(
+            vec!["P1".to_string(), "P2".to_string()],
+            vec![0, 2],
+            vec![2, 1],
+            vec![0, 2, 1],
+            vec![1.0, 0.5, -2.0],
+        );
+        for feature_names in [None, Some(names(&["CD3E", "CD4", "MS4A1"]))] {
+            if let Some(feature_names) = feature_names {
+                network.set_feature_names(feature_names).unwrap();
+            }
+            let mut bytes = Vec::new();
+            network.write_binary(&mut bytes).unwrap();
+            assert_eq!(
+                PathwayNetwork::read_binary(bytes.as_slice()).unwrap(),
+                network
+            );
+        }
+
+        network.normalize_weights(WeightNormalization::MeanCenter);
+        let mut bytes = Vec::new();
+        network.write_binary(&mut bytes).unwrap();
+        let restored = PathwayNetwork::read_binary(bytes.as_slice()).unwrap();
+        assert_eq!(restored, network);
+        assert_eq!(restored.get_pathway_signs(1), [EdgeSign::Negative]);
+    }
+
+    fn header(version: u32, n_pathways: u64, n_entries: u64) -> Vec<u8> {
+        let mut bytes = BINARY_MAGIC.to_vec();
+        bytes.extend(version.to_le_bytes());
+        if version > 1 {
+            bytes.extend(4u32.to_le_bytes());
+        }
+        bytes.extend(n_pathways.to_le_bytes());
+        bytes.extend(n_entries.to_le_bytes());
+        bytes
+    }
+
+    #[test]
+    fn read_binary_accepts_version_1() {
+        let mut bytes = header(1, 1, 2);
+        bytes.extend(2u64.to_le_bytes());
+        bytes.extend(b"P1");
+        for value in [0u64, 2, 1, 0] {
+            bytes.extend(value.to_le_bytes());
+        }
+        for weight in [0.5f32, -1.0] {
+            bytes.extend(weight.to_le_bytes());
+        }
+        let network = GenericPathwayNetwork::<f64>::read_binary(bytes.as_slice()).unwrap();
+        assert_eq!(
+            network,
+            GenericPathwayNetwork::new(
+                names(&["P1"]),
+                vec![0],
+                vec![2],
+                vec![1, 0],
+                vec![0.5, -1.0]
+            )
+        );
+    }
+
+    #[test]
+    fn read_binary_rejects_corrupt_content() {
+        // lengths far beyond the content must fail without allocating them
+        for (n_pathways, n_entries) in [(u64::MAX, 0), (0, u64::MAX), (0, u64::MAX / 4)] {
+            let bytes = header(BINARY_VERSION, n_pathways, n_entries);
+            assert!(PathwayNetwork::read_binary(bytes.as_slice()).is_err());
+        }
+        let future = header(BINARY_VERSION + 1, 0, 0);
+        assert!(PathwayNetwork::read_binary(future.as_slice()).is_err());
+
+        // two pathways sharing a gene range
+        let mut bytes = header(BINARY_VERSION, 2, 2);
+        for name in ["P1", "P2"] {
+            bytes.extend((name.len() as u64).to_le_bytes());
+            bytes.extend(name.as_bytes());
+        }
+        for value in [0u64, 0, 2, 1, 0, 1] {
+            bytes.extend(value.to_le_bytes());
+        }
+        for weight in [1.0f32, 1.0] {
+            bytes.extend(weight.to_le_bytes());
+        }
+        // neither feature names nor edge signs
+        bytes.extend([0, 0]);
+        let err = PathwayNetwork::read_binary(bytes.as_slice()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<NetworkError>(),
+            Some(&NetworkError::OverlappingPathways {
+                first: 1,
+                second: 0
+            })
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use super::GenericPathwayNetwork;
+use crate::traits::{FloatOps, UIndex};
+use std::iter::FusedIterator;
+
+/// Borrowing iterator over the pathways of a `PathwayNetwork`.
+///
+/// Yields `(name, genes, weights)` for every pathway in order.
+#[derive(Clone)]
+pub struct PathwayIter<'a, W = f32, I = usize> {
+    network: &'a GenericPathwayNetwork<W, I>,
+    idx: usize,
+}
+
+impl<'a, W: FloatOps, I: UIndex> Iterator for PathwayIter<'a, W, I> {
+    type Item = (&'a str, &'a [I], &'a [W]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.idx >= self.network.get_num_pathways() {
+            return None;
+        }
+        let idx = self.idx;
+        self.idx += 1;
+        let (genes, weights) = self.network.get_pathway_features_and_weights(idx);
+        Some((self.network.get_pathway_name(idx), genes, weights))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.network.get_num_pathways() - self.idx;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<W: FloatOps, I: UIndex> ExactSizeIterator for PathwayIter<'_, W, I> {}
+
+impl<W: FloatOps, I: UIndex> FusedIterator for PathwayIter<'_, W, I> {}
+
+/// Owning iterator over the pathways of a `PathwayNetwork`.
+///
+/// Yields `(name, genes, weights)` for every pathway in order.
+#[derive(Debug)]
+pub struct PathwayIntoIter<W = f32, I = usize> {
+    names: std::vec::IntoIter<String>,
+    starts: Vec<I>,
+    offsets: Vec<I>,
+    cnct: Vec<I>,
+    weights: Vec<W>,
+    idx: usize,
+}
+
+impl<W: FloatOps, I: UIndex> Iterator for PathwayIntoIter<W, I> {
+    type Item = (String, Vec<I>, Vec<W>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let name = self.names.next()?;
+        let start = self.starts[self.idx].to_index();
+        let end = start + self.offsets[self.idx].to_index();
+        self.idx += 1;
+        Some((
+            name,
+            self.cnct[start..end].to_vec(),
+            self.weights[start..end].to_vec(),
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.names.size_hint()
+    }
+}
+
+impl<W: FloatOps, I: UIndex> ExactSizeIterator for PathwayIntoIter<W, I> {}
+
+impl<W: FloatOps, I: UIndex> FusedIterator for PathwayIntoIter<W, I> {}
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Iterates over all pathways as `(name, genes, weights)`.
+    pub fn iter(&self) -> PathwayIter<'_, W, I> {
+        PathwayIter {
+            network: self,
+            idx: 0,
+        }
+    }
+}
+
+impl<'a, W: FloatOps, I: UIndex> IntoIterator for &'a GenericPathwayNetwork<W, I> {
+    type Item = (&'a str, &'a [I], &'a [W]);
+    type IntoIter = PathwayIter<'a, W, I>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<W: FloatOps, I: UIndex> IntoIterator for GenericPathwayNetwork<W, I> {
+    type Item = (String, Vec<I>, Vec<W>);
+    type IntoIter = PathwayIntoIter<W, I>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        PathwayIntoIter {
+            names: self.names.into_iter(),
+            starts: self.starts,
+            offsets: self.offsets,
+            cnct: self.cnct,
+            weights: self.weights,
+            idx: 0,
+        }
+    }
+}
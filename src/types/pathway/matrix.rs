@@ -0,0 +1,70 @@
+use super::GenericPathwayNetwork;
+use crate::traits::{FloatOps, UIndex};
+use crate::types::CsrData;
+use anyhow::anyhow;
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Converts the network into a sparse pathway × gene matrix.
+    ///
+    /// Row `i` holds the genes of pathway `i` as column indices and their weights as
+    /// values, so pathway scores for an expression matrix are a sparse-dense product.
+    ///
+    /// # Arguments
+    /// * `n_features` - The number of columns, i.e. the size of the feature space
+    ///
+    /// # Returns
+    /// The CSR matrix, or an error if a gene index is not below `n_features`
+    pub fn to_csr(&self, n_features: usize) -> anyhow::Result<CsrData<W, I>> {
+        let mut indptr = Vec::with_capacity(self.get_num_pathways() + 1);
+        let mut indices = Vec::with_capacity(self.cnct.len());
+        let mut data = Vec::with_capacity(self.weights.len());
+        indptr.push(0);
+        for (_, genes, weights) in self.iter() {
+            indices.extend_from_slice(genes);
+            data.extend_from_slice(weights);
+            indptr.push(indices.len());
+        }
+        CsrData::new(self.get_num_pathways(), n_features, indptr, indices, data)
+    }
+
+    /// Builds a network from a sparse pathway × gene matrix.
+    ///
+    /// Every row becomes a pathway whose genes are the stored column indices and
+    /// whose weights are the stored values.
+    ///
+    /// # Arguments
+    /// * `matrix` - The pathway × gene matrix
+    /// * `names` - One name per row of `matrix`
+    ///
+    /// # Returns
+    /// The network, or an error if the number of names does not match the number of rows
+    pub fn from_csr(matrix: &CsrData<W, I>, names: Vec<String>) -> anyhow::Result<Self> {
+        if names.len() != matrix.indptr().len() - 1 {
+            return Err(anyhow!(
+                "Expected {} pathway names, got {}!",
+                matrix.indptr().len() - 1,
+                names.len()
+            ));
+        }
+        let indptr = matrix.indptr();
+        let to_index = |v: usize| {
+            I::try_from_index(v)
+                .ok_or_else(|| anyhow!("Value {} exceeds the range of the index type!", v))
+        };
+        let starts = indptr[..indptr.len() - 1]
+            .iter()
+            .map(|&v| to_index(v))
+            .collect::<anyhow::Result<Vec<I>>>()?;
+        let offsets = indptr
+            .windows(2)
+            .map(|w| to_index(w[1] - w[0]))
+            .collect::<anyhow::Result<Vec<I>>>()?;
+        Ok(Self::new(
+            names,
+            starts,
+            offsets,
+            matrix.indices().to_vec(),
+            matrix.data().to_vec(),
+        ))
+    }
+}
@@ -0,0 +1,759 @@
+use crate::traits::{FloatOps, FloatOpsTS, UIndex};
+use crate::utils::parallel::{concat, map_vec};
+use crate::utils::{
+    EdgeGroups, EdgeStream, SymbolCase, ValidationOptions, ValidationReport, WeightPolicy,
+    validate_net,
+};
+use anyhow::anyhow;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ops::Range;
+
+mod builder;
+mod dot;
+mod enrichment;
+mod index;
+mod io;
+mod iter;
+mod matrix;
+mod mutate;
+mod null;
+mod ops;
+mod overlap;
+mod prune;
+#[cfg(feature = "ndarray")]
+mod scoring;
+#[cfg(feature = "serde")]
+mod serialization;
+mod signed;
+mod summary;
+mod validate;
+mod weights;
+
+pub use builder::PathwayNetworkBuilder;
+pub use dot::{DotGraph, DotOptions};
+pub use enrichment::{GseaResult, OraResult};
+pub use index::GeneIndex;
+pub use iter::{PathwayIntoIter, PathwayIter};
+pub use ops::{ConflictPolicy, SubsetReport, WeightCombination};
+pub use overlap::OverlapMetric;
+#[cfg(feature = "ndarray")]
+pub use scoring::{ScoringMethod, ScoringOptions};
+pub use signed::{EdgeSign, SignedTargets};
+pub use summary::NetworkSummary;
+pub use validate::NetworkError;
+pub use weights::{TieMethod, WeightNormalization};
+
+/// Policy applied when a gene of a pathway is not part of the feature space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MissingFeaturePolicy {
+    /// Abort construction with an error naming the missing gene
+    Error,
+    /// Silently drop the gene from the pathway
+    Skip,
+    /// Drop the gene from the pathway and list it in the `ConstructionReport` of
+    /// constructors returning one; behaves like `Skip` elsewhere
+    SkipAndReport,
+}
+
+/// Policy applied when the same gene is assigned to a pathway more than once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Abort construction with an error naming the duplicated gene
+    Error,
+    /// Keep the weight of the first occurrence
+    First,
+    /// Keep the weight of the last occurrence
+    Last,
+    /// Sum the weights of all occurrences
+    Sum,
+    /// Average the weights of all occurrences
+    Mean,
+    /// Keep the weight with the largest absolute value
+    MaxAbs,
+}
+
+/// Options controlling how `GenericPathwayNetwork::try_new_from_vec` and
+/// `GenericPathwayNetwork::from_edges` build a network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkOptions {
+    /// Minimum number of genes a pathway needs to be kept
+    pub min_size: usize,
+    /// Maximum number of genes a pathway may have to be kept, or `None` for no limit
+    pub max_size: Option<usize>,
+    /// Apply the size filter to the distinct targets of the edge list, including those
+    /// missing from the feature space, instead of to the genes left after mapping;
+    /// this reproduces the behavior of earlier versions
+    pub filter_before_mapping: bool,
+    /// How targets missing from the feature space are handled
+    pub missing: MissingFeaturePolicy,
+    /// Duplicate resolution and symbol normalization applied to the edge list
+    pub validation: ValidationOptions,
+}
+
+impl NetworkOptions {
+    fn accepts_size(&self, size: usize) -> bool {
+        size >= self.min_size && self.max_size.is_none_or(|max| size <= max)
+    }
+}
+
+impl Default for NetworkOptions {
+    fn default() -> Self {
+        Self {
+            min_size: 0,
+            max_size: None,
+            filter_before_mapping: false,
+            missing: MissingFeaturePolicy::Error,
+            validation: ValidationOptions::default(),
+        }
+    }
+}
+
+/// Summary of what was dropped while building a network from an edge list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConstructionReport {
+    /// Genes missing from the feature space per pathway, in pathway order; only
+    /// filled under `MissingFeaturePolicy::SkipAndReport`
+    pub dropped_genes: Vec<(String, Vec<String>)>,
+    /// Names of the pathways removed by the size filter
+    pub dropped_pathways: Vec<String>,
+    /// Anomalies found while validating the edge list; only filled by
+    /// `try_new_from_vec` and `from_edges`
+    pub validation: ValidationReport,
+}
+
+impl ConstructionReport {
+    /// Returns the total number of dropped genes over all pathways.
+    pub fn num_dropped_genes(&self) -> usize {
+        self.dropped_genes
+            .iter()
+            .map(|(_, genes)| genes.len())
+            .sum()
+    }
+}
+
+/// A compact collection of weighted gene sets, generic over the weight and index types.
+///
+/// All pathways share one packed gene buffer: pathway `i` occupies
+/// `starts[i]..starts[i] + offsets[i]` of the gene indices and weights. Most code
+/// should use the `PathwayNetwork` alias with `f32` weights and `usize` indices;
+/// `f64` networks avoid conversions in double-precision pipelines and `u32` indices
+/// halve the memory of very large collections.
+pub struct GenericPathwayNetwork<W = f32, I = usize> {
+    names: Vec<String>,                 // name of pathways
+    starts: Vec<I>,                     // start of pathway
+    offsets: Vec<I>,                    // length of pathway
+    cnct: Vec<I>,                       // gene index of pathway
+    weights: Vec<W>,                    // weight of each gene in the pathway
+    name_index: HashMap<String, usize>, // pathway index by name
+    feature_names: Option<Vec<String>>, // names of the indexed features, if known
+    signs: Option<Vec<EdgeSign>>,       // sign of each gene once the weights no longer carry it
+}
+
+/// Networks are equal if they hold the same pathways in the same order with the same
+/// genes, weights, recorded edge signs and feature names.
+impl<W: PartialEq, I: PartialEq> PartialEq for GenericPathwayNetwork<W, I> {
+    fn eq(&self, other: &Self) -> bool {
+        self.names == other.names
+            && self.starts == other.starts
+            && self.offsets == other.offsets
+            && self.cnct == other.cnct
+            && self.weights == other.weights
+            && self.feature_names == other.feature_names
+            && self.signs == other.signs
+    }
+}
+
+/// A pathway network with `f32` weights and `usize` indices.
+pub type PathwayNetwork = GenericPathwayNetwork<f32>;
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    pub fn new(
+        names: Vec<String>,
+        starts: Vec<I>,
+        offsets: Vec<I>,
+        cnct: Vec<I>,
+        weights: Vec<W>,
+    ) -> Self {
+        let name_index = Self::build_name_index(&names);
+        let network = Self {
+            names,
+            starts,
+            offsets,
+            cnct,
+            weights,
+            name_index,
+            feature_names: None,
+            signs: None,
+        };
+        #[cfg(debug_assertions)]
+        if let Err(e) = network.validate_layout() {
+            panic!("Inconsistent pathway network: {}", e);
+        }
+        network
+    }
+
+    pub fn new_wo_weights(
+        names: Vec<String>,
+        starts: Vec<I>,
+        offsets: Vec<I>,
+        cnct: Vec<I>,
+    ) -> Self {
+        let weights = vec![W::one(); cnct.len()];
+        Self::new(names, starts, offsets, cnct, weights)
+    }
+
+    /// Builds a network from `usize` buffers, converting them to the index type.
+    ///
+    /// # Returns
+    /// The network, or an error if a start, length or gene index does not fit into `I`
+    /// or the buffers do not describe a consistent layout
+    pub(crate) fn from_usize_parts(
+        names: Vec<String>,
+        starts: Vec<usize>,
+        offsets: Vec<usize>,
+        cnct: Vec<usize>,
+        weights: Vec<W>,
+    ) -> anyhow::Result<Self> {
+        let convert = |values: Vec<usize>| -> anyhow::Result<Vec<I>> {
+            values
+                .into_iter()
+                .map(|v| {
+                    I::try_from_index(v)
+                        .ok_or_else(|| anyhow!("Value {} exceeds the range of the index type!", v))
+                })
+                .collect()
+        };
+        let network = Self {
+            name_index: Self::build_name_index(&names),
+            names,
+            starts: convert(starts)?,
+            offsets: convert(offsets)?,
+            cnct: convert(cnct)?,
+            weights,
+            feature_names: None,
+            signs: None,
+        };
+        network.validate_layout()?;
+        Ok(network)
+    }
+
+    /// Maps every pathway name to its index; the first pathway wins if a name is repeated.
+    fn build_name_index(names: &[String]) -> HashMap<String, usize> {
+        let mut index = HashMap::with_capacity(names.len());
+        for (idx, name) in names.iter().enumerate() {
+            index.entry(name.clone()).or_insert(idx);
+        }
+        index
+    }
+
+    pub fn get_pathway_name(&self, idx: usize) -> &str {
+        self.names[idx].as_str()
+    }
+
+    pub fn get_pathway_features(&self, idx: usize) -> &[I] {
+        &self.cnct[self.pathway_range(idx)]
+    }
+
+    pub fn get_pathway_features_and_weights(&self, idx: usize) -> (&[I], &[W]) {
+        let range = self.pathway_range(idx);
+        (&self.cnct[range.clone()], &self.weights[range])
+    }
+
+    /// Returns the positions of the genes of a pathway in the packed buffers.
+    pub(crate) fn pathway_range(&self, idx: usize) -> Range<usize> {
+        let start = self.starts[idx].to_index();
+        start..start + self.offsets[idx].to_index()
+    }
+
+    pub fn get_num_pathways(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Attaches the names of the features the gene indices refer to.
+    ///
+    /// Networks built from gene names (`new_from_vec`, `from_gmt`, `from_net_tsv`)
+    /// store their feature names automatically.
+    ///
+    /// # Returns
+    /// An error if a gene index of the network has no entry in `feature_names`
+    pub fn set_feature_names(&mut self, feature_names: Vec<String>) -> anyhow::Result<()> {
+        if let Some(&gene) = self
+            .cnct
+            .iter()
+            .find(|g| g.to_index() >= feature_names.len())
+        {
+            return Err(anyhow!(
+                "Gene index {:?} has no entry in the {} feature names!",
+                gene,
+                feature_names.len()
+            ));
+        }
+        self.feature_names = Some(feature_names);
+        Ok(())
+    }
+
+    /// Removes the stored feature names and returns them.
+    pub fn take_feature_names(&mut self) -> Option<Vec<String>> {
+        self.feature_names.take()
+    }
+
+    /// Returns the stored feature names, if any.
+    pub fn feature_names(&self) -> Option<&[String]> {
+        self.feature_names.as_deref()
+    }
+
+    /// Returns the names of the genes of a pathway, in the order of `get_pathway_features`.
+    ///
+    /// # Returns
+    /// The gene names, or `None` if the network has no feature names attached
+    pub fn get_pathway_feature_names(&self, idx: usize) -> Option<Vec<&str>> {
+        let feature_names = self.feature_names.as_ref()?;
+        Some(
+            self.get_pathway_features(idx)
+                .iter()
+                .map(|g| feature_names[g.to_index()].as_str())
+                .collect(),
+        )
+    }
+
+    /// Returns the index of the pathway with the given name.
+    ///
+    /// The lookup is backed by a hash map built at construction. If several pathways
+    /// share a name, the index of the first one is returned.
+    pub fn get_pathway_index(&self, name: &str) -> Option<usize> {
+        self.name_index.get(name).copied()
+    }
+
+    /// Returns the gene indices of the pathway with the given name.
+    pub fn get_pathway_features_by_name(&self, name: &str) -> Option<&[I]> {
+        self.get_pathway_index(name)
+            .map(|idx| self.get_pathway_features(idx))
+    }
+
+    /// Returns the gene indices and weights of the pathway with the given name.
+    pub fn get_pathway_by_name(&self, name: &str) -> Option<(&[I], &[W])> {
+        self.get_pathway_index(name)
+            .map(|idx| self.get_pathway_features_and_weights(idx))
+    }
+}
+
+impl<W: FloatOpsTS, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Builds a network from an edge list of pathway sources and gene targets.
+    ///
+    /// Targets listed more than once for the same pathway keep their last weight,
+    /// edges with a NaN or infinite weight are dropped and pathways with fewer than
+    /// `tmin` genes left after mapping onto `features` are dropped. Pathways are kept
+    /// in order of first appearance of their source. An empty edge list gives an empty
+    /// network.
+    ///
+    /// # Panics
+    /// Panics if the source, target and weight columns differ in length or a target is
+    /// not part of `features`; use `try_new_from_vec` to handle these gracefully.
+    pub fn new_from_vec(
+        sources: Vec<String>,
+        targets: Vec<String>,
+        weights: Option<Vec<W>>,
+        features: Vec<String>,
+        tmin: u32,
+    ) -> Self {
+        let options = NetworkOptions {
+            min_size: tmin as usize,
+            validation: ValidationOptions {
+                weights: WeightPolicy::Drop,
+                ..ValidationOptions::default()
+            },
+            ..NetworkOptions::default()
+        };
+        match Self::try_new_from_vec(sources, targets, weights, features, &options) {
+            Ok((network, _)) => network,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    /// Builds a network from an edge list of pathway sources and gene targets.
+    ///
+    /// The edge list is cleaned by `validate_net` according to `options.validation`,
+    /// which resolves duplicated targets and normalizes symbols; the feature names are
+    /// matched after the same case normalization. After the targets are mapped onto
+    /// `features`, pathways whose number of genes lies outside `options.min_size` and
+    /// `options.max_size` are dropped; with `options.filter_before_mapping` the size
+    /// filter is applied to the resolved targets before mapping instead. The edge list
+    /// does not need to be ordered; pathways are kept in order of first appearance of
+    /// their source, so the network is identical across runs and thread counts. The
+    /// targets are resolved, mapped and packed in parallel when the `rayon` feature is
+    /// enabled.
+    ///
+    /// # Arguments
+    /// * `sources` - The pathway name of every edge
+    /// * `targets` - The gene name of every edge
+    /// * `weights` - The weight of every edge, or `None` to weight every edge with 1
+    /// * `features` - The feature names of the dataset; stored in the network
+    /// * `options` - Size filter, missing-feature policy and edge list validation
+    ///
+    /// # Returns
+    /// The network and a report of the dropped genes and pathways, or an error if the
+    /// edge list is malformed, a target is missing under `MissingFeaturePolicy::Error`
+    /// or duplicated under `DuplicatePolicy::Error`. Errors of the edge list validation
+    /// are `NetworkValidationError`s that can be recovered with `downcast_ref`; call
+    /// `validate_net` directly to match on them without `anyhow`.
+    pub fn try_new_from_vec(
+        sources: Vec<String>,
+        targets: Vec<String>,
+        weights: Option<Vec<W>>,
+        features: Vec<String>,
+        options: &NetworkOptions,
+    ) -> anyhow::Result<(Self, ConstructionReport)> {
+        let (groups, validation) =
+            validate_net(&sources, &targets, weights.as_deref(), &options.validation)?;
+        drop((sources, targets, weights));
+        Self::from_edge_groups(groups, validation, features, options)
+    }
+
+    /// Builds a network in a single pass over an edge iterator.
+    ///
+    /// Unlike `try_new_from_vec` the edges do not need to be collected into separate
+    /// source, target and weight vectors first, which keeps the peak memory low for
+    /// edge lists streamed from files or database cursors. The edges are cleaned and
+    /// grouped as they arrive by the same rules as `validate_net`, and the groups are then mapped onto `features` like in
+    /// `try_new_from_vec`, so both constructors build the same network and report
+    /// from the same edge list.
+    ///
+    /// # Arguments
+    /// * `edges` - `(source, target, weight)` triples; a `None` weight counts as 1
+    /// * `features` - The feature names of the dataset; stored in the network
+    /// * `options` - Size filter, missing-feature policy and edge list validation
+    ///
+    /// # Returns
+    /// The network and a report of the validation anomalies and the dropped genes and
+    /// pathways, or an error if the edge list is rejected by `options.validation` or a
+    /// target is missing under `MissingFeaturePolicy::Error`. Errors of the edge list
+    /// validation are `NetworkValidationError`s that can be recovered with
+    /// `downcast_ref`.
+    pub fn from_edges<S, E>(
+        edges: E,
+        features: Vec<String>,
+        options: &NetworkOptions,
+    ) -> anyhow::Result<(Self, ConstructionReport)>
+    where
+        S: AsRef<str>,
+        E: IntoIterator<Item = (S, S, Option<W>)>,
+    {
+        let mut stream = EdgeStream::new(&options.validation);
+        for (source, target, weight) in edges {
+            stream.push(
+                source.as_ref(),
+                target.as_ref(),
+                weight.unwrap_or_else(W::one),
+            )?;
+        }
+        let (groups, validation) = stream.finish()?;
+        Self::from_edge_groups(groups, validation, features, options)
+    }
+
+    /// Maps the validated edge groups onto `features` and packs them into a network.
+    pub(crate) fn from_edge_groups(
+        groups: EdgeGroups<W>,
+        validation: ValidationReport,
+        features: Vec<String>,
+        options: &NetworkOptions,
+    ) -> anyhow::Result<(Self, ConstructionReport)> {
+        let mut report = ConstructionReport {
+            validation,
+            ..ConstructionReport::default()
+        };
+        let mut filtered: Vec<(String, Vec<(String, W)>)> = Vec::with_capacity(groups.len());
+        for (k, v) in groups {
+            if !options.filter_before_mapping || options.accepts_size(v.len()) {
+                filtered.push((k, v));
+            } else {
+                report.dropped_pathways.push(k);
+            }
+        }
+
+        let name_to_id = feature_lookup(&features, options.validation.case);
+
+        let mapped = map_vec(filtered, |(k, v)| {
+            let mut genes: Vec<usize> = Vec::with_capacity(v.len());
+            let mut gene_weights: Vec<W> = Vec::with_capacity(v.len());
+            let mut dropped: Vec<String> = Vec::new();
+            for (g_name, g_weight) in v {
+                match name_to_id.get(g_name.as_str()) {
+                    Some(&g_idx) => {
+                        genes.push(g_idx);
+                        gene_weights.push(g_weight);
+                    }
+                    None => match options.missing {
+                        MissingFeaturePolicy::Error => {
+                            return Err(anyhow!(
+                                "Gene '{}' of pathway '{}' is not part of the feature space!",
+                                g_name,
+                                k
+                            ));
+                        }
+                        MissingFeaturePolicy::Skip => {}
+                        MissingFeaturePolicy::SkipAndReport => dropped.push(g_name),
+                    },
+                }
+            }
+            Ok((k, genes, gene_weights, dropped))
+        });
+
+        let num_pathways = mapped.len();
+        let mut names: Vec<String> = Vec::with_capacity(num_pathways);
+        let mut starts: Vec<usize> = Vec::with_capacity(num_pathways);
+        let mut offsets: Vec<usize> = Vec::with_capacity(num_pathways);
+        let mut gene_parts: Vec<Vec<usize>> = Vec::with_capacity(num_pathways);
+        let mut weight_parts: Vec<Vec<W>> = Vec::with_capacity(num_pathways);
+        let mut total_length = 0usize;
+        for group in mapped {
+            let (k, genes, gene_weights, dropped) = group?;
+            if !options.filter_before_mapping && !options.accepts_size(genes.len()) {
+                report.dropped_pathways.push(k);
+                continue;
+            }
+            if !dropped.is_empty() {
+                report.dropped_genes.push((k.clone(), dropped));
+            }
+            names.push(k);
+            starts.push(total_length);
+            offsets.push(genes.len());
+            total_length += genes.len();
+            gene_parts.push(genes);
+            weight_parts.push(gene_weights);
+        }
+        let cnct = concat(&gene_parts);
+        drop(gene_parts);
+        let weights_vec = concat(&weight_parts);
+        drop(weight_parts);
+
+        let mut network = Self::from_usize_parts(names, starts, offsets, cnct, weights_vec)?;
+        network.feature_names = Some(features);
+        Ok((network, report))
+    }
+}
+
+/// Maps every feature name, normalized according to `case`, to its index.
+pub(crate) fn feature_lookup(
+    features: &[String],
+    case: SymbolCase,
+) -> HashMap<Cow<'_, str>, usize> {
+    features
+        .iter()
+        .enumerate()
+        .map(|(idx, name)| (case.apply(name), idx))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{NetworkValidationError, SelfLoopPolicy};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn new_from_vec_accepts_an_empty_edge_list() {
+        let network =
+            PathwayNetwork::new_from_vec(Vec::new(), Vec::new(), None, names(&["g1", "g2"]), 0);
+        assert_eq!(network.get_num_pathways(), 0);
+        assert_eq!(network.feature_names(), Some(&names(&["g1", "g2"])[..]));
+    }
+
+    #[test]
+    fn constructors_accept_an_empty_edge_list() {
+        let features = names(&["g1", "g2"]);
+        let options = NetworkOptions::default();
+        let (network, report) = PathwayNetwork::try_new_from_vec(
+            Vec::new(),
+            Vec::new(),
+            Some(Vec::new()),
+            features.clone(),
+            &options,
+        )
+        .unwrap();
+        assert_eq!(network.get_num_pathways(), 0);
+        assert_eq!(report, ConstructionReport::default());
+
+        let edges: Vec<(&str, &str, Option<f32>)> = Vec::new();
+        let (streamed, report) =
+            PathwayNetwork::from_edges(edges, features.clone(), &options).unwrap();
+        assert_eq!(streamed, network);
+        assert_eq!(report, ConstructionReport::default());
+    }
+
+    #[test]
+    fn new_from_vec_drops_non_finite_weights() {
+        let network = PathwayNetwork::new_from_vec(
+            names(&["A", "A", "A"]),
+            names(&["g1", "g2", "g3"]),
+            Some(vec![1.0, f32::NAN, f32::INFINITY]),
+            names(&["g1", "g2", "g3"]),
+            0,
+        );
+        assert_eq!(
+            network.get_pathway_features_and_weights(0),
+            (&[0usize][..], &[1.0f32][..])
+        );
+    }
+
+    #[test]
+    fn try_new_from_vec_keeps_typed_validation_errors() {
+        let err = PathwayNetwork::try_new_from_vec(
+            names(&["A"]),
+            names(&["g1"]),
+            Some(vec![f32::NAN]),
+            names(&["g1"]),
+            &NetworkOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<NetworkValidationError>(),
+            Some(&NetworkValidationError::NonFiniteWeight { row: 0 })
+        );
+    }
+
+    #[test]
+    fn from_edges_applies_weight_and_self_loop_policies() {
+        let edges = vec![
+            ("A", "g1", Some(1.0)),
+            ("A", "g2", Some(f32::INFINITY)),
+            ("A", "A", Some(2.0)),
+            ("B", "g1", Some(f32::NAN)),
+            ("B", "g2", Some(-3.0)),
+            ("B", "", None),
+        ];
+        let options = NetworkOptions {
+            validation: ValidationOptions {
+                weights: WeightPolicy::Clamp,
+                self_loops: SelfLoopPolicy::Drop,
+                ..ValidationOptions::default()
+            },
+            ..NetworkOptions::default()
+        };
+        let (network, report) =
+            PathwayNetwork::from_edges(edges, names(&["g1", "g2", "A"]), &options).unwrap();
+        assert_eq!(
+            network.get_pathway_features_and_weights(0),
+            (&[0usize, 1][..], &[1.0f32, 2.0][..])
+        );
+        assert_eq!(
+            network.get_pathway_features_and_weights(1),
+            (&[1usize][..], &[-3.0f32][..])
+        );
+        assert_eq!(
+            report.validation,
+            ValidationReport {
+                num_edges: 6,
+                num_sources: 2,
+                self_loops: 1,
+                self_loops_dropped: 1,
+                empty_names_dropped: 1,
+                negative_weights: 1,
+                non_finite_weights: 2,
+                non_finite_dropped: 1,
+                ..ValidationReport::default()
+            }
+        );
+    }
+
+    #[test]
+    fn from_edges_keeps_typed_validation_errors() {
+        let err = PathwayNetwork::from_edges(
+            vec![("A", "g1", Some(1.0)), ("A", "g2", Some(f32::NAN))],
+            names(&["g1", "g2"]),
+            &NetworkOptions::default(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<NetworkValidationError>(),
+            Some(&NetworkValidationError::NonFiniteWeight { row: 1 })
+        );
+
+        let options = NetworkOptions {
+            validation: ValidationOptions {
+                self_loops: SelfLoopPolicy::Error,
+                ..ValidationOptions::default()
+            },
+            ..NetworkOptions::default()
+        };
+        let err = PathwayNetwork::from_edges(vec![("g1", "g1", None)], names(&["g1"]), &options)
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<NetworkValidationError>(),
+            Some(&NetworkValidationError::SelfLoop {
+                row: 0,
+                name: "g1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn from_edges_resolves_duplicates_like_try_new_from_vec() {
+        let edges = [
+            ("A", "g1", 1.0),
+            ("A", "x", 2.0),
+            ("A", "g1", 3.0),
+            ("A", "x", 4.0),
+        ];
+        let strict = NetworkOptions {
+            validation: ValidationOptions {
+                duplicates: DuplicatePolicy::Error,
+                ..ValidationOptions::default()
+            },
+            missing: MissingFeaturePolicy::SkipAndReport,
+            ..NetworkOptions::default()
+        };
+        let from_edges = |edges: &[(&str, &str, f32)], options: &NetworkOptions| {
+            PathwayNetwork::from_edges(
+                edges.iter().map(|&(s, t, w)| (s, t, Some(w))),
+                names(&["g1"]),
+                options,
+            )
+        };
+        let from_vec = |edges: &[(&str, &str, f32)], options: &NetworkOptions| {
+            PathwayNetwork::try_new_from_vec(
+                edges.iter().map(|e| e.0.to_string()).collect(),
+                edges.iter().map(|e| e.1.to_string()).collect(),
+                Some(edges.iter().map(|e| e.2).collect()),
+                names(&["g1"]),
+                options,
+            )
+        };
+
+        // a duplicated target and a duplicated missing target are both rejected
+        for edges in [&edges[..3], &[edges[1], edges[3]][..]] {
+            let expected = NetworkValidationError::DuplicateEdge {
+                from: "A".to_string(),
+                to: edges[0].1.to_string(),
+            };
+            let err = from_edges(edges, &strict).unwrap_err();
+            assert_eq!(
+                err.downcast_ref::<NetworkValidationError>(),
+                Some(&expected)
+            );
+            let err = from_vec(edges, &strict).unwrap_err();
+            assert_eq!(
+                err.downcast_ref::<NetworkValidationError>(),
+                Some(&expected)
+            );
+        }
+
+        let last = NetworkOptions {
+            validation: ValidationOptions::default(),
+            ..strict
+        };
+        let (network, report) = from_edges(&edges, &last).unwrap();
+        assert_eq!(
+            network.get_pathway_features_and_weights(0),
+            (&[0usize][..], &[3.0f32][..])
+        );
+        assert_eq!(report.validation.duplicate_edges, 2);
+        assert_eq!(report.dropped_genes, vec![("A".to_string(), names(&["x"]))]);
+        let (expected, expected_report) = from_vec(&edges, &last).unwrap();
+        assert_eq!(network, expected);
+        assert_eq!(report, expected_report);
+    }
+}
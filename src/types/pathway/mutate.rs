@@ -0,0 +1,135 @@
+use super::{EdgeSign, GenericPathwayNetwork};
+use crate::traits::{FloatOps, UIndex};
+use anyhow::anyhow;
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Appends a pathway to the end of the network.
+    ///
+    /// The genes are appended to the packed buffers, so existing pathways keep their
+    /// indices. A pathway sharing the name of an existing one is added as well, but
+    /// name lookups keep resolving to the first of them.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the new pathway
+    /// * `genes` - The gene indices of the pathway
+    /// * `weights` - One weight per gene, or `None` to weight every gene with 1
+    ///
+    /// # Returns
+    /// The index of the new pathway, or an error if the number of weights does not
+    /// match the number of genes or a gene index does not fit into the index type or
+    /// the stored feature names
+    pub fn add_pathway(
+        &mut self,
+        name: &str,
+        genes: &[usize],
+        weights: Option<&[W]>,
+    ) -> anyhow::Result<usize> {
+        if let Some(weights) = weights
+            && weights.len() != genes.len()
+        {
+            return Err(anyhow!(
+                "Pathway '{}' has {} genes but {} weights!",
+                name,
+                genes.len(),
+                weights.len()
+            ));
+        }
+        if let Some(feature_names) = &self.feature_names
+            && let Some(&gene) = genes.iter().find(|&&g| g >= feature_names.len())
+        {
+            return Err(anyhow!(
+                "Gene index {} has no entry in the {} feature names!",
+                gene,
+                feature_names.len()
+            ));
+        }
+        let converted = genes
+            .iter()
+            .map(|&gene| {
+                I::try_from_index(gene).ok_or_else(|| {
+                    anyhow!("Gene index {} exceeds the range of the index type!", gene)
+                })
+            })
+            .collect::<anyhow::Result<Vec<I>>>()?;
+        let start = I::try_from_index(self.cnct.len())
+            .ok_or_else(|| anyhow!("Network exceeds the range of the index type!"))?;
+
+        let idx = self.names.len();
+        self.names.push(name.to_string());
+        self.starts.push(start);
+        self.offsets.push(I::from_index(genes.len()));
+        self.cnct.extend(converted);
+        match weights {
+            Some(weights) => self.weights.extend_from_slice(weights),
+            None => self
+                .weights
+                .extend(std::iter::repeat_n(W::one(), genes.len())),
+        }
+        if let Some(signs) = &mut self.signs {
+            let added = &self.weights[start.to_index()..];
+            signs.extend(added.iter().map(|&w| EdgeSign::of(w)));
+        }
+        self.name_index.entry(name.to_string()).or_insert(idx);
+        Ok(idx)
+    }
+
+    /// Removes the pathway at `idx` and compacts the packed buffers.
+    ///
+    /// Pathways after `idx` move down by one index.
+    ///
+    /// # Returns
+    /// The name, genes and weights of the removed pathway, or `None` if `idx` is out of range
+    pub fn remove_pathway(&mut self, idx: usize) -> Option<(String, Vec<I>, Vec<W>)> {
+        if idx >= self.get_num_pathways() {
+            return None;
+        }
+        let name = self.names[idx].clone();
+        let (genes, weights) = self.get_pathway_features_and_weights(idx);
+        let removed = (name, genes.to_vec(), weights.to_vec());
+        *self = self.select_pathways(|i, _, _| i != idx);
+        Some(removed)
+    }
+
+    /// Removes the pathway with the given name and compacts the packed buffers.
+    ///
+    /// If several pathways share the name, only the first one is removed.
+    pub fn remove_pathway_by_name(&mut self, name: &str) -> Option<(String, Vec<I>, Vec<W>)> {
+        let idx = self.get_pathway_index(name)?;
+        self.remove_pathway(idx)
+    }
+
+    /// Replaces the weights of the pathway at `idx` in place.
+    ///
+    /// If the network records edge signs, the signs of the pathway are taken from the
+    /// new weights.
+    ///
+    /// # Returns
+    /// An error if `idx` is out of range or the number of weights does not match the
+    /// number of genes of the pathway
+    pub fn set_weights(&mut self, idx: usize, weights: &[W]) -> anyhow::Result<()> {
+        if idx >= self.get_num_pathways() {
+            return Err(anyhow!(
+                "Pathway index {} is out of range for {} pathways!",
+                idx,
+                self.get_num_pathways()
+            ));
+        }
+        let start = self.starts[idx].to_index();
+        let len = self.offsets[idx].to_index();
+        if weights.len() != len {
+            return Err(anyhow!(
+                "Pathway '{}' has {} genes but {} weights were given!",
+                self.names[idx],
+                len,
+                weights.len()
+            ));
+        }
+        self.weights[start..start + len].copy_from_slice(weights);
+        if let Some(signs) = &mut self.signs {
+            for (sign, &weight) in signs[start..start + len].iter_mut().zip(weights) {
+                *sign = EdgeSign::of(weight);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+use super::GenericPathwayNetwork;
+use crate::random::{RngOps, sample_indices};
+use crate::traits::{FloatOps, UIndex};
+use std::collections::HashSet;
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Generates a network of random gene sets mirroring the sizes of this network.
+    ///
+    /// Every random set copies a template pathway drawn uniformly from the network:
+    /// it has the same size, weights and edge signs, with each gene replaced by a
+    /// random one. Genes are drawn from the feature space, which spans the stored feature
+    /// names or, without them, every index up to the largest gene of the network.
+    /// With `frequency_bins`, genes are grouped into that many equally sized bins by
+    /// the number of pathways containing them and every template gene is replaced by
+    /// a gene from its own bin, so promiscuous genes stay as frequent as in the
+    /// original collection. As the genes of a random set are distinct, sets are
+    /// capped at the size of the feature space, which templates with repeated genes
+    /// can exceed.
+    ///
+    /// # Arguments
+    /// * `n_sets` - Number of random sets to generate, named `random_0`, `random_1`, ...
+    /// * `frequency_bins` - Optional number of gene-frequency bins to match
+    /// * `rng` - The random number generator
+    ///
+    /// # Returns
+    /// A network of `n_sets` random pathways over the same feature space
+    pub fn random_size_matched<R: RngOps + ?Sized>(
+        &self,
+        n_sets: usize,
+        frequency_bins: Option<usize>,
+        rng: &mut R,
+    ) -> Self {
+        let n_features = match &self.feature_names {
+            Some(names) => names.len(),
+            None => self
+                .cnct
+                .iter()
+                .map(|g| g.to_index() + 1)
+                .max()
+                .unwrap_or(0),
+        };
+        let bins = frequency_bins.map(|n_bins| self.frequency_bins(n_features, n_bins));
+
+        let mut names = Vec::with_capacity(n_sets);
+        let mut starts = Vec::with_capacity(n_sets);
+        let mut offsets = Vec::with_capacity(n_sets);
+        let mut cnct = Vec::new();
+        let mut weights = Vec::new();
+        let mut positions = Vec::new();
+        if self.get_num_pathways() > 0 {
+            for i in 0..n_sets {
+                let template = rng.next_range(self.get_num_pathways());
+                let (genes, gene_weights) = self.get_pathway_features_and_weights(template);
+                let sampled: Vec<(usize, usize)> = match &bins {
+                    Some((bin_of, members)) => {
+                        sample_binned(genes, bin_of, members, n_features, rng)
+                    }
+                    None => sample_indices(rng, n_features, genes.len())
+                        .into_iter()
+                        .enumerate()
+                        .collect(),
+                };
+                names.push(format!("random_{}", i));
+                starts.push(cnct.len());
+                offsets.push(sampled.len());
+                let template_start = self.pathway_range(template).start;
+                for (pos, gene) in sampled {
+                    cnct.push(gene);
+                    weights.push(gene_weights[pos]);
+                    positions.push(template_start + pos);
+                }
+            }
+        }
+
+        let mut network = Self::from_usize_parts(names, starts, offsets, cnct, weights)
+            .expect("random genes lie inside the feature space of the network");
+        network.feature_names = self.feature_names.clone();
+        network.signs = self.gather_signs(positions);
+        network
+    }
+
+    /// Splits the feature space into `n_bins` equally sized bins of increasing pathway
+    /// frequency and returns the bin of every gene along with the members of every bin.
+    fn frequency_bins(&self, n_features: usize, n_bins: usize) -> (Vec<usize>, Vec<Vec<usize>>) {
+        let index = self.build_gene_index();
+        let mut order: Vec<usize> = (0..n_features).collect();
+        order.sort_by_key(|&g| (index.degree(g), g));
+
+        let n_bins = n_bins.clamp(1, n_features.max(1));
+        let mut bin_of = vec![0usize; n_features];
+        let mut members = vec![Vec::new(); n_bins];
+        for (pos, gene) in order.into_iter().enumerate() {
+            let bin = pos * n_bins / n_features;
+            bin_of[gene] = bin;
+            members[bin].push(gene);
+        }
+        (bin_of, members)
+    }
+}
+
+/// Replaces every template gene by a distinct random gene of the same frequency bin.
+///
+/// Bins with fewer members than required are topped up with random genes from the
+/// whole feature space. A template with more genes than the feature space, which is
+/// possible with repeated genes, keeps only as many positions as there are features.
+///
+/// # Returns
+/// The template position and random gene of every kept position, in template order
+fn sample_binned<I: UIndex, R: RngOps + ?Sized>(
+    template: &[I],
+    bin_of: &[usize],
+    members: &[Vec<usize>],
+    n_features: usize,
+    rng: &mut R,
+) -> Vec<(usize, usize)> {
+    let mut positions: Vec<Vec<usize>> = vec![Vec::new(); members.len()];
+    for (pos, gene) in template.iter().enumerate() {
+        positions[bin_of[gene.to_index()]].push(pos);
+    }
+
+    let mut sampled = vec![usize::MAX; template.len()];
+    let mut used: HashSet<usize> = HashSet::with_capacity(template.len());
+    let mut missing: Vec<usize> = Vec::new();
+    for (bin, bin_positions) in positions.iter().enumerate() {
+        let drawn = sample_indices(rng, members[bin].len(), bin_positions.len());
+        for (k, &pos) in bin_positions.iter().enumerate() {
+            match drawn.get(k) {
+                Some(&d) => {
+                    sampled[pos] = members[bin][d];
+                    used.insert(members[bin][d]);
+                }
+                None => missing.push(pos),
+            }
+        }
+    }
+    // only distinct genes can be drawn, so stop once the feature space is exhausted
+    missing.truncate(n_features - used.len());
+    for pos in missing {
+        let gene = loop {
+            let candidate = rng.next_range(n_features);
+            if used.insert(candidate) {
+                break candidate;
+            }
+        };
+        sampled[pos] = gene;
+    }
+    sampled
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, gene)| gene != usize::MAX)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::random::SeededRng;
+    use crate::types::PathwayNetwork;
+
+    #[test]
+    fn templates_larger_than_the_feature_space_are_capped() {
+        let network = PathwayNetwork::new(
+            vec!["P".to_string()],
+            vec![0],
+            vec![2],
+            vec![0, 0],
+            vec![1.0, 1.0],
+        );
+        for bins in [None, Some(1)] {
+            let random = network.random_size_matched(3, bins, &mut SeededRng::new(1));
+            assert_eq!(random.get_num_pathways(), 3);
+            for idx in 0..3 {
+                assert_eq!(random.get_pathway_features(idx), [0]);
+            }
+        }
+    }
+
+    #[test]
+    fn random_sets_match_template_sizes_with_distinct_genes() {
+        let network = PathwayNetwork::new(
+            vec!["P1".to_string(), "P2".to_string()],
+            vec![0, 3],
+            vec![3, 1],
+            vec![0, 1, 2, 5],
+            vec![1.0, 2.0, 3.0, 4.0],
+        );
+        let random = network.random_size_matched(20, Some(2), &mut SeededRng::new(3));
+        for idx in 0..random.get_num_pathways() {
+            let (genes, weights) = random.get_pathway_features_and_weights(idx);
+            assert!(weights == [1.0, 2.0, 3.0] || weights == [4.0]);
+            let mut distinct = genes.to_vec();
+            distinct.sort_unstable();
+            distinct.dedup();
+            assert_eq!(distinct.len(), genes.len());
+            assert!(genes.iter().all(|&g| g < 6));
+        }
+    }
+}
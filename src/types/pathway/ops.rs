@@ -0,0 +1,388 @@
+use super::{EdgeSign, GenericPathwayNetwork, PathwayNetworkBuilder};
+use crate::traits::{FloatOps, UIndex};
+use anyhow::anyhow;
+use std::collections::{HashMap, HashSet};
+
+/// Policy applied when networks being merged contain pathways with the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Abort the merge with an error naming the conflicting pathway
+    Error,
+    /// Keep all pathways, appending `_2`, `_3`, ... to later occurrences of a name
+    RenameWithSuffix,
+    /// Combine all pathways of the same name into one containing the union of their genes;
+    /// genes present in several of them keep the weight of their first occurrence
+    UnionGenes,
+    /// Keep only the first pathway with a given name
+    KeepFirst,
+}
+
+/// How the weights of a gene present in two networks are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightCombination {
+    /// Keep the weight from the left-hand network
+    Left,
+    /// Keep the weight from the right-hand network
+    Right,
+    /// Arithmetic mean of both weights
+    Mean,
+    /// Smaller of both weights
+    Min,
+    /// Larger of both weights
+    Max,
+    /// Sum of both weights
+    Sum,
+    /// Product of both weights
+    Product,
+}
+
+impl WeightCombination {
+    fn combine<W: FloatOps>(self, left: W, right: W) -> W {
+        match self {
+            Self::Left => left,
+            Self::Right => right,
+            Self::Mean => (left + right) / (W::one() + W::one()),
+            Self::Min => num_traits::Float::min(left, right),
+            Self::Max => num_traits::Float::max(left, right),
+            Self::Sum => left + right,
+            Self::Product => left * right,
+        }
+    }
+}
+
+/// Summary of how a network was mapped onto a new feature space by `subset_to_features`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SubsetReport {
+    /// Number of (pathway, gene) memberships carried over to the new feature space
+    pub kept_memberships: usize,
+    /// Number of (pathway, gene) memberships dropped because the gene is missing
+    pub dropped_memberships: usize,
+    /// Names of the genes used by the network that are missing from the new features
+    pub missing_features: Vec<String>,
+    /// Names of the pathways removed by the size filter after remapping
+    pub dropped_pathways: Vec<String>,
+}
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Returns a new network containing only pathways with between `min` and `max`
+    /// genes (both inclusive).
+    ///
+    /// Pass `usize::MAX` as `max` to disable the upper bound.
+    pub fn filter_by_size(&self, min: usize, max: usize) -> Self {
+        self.select_pathways(|_, genes, _| (min..=max).contains(&genes.len()))
+    }
+
+    /// Drops all pathways with fewer than `min` or more than `max` genes in place and
+    /// compacts the gene buffers.
+    ///
+    /// Pass `usize::MAX` as `max` to disable the upper bound.
+    pub fn retain_by_size(&mut self, min: usize, max: usize) {
+        *self = self.filter_by_size(min, max);
+    }
+
+    /// Builds a compacted network from the pathways for which `keep` returns `true`.
+    ///
+    /// `keep` receives the pathway index, its genes and its weights.
+    pub(crate) fn select_pathways<F>(&self, mut keep: F) -> Self
+    where
+        F: FnMut(usize, &[I], &[W]) -> bool,
+    {
+        let mut names = Vec::new();
+        let mut starts = Vec::new();
+        let mut offsets = Vec::new();
+        let mut cnct = Vec::new();
+        let mut weights = Vec::new();
+        let mut kept = Vec::new();
+
+        for (idx, (name, genes, gene_weights)) in self.iter().enumerate() {
+            if !keep(idx, genes, gene_weights) {
+                continue;
+            }
+            kept.push(idx);
+            names.push(name.to_string());
+            starts.push(I::from_index(cnct.len()));
+            offsets.push(I::from_index(genes.len()));
+            cnct.extend_from_slice(genes);
+            weights.extend_from_slice(gene_weights);
+        }
+
+        let mut network = Self::new(names, starts, offsets, cnct, weights);
+        network.feature_names = self.feature_names.clone();
+        network.signs = self.gather_signs(kept.into_iter().flat_map(|idx| self.pathway_range(idx)));
+        network
+    }
+
+    /// Maps the network onto a different feature space.
+    ///
+    /// Genes are translated to names through `old_feature_names` and looked up in
+    /// `new_features`; genes missing from the new feature space are dropped and all
+    /// remaining indices are remapped. Pathways outside `size_filter` (inclusive
+    /// minimum and maximum size after remapping) are dropped if a filter is given.
+    ///
+    /// # Arguments
+    /// * `new_features` - The feature names of the target feature space
+    /// * `old_feature_names` - The feature names the network is currently indexed against
+    /// * `size_filter` - Optional `(min, max)` pathway size applied after remapping
+    ///
+    /// # Returns
+    /// The remapped network and a report of what was dropped, or an error if a gene
+    /// index has no entry in `old_feature_names`
+    pub fn subset_to_features(
+        &self,
+        new_features: &[String],
+        old_feature_names: &[String],
+        size_filter: Option<(usize, usize)>,
+    ) -> anyhow::Result<(Self, SubsetReport)> {
+        let new_index: HashMap<&str, usize> = new_features
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| (name.as_str(), idx))
+            .collect();
+
+        let mut remap: HashMap<I, Option<usize>> = HashMap::new();
+        let mut report = SubsetReport::default();
+        let mut names = Vec::with_capacity(self.get_num_pathways());
+        let mut starts = Vec::with_capacity(self.get_num_pathways());
+        let mut offsets = Vec::with_capacity(self.get_num_pathways());
+        let mut cnct = Vec::with_capacity(self.cnct.len());
+        let mut weights = Vec::with_capacity(self.weights.len());
+        let mut signs = self
+            .signs
+            .as_ref()
+            .map(|_| Vec::with_capacity(self.cnct.len()));
+
+        for (idx, (name, genes, gene_weights)) in self.iter().enumerate() {
+            let start = cnct.len();
+            for (position, (&gene, &weight)) in
+                self.pathway_range(idx).zip(genes.iter().zip(gene_weights))
+            {
+                let mapped = match remap.get(&gene) {
+                    Some(&mapped) => mapped,
+                    None => {
+                        let old_name = old_feature_names.get(gene.to_index()).ok_or_else(|| {
+                            anyhow!(
+                                "Gene index {:?} of pathway '{}' has no feature name!",
+                                gene,
+                                name
+                            )
+                        })?;
+                        let mapped = new_index.get(old_name.as_str()).copied();
+                        if mapped.is_none() {
+                            report.missing_features.push(old_name.clone());
+                        }
+                        remap.insert(gene, mapped);
+                        mapped
+                    }
+                };
+                match mapped {
+                    Some(new_gene) => {
+                        cnct.push(new_gene);
+                        weights.push(weight);
+                        if let Some(signs) = &mut signs {
+                            signs.push(self.edge_sign(position));
+                        }
+                    }
+                    None => report.dropped_memberships += 1,
+                }
+            }
+
+            let len = cnct.len() - start;
+            if let Some((min, max)) = size_filter
+                && !(min..=max).contains(&len)
+            {
+                cnct.truncate(start);
+                weights.truncate(start);
+                if let Some(signs) = &mut signs {
+                    signs.truncate(start);
+                }
+                report.dropped_pathways.push(name.to_string());
+                continue;
+            }
+            names.push(name.to_string());
+            starts.push(start);
+            offsets.push(len);
+        }
+
+        report.kept_memberships = cnct.len();
+        let mut network = Self::from_usize_parts(names, starts, offsets, cnct, weights)?;
+        network.feature_names = Some(new_features.to_vec());
+        network.signs = signs;
+        Ok((network, report))
+    }
+
+    /// Combines several networks into one collection, e.g. Hallmark and Reactome.
+    ///
+    /// All networks must be indexed against the same feature space. Pathways keep the
+    /// order of `nets` and, within each network, their original order. The feature
+    /// names of the first network that has them are kept. If any network records edge
+    /// signs, every edge keeps the sign it has in its source network.
+    ///
+    /// # Arguments
+    /// * `nets` - The networks to merge
+    /// * `policy` - How pathways sharing a name are resolved
+    ///
+    /// # Returns
+    /// The merged network, or an error if names conflict under `ConflictPolicy::Error`
+    pub fn merge(nets: &[&Self], policy: ConflictPolicy) -> anyhow::Result<Self> {
+        let mut builder = PathwayNetworkBuilder::new();
+        let mut used: HashSet<String> = HashSet::new();
+        let record_signs = nets.iter().any(|net| net.signs.is_some());
+        let mut edge_signs = EdgeSigns::new();
+
+        for net in nets {
+            for (idx, (name, genes, weights)) in net.iter().enumerate() {
+                let name = if used.contains(name) {
+                    match policy {
+                        ConflictPolicy::Error => {
+                            return Err(anyhow!(
+                                "Pathway '{}' is present in more than one network!",
+                                name
+                            ));
+                        }
+                        ConflictPolicy::KeepFirst => continue,
+                        ConflictPolicy::UnionGenes => name.to_string(),
+                        ConflictPolicy::RenameWithSuffix => (2..)
+                            .map(|k| format!("{}_{}", name, k))
+                            .find(|candidate| !used.contains(candidate))
+                            .unwrap(),
+                    }
+                } else {
+                    name.to_string()
+                };
+
+                builder = builder.add_pathway(&name);
+                for (&gene, &weight) in genes.iter().zip(weights) {
+                    builder = builder.add_gene(gene.to_index(), weight);
+                }
+                if record_signs {
+                    let signs = edge_signs.entry(name.clone()).or_default();
+                    for (position, &gene) in net.pathway_range(idx).zip(genes) {
+                        signs
+                            .entry(gene.to_index())
+                            .or_insert(net.edge_sign(position));
+                    }
+                }
+                used.insert(name);
+            }
+        }
+
+        let mut network = builder.build()?;
+        network.feature_names = nets.iter().find_map(|net| net.feature_names.clone());
+        if record_signs {
+            network.signs = Some(network.signs_by_edge(&edge_signs));
+        }
+        Ok(network)
+    }
+
+    /// Returns the pathways present in both networks, restricted to their shared genes.
+    ///
+    /// Pathways are matched by name and kept in the order of `self`; genes keep the
+    /// order of `self` as well. Pathways without any shared gene are dropped. Both
+    /// networks must be indexed against the same feature space. Recorded edge signs
+    /// are taken from `self`.
+    ///
+    /// # Arguments
+    /// * `other` - The network to intersect with
+    /// * `combine` - How the weights of shared genes are combined
+    pub fn intersect(&self, other: &Self, combine: WeightCombination) -> Self {
+        let mut builder = PathwayNetworkBuilder::new();
+        let mut edge_signs = EdgeSigns::new();
+        for (idx, (name, genes, weights)) in self.iter().enumerate() {
+            let Some((other_genes, other_weights)) = other.get_pathway_by_name(name) else {
+                continue;
+            };
+            let other_map: HashMap<I, W> = other_genes
+                .iter()
+                .copied()
+                .zip(other_weights.iter().copied())
+                .collect();
+            let shared: Vec<(usize, usize, W)> = self
+                .pathway_range(idx)
+                .zip(genes.iter().zip(weights))
+                .filter_map(|(position, (gene, &w))| {
+                    other_map
+                        .get(gene)
+                        .map(|&o| (position, gene.to_index(), combine.combine(w, o)))
+                })
+                .collect();
+            if shared.is_empty() {
+                continue;
+            }
+            builder = builder.add_pathway(name);
+            for &(_, gene, weight) in &shared {
+                builder = builder.add_gene(gene, weight);
+            }
+            if self.signs.is_some() {
+                let signs = edge_signs.entry(name.to_string()).or_default();
+                for &(position, gene, _) in &shared {
+                    signs.entry(gene).or_insert(self.edge_sign(position));
+                }
+            }
+        }
+        let mut network = builder.build().expect("duplicate policy First never fails");
+        network.feature_names = self.feature_names.clone();
+        if self.signs.is_some() {
+            network.signs = Some(network.signs_by_edge(&edge_signs));
+        }
+        network
+    }
+
+    /// Returns the pathways of `self` with all genes removed that the equally named
+    /// pathway of `other` contains.
+    ///
+    /// Pathways missing from `other` are kept unchanged and pathways left without any
+    /// gene are dropped. Weights and recorded edge signs are taken from `self`. Both
+    /// networks must be indexed against the same feature space.
+    ///
+    /// # Arguments
+    /// * `other` - The network whose pathways and genes are subtracted
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut builder = PathwayNetworkBuilder::new();
+        let mut edge_signs = EdgeSigns::new();
+        for (idx, (name, genes, weights)) in self.iter().enumerate() {
+            let removed: HashSet<I> = other
+                .get_pathway_features_by_name(name)
+                .map(|g| g.iter().copied().collect())
+                .unwrap_or_default();
+            let remaining: Vec<(usize, usize, W)> = self
+                .pathway_range(idx)
+                .zip(genes.iter().zip(weights))
+                .filter(|(_, (gene, _))| !removed.contains(gene))
+                .map(|(position, (&gene, &weight))| (position, gene.to_index(), weight))
+                .collect();
+            if remaining.is_empty() {
+                continue;
+            }
+            builder = builder.add_pathway(name);
+            for &(_, gene, weight) in &remaining {
+                builder = builder.add_gene(gene, weight);
+            }
+            if self.signs.is_some() {
+                let signs = edge_signs.entry(name.to_string()).or_default();
+                for &(position, gene, _) in &remaining {
+                    signs.entry(gene).or_insert(self.edge_sign(position));
+                }
+            }
+        }
+        let mut network = builder.build().expect("duplicate policy First never fails");
+        network.feature_names = self.feature_names.clone();
+        if self.signs.is_some() {
+            network.signs = Some(network.signs_by_edge(&edge_signs));
+        }
+        network
+    }
+
+    /// Looks up the sign of every edge of a network assembled by the builder, whose
+    /// duplicate policy `First` keeps the first occurrence of every gene.
+    fn signs_by_edge(&self, edge_signs: &EdgeSigns) -> Vec<EdgeSign> {
+        self.iter()
+            .flat_map(|(name, genes, _)| {
+                let signs = &edge_signs[name];
+                genes.iter().map(move |gene| signs[&gene.to_index()])
+            })
+            .collect()
+    }
+}
+
+/// Signs of the edges fed into a builder, by pathway name and gene.
+type EdgeSigns = HashMap<String, HashMap<usize, EdgeSign>>;
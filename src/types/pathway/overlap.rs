@@ -0,0 +1,133 @@
+use super::GenericPathwayNetwork;
+use crate::traits::{FloatOpsTS, UIndex};
+use crate::types::CsrData;
+use crate::utils::parallel::map_indices_init;
+
+/// Similarity measure between the gene sets of two pathways.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlapMetric {
+    /// Shared genes divided by the size of the union, `|A ∩ B| / |A ∪ B|`
+    Jaccard,
+    /// Shared genes divided by the size of the smaller set, `|A ∩ B| / min(|A|, |B|)`
+    OverlapCoefficient,
+}
+
+impl OverlapMetric {
+    fn score(self, shared: usize, size_a: usize, size_b: usize) -> f64 {
+        match self {
+            Self::Jaccard => shared as f64 / (size_a + size_b - shared) as f64,
+            Self::OverlapCoefficient => shared as f64 / size_a.min(size_b) as f64,
+        }
+    }
+}
+
+impl<W: FloatOpsTS, I: UIndex + Send + Sync> GenericPathwayNetwork<W, I> {
+    /// Computes the pairwise gene set similarity between all pathways.
+    ///
+    /// Shared genes are counted through the reverse gene index, so only pathway pairs
+    /// with at least one shared gene are visited. Rows are processed in parallel when
+    /// the `rayon` feature is enabled; every worker reuses one counting buffer and
+    /// resets only the entries it touched. Genes are assumed to be unique within a
+    /// pathway.
+    ///
+    /// # Arguments
+    /// * `metric` - The similarity measure
+    /// * `threshold` - Minimum similarity for a pair to be stored
+    ///
+    /// # Returns
+    /// A symmetric pathway × pathway CSR matrix holding every pair with a similarity of
+    /// at least `threshold`, excluding the diagonal; column indices are sorted per row
+    pub fn pathway_overlap(&self, metric: OverlapMetric, threshold: f64) -> CsrData<f64> {
+        let n_pathways = self.get_num_pathways();
+        let gene_index = self.build_gene_index();
+        let sizes: Vec<usize> = self.offsets.iter().map(|o| o.to_index()).collect();
+
+        let init = || (vec![0usize; n_pathways], Vec::new());
+        let rows = map_indices_init(n_pathways, init, |(shared, touched), i| {
+            for gene in self.get_pathway_features(i) {
+                for &other in gene_index.pathways_containing(gene.to_index()) {
+                    if other == i {
+                        continue;
+                    }
+                    if shared[other] == 0 {
+                        touched.push(other);
+                    }
+                    shared[other] += 1;
+                }
+            }
+            touched.sort_unstable();
+            let row: Vec<(usize, f64)> = touched
+                .iter()
+                .map(|&j| (j, metric.score(shared[j], sizes[i], sizes[j])))
+                .filter(|&(_, score)| score >= threshold)
+                .collect();
+            for j in touched.drain(..) {
+                shared[j] = 0;
+            }
+            row
+        });
+
+        let mut indptr = Vec::with_capacity(n_pathways + 1);
+        let mut indices = Vec::new();
+        let mut data = Vec::new();
+        indptr.push(0);
+        for row in rows {
+            for (j, score) in row {
+                indices.push(j);
+                data.push(score);
+            }
+            indptr.push(indices.len());
+        }
+        CsrData::new(n_pathways, n_pathways, indptr, indices, data)
+            .expect("overlap matrix is consistent by construction")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PathwayNetwork;
+    use std::collections::HashSet;
+
+    #[test]
+    fn pathway_overlap_matches_pairwise_sets() {
+        let genes: Vec<Vec<usize>> = (0..40)
+            .map(|p| (0..(p % 7 + 1)).map(|k| (p * 5 + k * 11) % 23).collect())
+            .map(|g: Vec<usize>| g.into_iter().collect::<HashSet<_>>().into_iter().collect())
+            .collect();
+        let mut starts = Vec::new();
+        let mut cnct = Vec::new();
+        for g in &genes {
+            starts.push(cnct.len());
+            cnct.extend(g);
+        }
+        let network = PathwayNetwork::new_wo_weights(
+            (0..genes.len()).map(|p| format!("P{}", p)).collect(),
+            starts,
+            genes.iter().map(Vec::len).collect(),
+            cnct,
+        );
+
+        let overlap = network.pathway_overlap(OverlapMetric::Jaccard, 0.0);
+        for (i, a) in genes.iter().enumerate() {
+            let a: HashSet<_> = a.iter().collect();
+            let expected: Vec<(usize, f64)> = genes
+                .iter()
+                .enumerate()
+                .filter(|&(j, _)| j != i)
+                .filter_map(|(j, b)| {
+                    let b: HashSet<_> = b.iter().collect();
+                    let shared = a.intersection(&b).count();
+                    (shared > 0).then(|| (j, shared as f64 / a.union(&b).count() as f64))
+                })
+                .collect();
+            let range = overlap.indptr()[i]..overlap.indptr()[i + 1];
+            let row: Vec<(usize, f64)> = overlap.indices()[range.clone()]
+                .iter()
+                .copied()
+                .zip(overlap.data()[range].iter().copied())
+                .collect();
+            assert_eq!(row, expected);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use super::GenericPathwayNetwork;
+use crate::traits::{FloatOps, UIndex};
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Removes promiscuous genes that belong to too many pathways.
+    ///
+    /// Genes contained in more than `max_fraction` of all pathways, typically
+    /// ribosomal or housekeeping genes, are removed from every pathway. Pathways
+    /// left with fewer than `min_size` genes are dropped afterwards.
+    ///
+    /// # Arguments
+    /// * `max_fraction` - Largest fraction of pathways a gene may belong to
+    /// * `min_size` - Minimum number of genes a pathway needs after pruning to be kept
+    ///
+    /// # Returns
+    /// The indices of the pruned genes in ascending order
+    pub fn prune_frequent_genes(&mut self, max_fraction: f64, min_size: usize) -> Vec<usize> {
+        let max_pathways = max_fraction * self.get_num_pathways() as f64;
+        let index = self.build_gene_index();
+        let pruned: Vec<usize> = (0..index.num_features())
+            .filter(|&gene| index.degree(gene) as f64 > max_pathways)
+            .collect();
+        if pruned.is_empty() {
+            self.retain_by_size(min_size, usize::MAX);
+            return pruned;
+        }
+
+        let mut is_pruned = vec![false; index.num_features()];
+        for &gene in &pruned {
+            is_pruned[gene] = true;
+        }
+        let mut names = Vec::with_capacity(self.get_num_pathways());
+        let mut starts = Vec::with_capacity(self.get_num_pathways());
+        let mut offsets = Vec::with_capacity(self.get_num_pathways());
+        let mut cnct = Vec::with_capacity(self.cnct.len());
+        let mut weights = Vec::with_capacity(self.weights.len());
+        let mut signs = self
+            .signs
+            .as_ref()
+            .map(|_| Vec::with_capacity(self.cnct.len()));
+        for (idx, (name, genes, gene_weights)) in self.iter().enumerate() {
+            let start = cnct.len();
+            for (position, (&gene, &weight)) in
+                self.pathway_range(idx).zip(genes.iter().zip(gene_weights))
+            {
+                if !is_pruned[gene.to_index()] {
+                    cnct.push(gene.to_index());
+                    weights.push(weight);
+                    if let Some(signs) = &mut signs {
+                        signs.push(self.edge_sign(position));
+                    }
+                }
+            }
+            if cnct.len() - start < min_size {
+                cnct.truncate(start);
+                weights.truncate(start);
+                if let Some(signs) = &mut signs {
+                    signs.truncate(start);
+                }
+                continue;
+            }
+            names.push(name.to_string());
+            starts.push(start);
+            offsets.push(cnct.len() - start);
+        }
+
+        let feature_names = self.feature_names.take();
+        *self = Self::from_usize_parts(names, starts, offsets, cnct, weights)
+            .expect("pruning only shrinks the network");
+        self.feature_names = feature_names;
+        self.signs = signs;
+        pruned
+    }
+}
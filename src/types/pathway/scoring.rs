@@ -0,0 +1,782 @@
+//! Per-observation pathway activity scores computed from an expression matrix.
+
+use super::GenericPathwayNetwork;
+use crate::random::RngOps;
+use crate::traits::{FloatOpsTS, MatrixLike, UIndex};
+use crate::types::Direction;
+use crate::utils::parallel::map_indices;
+use anyhow::anyhow;
+use ndarray::Array2;
+use std::cmp::Ordering;
+
+/// Method used by `score_matrix` to turn expression into pathway activities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoringMethod {
+    /// Over-representation of the top expressed features, scored as `-log10(p)`
+    Ora,
+    /// GSEA enrichment of the expression ranking; NES with permutations, ES without
+    Gsea,
+    /// Area under the recovery curve of the top expressed features
+    AuCell,
+    /// Single-sample GSEA
+    SsGsea,
+    /// t-value of a univariate linear model on the pathway weights
+    Ulm,
+    /// Weighted mean, z-scored against shuffled features with permutations
+    WMean,
+}
+
+/// Parameters of the scoring methods dispatched by `score_matrix`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScoringOptions {
+    /// Fraction of top expressed features used by `Ora` and `AuCell`
+    pub top_fraction: f64,
+    /// Rank weight exponent of `SsGsea`
+    pub alpha: f64,
+    /// Number of permutations of `Gsea` and `WMean`
+    pub permutations: usize,
+}
+
+impl Default for ScoringOptions {
+    fn default() -> Self {
+        Self {
+            top_fraction: 0.05,
+            alpha: 0.25,
+            permutations: 0,
+        }
+    }
+}
+
+impl<W: FloatOpsTS, I: UIndex + Send + Sync> GenericPathwayNetwork<W, I> {
+    /// Scores every observation with the area under the recovery curve (AUCell).
+    ///
+    /// The features of each observation are ranked by decreasing expression (ties by
+    /// feature index) and only the top `top_fraction` of them are considered. The
+    /// recovery curve counts the pathway genes found up to each rank; its area is
+    /// normalized by the area of a pathway whose genes occupy the top ranks, so scores
+    /// lie in `[0, 1]`. Observations are processed in parallel when the `rayon` feature
+    /// is enabled.
+    ///
+    /// # Arguments
+    /// * `expression` - A dense or sparse matrix implementing `MatrixLike`
+    /// * `direction` - The axis along which observations are laid out
+    /// * `top_fraction` - Fraction of the features used for the curve, e.g. 0.05
+    ///
+    /// # Returns
+    /// An observations × pathways score matrix, or an error if `top_fraction` is not in
+    /// `(0, 1]` or a pathway gene lies outside the feature space of `expression`
+    pub fn aucell_scores<T, M>(
+        &self,
+        expression: &M,
+        direction: Direction,
+        top_fraction: f64,
+    ) -> anyhow::Result<Array2<T>>
+    where
+        T: FloatOpsTS,
+        M: MatrixLike<T> + Sync,
+    {
+        let n_features = feature_count(expression, &direction);
+        self.check_feature_space(n_features)?;
+        let n_top = top_count(n_features, top_fraction)?;
+
+        let rows = map_observations(expression, &direction, |_, values: &[T]| {
+            let order = top_features(values, n_top);
+            let mut rank_of = vec![usize::MAX; values.len()];
+            for (rank, &feature) in order.iter().enumerate() {
+                rank_of[feature] = rank;
+            }
+
+            (0..self.get_num_pathways())
+                .map(|idx| {
+                    let genes = self.get_pathway_features(idx);
+                    let k = genes.len().min(n_top);
+                    if k == 0 {
+                        return T::zero();
+                    }
+                    let auc: usize = genes
+                        .iter()
+                        .map(|g| rank_of[g.to_index()])
+                        .filter(|&rank| rank < n_top)
+                        .map(|rank| n_top - rank)
+                        .sum();
+                    let max_auc = k * n_top - k * (k - 1) / 2;
+                    T::from_f64(auc as f64 / max_auc as f64).unwrap()
+                })
+                .collect::<Vec<T>>()
+        });
+
+        Ok(Array2::from_shape_vec(
+            (rows.len(), self.get_num_pathways()),
+            rows.concat(),
+        )?)
+    }
+
+    /// Scores every observation with single-sample GSEA (ssGSEA).
+    ///
+    /// The features of each observation are ranked by decreasing expression (ties by
+    /// feature index). Walking down the ranking, hits step a running sum up by their
+    /// ascending rank raised to `alpha` and misses step it down uniformly; the score
+    /// of a pathway is the sum of the running sum over all positions. As in GSVA's
+    /// default, all scores are finally divided by the range of the score matrix.
+    /// Observations are processed in parallel when the `rayon` feature is enabled.
+    ///
+    /// # Arguments
+    /// * `expression` - A dense or sparse matrix implementing `MatrixLike`
+    /// * `direction` - The axis along which observations are laid out
+    /// * `alpha` - Exponent of the rank weights, 0.25 in the original method
+    ///
+    /// # Returns
+    /// An observations × pathways score matrix with NaN for pathways containing no or
+    /// all features, or an error if a pathway gene lies outside the feature space
+    pub fn ssgsea_scores<T, M>(
+        &self,
+        expression: &M,
+        direction: Direction,
+        alpha: f64,
+    ) -> anyhow::Result<Array2<T>>
+    where
+        T: FloatOpsTS,
+        M: MatrixLike<T> + Sync,
+    {
+        let n_features = feature_count(expression, &direction);
+        self.check_feature_space(n_features)?;
+        let n = n_features as f64;
+        let total_positions = n * (n + 1.0) / 2.0;
+
+        let rows = map_observations(expression, &direction, |_, values: &[T]| {
+            let order = top_features(values, values.len());
+            let mut position_of = vec![0usize; values.len()];
+            for (pos, &feature) in order.iter().enumerate() {
+                position_of[feature] = pos;
+            }
+
+            (0..self.get_num_pathways())
+                .map(|idx| {
+                    let genes = self.get_pathway_features(idx);
+                    let k = genes.len();
+                    if k == 0 || k >= n_features {
+                        return f64::NAN;
+                    }
+                    // Every hit at position p contributes to the running sums of the
+                    // remaining n - p positions, which is also its ascending rank.
+                    let mut weight_sum = 0.0;
+                    let mut weighted_tail = 0.0;
+                    let mut hit_tail = 0.0;
+                    for gene in genes {
+                        let tail = n - position_of[gene.to_index()] as f64;
+                        let weight = tail.powf(alpha);
+                        weight_sum += weight;
+                        weighted_tail += weight * tail;
+                        hit_tail += tail;
+                    }
+                    weighted_tail / weight_sum - (total_positions - hit_tail) / (n - k as f64)
+                })
+                .collect::<Vec<f64>>()
+        });
+
+        let (min, max) = rows
+            .iter()
+            .flatten()
+            .filter(|v| !v.is_nan())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &v| {
+                (lo.min(v), hi.max(v))
+            });
+        let range = if max > min { max - min } else { 1.0 };
+        let n_obs = rows.len();
+        let scores: Vec<T> = rows
+            .into_iter()
+            .flatten()
+            .map(|v| T::from_f64(v / range).unwrap())
+            .collect();
+
+        Ok(Array2::from_shape_vec(
+            (n_obs, self.get_num_pathways()),
+            scores,
+        )?)
+    }
+
+    /// Scores every observation with a univariate linear model (ULM).
+    ///
+    /// For every observation and pathway, the expression of all features is regressed
+    /// on the pathway weights, with features outside the pathway weighted 0. The score
+    /// is the t-value of the slope, so positive scores indicate that the pathway genes
+    /// are expressed in the direction of their weights. Observations are processed in
+    /// parallel when the `rayon` feature is enabled.
+    ///
+    /// # Arguments
+    /// * `expression` - A dense or sparse matrix implementing `MatrixLike`
+    /// * `direction` - The axis along which observations are laid out
+    ///
+    /// # Returns
+    /// An observations × pathways matrix of t-values, NaN where the slope is undefined,
+    /// or an error if a pathway gene lies outside the feature space
+    pub fn ulm_scores<T, M>(
+        &self,
+        expression: &M,
+        direction: Direction,
+    ) -> anyhow::Result<Array2<T>>
+    where
+        T: FloatOpsTS,
+        M: MatrixLike<T> + Sync,
+    {
+        let n_features = feature_count(expression, &direction);
+        self.check_feature_space(n_features)?;
+        let n = n_features as f64;
+
+        let rows = map_observations(expression, &direction, |_, values: &[T]| {
+            let (sum_y, sum_yy) = values.iter().fold((0.0, 0.0), |(s, ss), v| {
+                let v = v.to_f64().unwrap();
+                (s + v, ss + v * v)
+            });
+            let mean_y = sum_y / n;
+            let syy = sum_yy - n * mean_y * mean_y;
+
+            (0..self.get_num_pathways())
+                .map(|idx| {
+                    let (genes, weights) = self.get_pathway_features_and_weights(idx);
+                    let (mut sum_x, mut sum_xx, mut sum_xy) = (0.0, 0.0, 0.0);
+                    for (gene, weight) in genes.iter().zip(weights) {
+                        let x = weight.to_f64().unwrap();
+                        sum_x += x;
+                        sum_xx += x * x;
+                        sum_xy += x * values[gene.to_index()].to_f64().unwrap();
+                    }
+                    let mean_x = sum_x / n;
+                    let sxx = sum_xx - n * mean_x * mean_x;
+                    let sxy = sum_xy - n * mean_x * mean_y;
+                    let slope = sxy / sxx;
+                    let rss = (syy - slope * sxy).max(0.0);
+                    let se = (rss / (n - 2.0) / sxx).sqrt();
+                    T::from_f64(slope / se).unwrap()
+                })
+                .collect::<Vec<T>>()
+        });
+
+        Ok(Array2::from_shape_vec(
+            (rows.len(), self.get_num_pathways()),
+            rows.concat(),
+        )?)
+    }
+
+    /// Scores every observation with the weighted mean of its pathway genes (wmean).
+    ///
+    /// The raw score is `sum(w * x) / sum(|w|)` over the genes of a pathway. With
+    /// `permutations > 0` the expression values of each observation are shuffled
+    /// across features that many times and the raw score is z-scored against the
+    /// resulting null distribution. Observations are processed in parallel when the
+    /// `rayon` feature is enabled. Every observation shuffles with its own stream
+    /// forked from `rng`, which is advanced by a single draw, so results do not depend
+    /// on the number of threads.
+    ///
+    /// # Arguments
+    /// * `expression` - A dense or sparse matrix implementing `MatrixLike`
+    /// * `direction` - The axis along which observations are laid out
+    /// * `permutations` - Number of shuffles for the null; 0 returns the raw scores
+    /// * `rng` - The random number generator
+    ///
+    /// # Returns
+    /// An observations × pathways score matrix, or an error if a pathway gene lies
+    /// outside the feature space
+    pub fn wmean_scores<T, M, R>(
+        &self,
+        expression: &M,
+        direction: Direction,
+        permutations: usize,
+        rng: &mut R,
+    ) -> anyhow::Result<Array2<T>>
+    where
+        T: FloatOpsTS,
+        M: MatrixLike<T> + Sync,
+        R: RngOps + Sync,
+    {
+        let n_features = feature_count(expression, &direction);
+        self.check_feature_space(n_features)?;
+        let n_pathways = self.get_num_pathways();
+        let norms: Vec<f64> = (0..n_pathways)
+            .map(|idx| {
+                self.get_pathway_features_and_weights(idx)
+                    .1
+                    .iter()
+                    .map(|w| w.to_f64().unwrap().abs())
+                    .sum()
+            })
+            .collect();
+        let weighted_means = |values: &[f64]| -> Vec<f64> {
+            (0..n_pathways)
+                .map(|idx| {
+                    let (genes, weights) = self.get_pathway_features_and_weights(idx);
+                    let sum: f64 = genes
+                        .iter()
+                        .zip(weights)
+                        .map(|(g, w)| w.to_f64().unwrap() * values[g.to_index()])
+                        .sum();
+                    sum / norms[idx]
+                })
+                .collect()
+        };
+
+        let stream = rng.next_u64();
+        let parent = rng.fork(stream);
+        let rows = map_observations(expression, &direction, |obs, values: &[T]| {
+            let mut values: Vec<f64> = values.iter().map(|v| v.to_f64().unwrap()).collect();
+            let observed = weighted_means(&values);
+            if permutations == 0 {
+                return observed
+                    .into_iter()
+                    .map(|v| T::from_f64(v).unwrap())
+                    .collect::<Vec<T>>();
+            }
+
+            let mut rng = parent.fork(obs as u64);
+            let mut sum = vec![0.0; n_pathways];
+            let mut sum_sq = vec![0.0; n_pathways];
+            for _ in 0..permutations {
+                rng.shuffle(&mut values);
+                for (idx, null) in weighted_means(&values).into_iter().enumerate() {
+                    sum[idx] += null;
+                    sum_sq[idx] += null * null;
+                }
+            }
+            let p = permutations as f64;
+            observed
+                .into_iter()
+                .enumerate()
+                .map(|(idx, v)| {
+                    let mean = sum[idx] / p;
+                    let std = (sum_sq[idx] / p - mean * mean).max(0.0).sqrt();
+                    T::from_f64((v - mean) / std).unwrap()
+                })
+                .collect::<Vec<T>>()
+        });
+
+        Ok(Array2::from_shape_vec(
+            (rows.len(), n_pathways),
+            rows.concat(),
+        )?)
+    }
+
+    /// Computes an observations × pathways activity matrix with the given method.
+    ///
+    /// This is the common entry point for all per-observation scoring methods; dense
+    /// and sparse inputs are accepted through `MatrixLike` and observations are scored
+    /// in parallel when the `rayon` feature is enabled. `Ora` and `Gsea` treat every
+    /// observation as its own query: `Ora` tests the top `top_fraction` expressed
+    /// features and `Gsea` ranks all features by expression.
+    ///
+    /// # Arguments
+    /// * `expression` - A dense or sparse matrix implementing `MatrixLike`
+    /// * `direction` - The axis along which observations are laid out
+    /// * `method` - The scoring method
+    /// * `options` - Method parameters; unused ones are ignored
+    /// * `rng` - The random number generator of the permutations of `Gsea` and `WMean`
+    ///
+    /// # Returns
+    /// The activity matrix, or an error if the options are invalid for the method or a
+    /// pathway gene lies outside the feature space
+    pub fn score_matrix<T, M, R>(
+        &self,
+        expression: &M,
+        direction: Direction,
+        method: ScoringMethod,
+        options: &ScoringOptions,
+        rng: &mut R,
+    ) -> anyhow::Result<Array2<T>>
+    where
+        T: FloatOpsTS,
+        M: MatrixLike<T> + Sync,
+        R: RngOps + Sync,
+    {
+        match method {
+            ScoringMethod::AuCell => {
+                self.aucell_scores(expression, direction, options.top_fraction)
+            }
+            ScoringMethod::SsGsea => self.ssgsea_scores(expression, direction, options.alpha),
+            ScoringMethod::Ulm => self.ulm_scores(expression, direction),
+            ScoringMethod::WMean => {
+                self.wmean_scores(expression, direction, options.permutations, rng)
+            }
+            ScoringMethod::Ora => self.ora_scores(expression, direction, options.top_fraction),
+            ScoringMethod::Gsea => self.gsea_scores(expression, direction, options, rng),
+        }
+    }
+
+    /// Per-observation ORA on the top expressed features, scored as `-log10(p)`.
+    fn ora_scores<T, M>(
+        &self,
+        expression: &M,
+        direction: Direction,
+        top_fraction: f64,
+    ) -> anyhow::Result<Array2<T>>
+    where
+        T: FloatOpsTS,
+        M: MatrixLike<T> + Sync,
+    {
+        let n_features = feature_count(expression, &direction);
+        self.check_feature_space(n_features)?;
+        let n_top = top_count(n_features, top_fraction)?;
+
+        let rows = map_observations(expression, &direction, |_, values: &[T]| {
+            self.ora(&top_features(values, n_top), n_features)
+                .expect("query genes lie inside the feature space")
+                .into_iter()
+                .map(|r| T::from_f64(-r.pvalue.log10()).unwrap())
+                .collect::<Vec<T>>()
+        });
+
+        Ok(Array2::from_shape_vec(
+            (rows.len(), self.get_num_pathways()),
+            rows.concat(),
+        )?)
+    }
+
+    /// Per-observation GSEA on the expression ranking, scored as NES or ES.
+    ///
+    /// Every observation forks its own stream from `rng`, from which `gsea` forks one
+    /// stream per pathway.
+    fn gsea_scores<T, M, R>(
+        &self,
+        expression: &M,
+        direction: Direction,
+        options: &ScoringOptions,
+        rng: &mut R,
+    ) -> anyhow::Result<Array2<T>>
+    where
+        T: FloatOpsTS,
+        M: MatrixLike<T> + Sync,
+        R: RngOps + Sync,
+    {
+        let n_features = feature_count(expression, &direction);
+        self.check_feature_space(n_features)?;
+        let stream = rng.next_u64();
+        let parent = rng.fork(stream);
+
+        let rows = map_observations(expression, &direction, |obs, values: &[T]| {
+            let stats: Vec<W> = values
+                .iter()
+                .map(|v| W::from_f64(v.to_f64().unwrap()).unwrap())
+                .collect();
+            self.gsea(&stats, options.permutations, &mut parent.fork(obs as u64))
+                .into_iter()
+                .map(|r| {
+                    let score = if options.permutations > 0 {
+                        r.nes
+                    } else {
+                        r.es
+                    };
+                    T::from_f64(score).unwrap()
+                })
+                .collect::<Vec<T>>()
+        });
+
+        Ok(Array2::from_shape_vec(
+            (rows.len(), self.get_num_pathways()),
+            rows.concat(),
+        )?)
+    }
+
+    /// Checks that every gene index of the network lies inside a feature space of
+    /// `n_features` features.
+    pub(crate) fn check_feature_space(&self, n_features: usize) -> anyhow::Result<()> {
+        match self.cnct.iter().map(|g| g.to_index()).max() {
+            Some(max) if max >= n_features => Err(anyhow!(
+                "Gene index {} is outside the feature space of {} features!",
+                max,
+                n_features
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Converts a fraction of the features into a count of at least one feature.
+fn top_count(n_features: usize, top_fraction: f64) -> anyhow::Result<usize> {
+    if !(top_fraction > 0.0 && top_fraction <= 1.0) {
+        return Err(anyhow!(
+            "top_fraction must be in (0, 1], got {}!",
+            top_fraction
+        ));
+    }
+    Ok(((top_fraction * n_features as f64).ceil() as usize).clamp(1, n_features.max(1)))
+}
+
+/// Returns the indices of the `n` highest values in decreasing order, ties by index.
+fn top_features<T: FloatOpsTS>(values: &[T], n: usize) -> Vec<usize> {
+    let by_value = |&a: &usize, &b: &usize| {
+        values[b]
+            .partial_cmp(&values[a])
+            .unwrap_or(Ordering::Equal)
+            .then(a.cmp(&b))
+    };
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    if n < order.len() {
+        order.select_nth_unstable_by(n, by_value);
+        order.truncate(n);
+    }
+    order.sort_unstable_by(by_value);
+    order
+}
+
+/// Returns the number of features of a matrix whose observations lie along `direction`.
+pub(crate) fn feature_count<T: Copy, M: MatrixLike<T>>(matrix: &M, direction: &Direction) -> usize {
+    if direction.is_row() {
+        matrix.ncols()
+    } else {
+        matrix.nrows()
+    }
+}
+
+/// Maps the index and dense feature vector of every observation through `f`,
+/// preserving order.
+///
+/// Observations along columns are first gathered from the row-wise traversal, visiting
+/// only the stored entries of sparse inputs.
+pub(crate) fn map_observations<T, M, R, F>(matrix: &M, direction: &Direction, f: F) -> Vec<R>
+where
+    T: FloatOpsTS,
+    M: MatrixLike<T> + Sync,
+    R: Send,
+    F: Fn(usize, &[T]) -> R + Sync + Send,
+{
+    if direction.is_row() {
+        let n_features = matrix.ncols();
+        map_indices(matrix.nrows(), |obs| {
+            let mut values = vec![T::zero(); n_features];
+            matrix.row_into(obs, &mut values);
+            f(obs, &values)
+        })
+    } else {
+        let n_features = matrix.nrows();
+        let mut columns: Vec<Vec<(usize, T)>> = vec![Vec::new(); matrix.ncols()];
+        for feature in 0..n_features {
+            matrix.for_each_in_row(feature, |obs, val| columns[obs].push((feature, val)));
+        }
+        map_indices(columns.len(), |obs| {
+            let mut values = vec![T::zero(); n_features];
+            for &(feature, val) in &columns[obs] {
+                values[feature] = val;
+            }
+            f(obs, &values)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::SeededRng;
+    use ndarray::{Array2, array};
+
+    type Network = GenericPathwayNetwork<f64>;
+
+    // Reference values were computed independently with explicit running sums,
+    // recovery curves and least-squares fits in mpmath.
+
+    fn network() -> Network {
+        Network::from_usize_parts(
+            vec!["P0".into(), "P1".into(), "P2".into()],
+            vec![0, 3, 7],
+            vec![3, 4, 1],
+            vec![0, 2, 5, 1, 3, 4, 7, 6],
+            vec![1.0, 2.0, -1.0, 0.5, 1.0, 1.5, -0.5, 2.0],
+        )
+        .unwrap()
+    }
+
+    fn expression() -> Array2<f64> {
+        array![
+            [5.0, 1.0, 3.5, 0.2, 2.2, 4.1, 0.7, 1.9],
+            [0.3, 2.8, 1.1, 4.4, 3.3, 0.9, 2.0, 5.2],
+            [1.5, 0.4, 4.8, 2.6, 0.1, 3.0, 5.5, 1.2],
+        ]
+    }
+
+    fn score(method: ScoringMethod, options: &ScoringOptions) -> Array2<f64> {
+        network()
+            .score_matrix(
+                &expression(),
+                Direction::ROW,
+                method,
+                options,
+                &mut SeededRng::new(0),
+            )
+            .unwrap()
+    }
+
+    fn assert_close(actual: &Array2<f64>, expected: [[f64; 3]; 3]) {
+        for (row, expected_row) in expected.iter().enumerate() {
+            for (col, &e) in expected_row.iter().enumerate() {
+                let a = actual[[row, col]];
+                assert!((a - e).abs() < 1e-12, "[{}, {}]: {} != {}", row, col, a, e);
+            }
+        }
+    }
+
+    #[test]
+    fn ora_matches_reference() {
+        let results = network().ora(&[0, 2, 6], 8).unwrap();
+        let expected = [
+            (vec![0, 2], 0.2857142857142857, 8.0, 0.5625),
+            (vec![], 1.0, 0.0, 1.0),
+            (vec![6], 0.375, f64::INFINITY, 0.5625),
+        ];
+        for (result, (overlap, pvalue, odds_ratio, padj)) in results.iter().zip(expected) {
+            assert_eq!(result.overlap, overlap);
+            assert!((result.pvalue - pvalue).abs() < 1e-12);
+            assert_eq!(result.odds_ratio, odds_ratio);
+            assert!((result.padj - padj).abs() < 1e-12);
+        }
+
+        let options = ScoringOptions {
+            top_fraction: 0.25,
+            ..ScoringOptions::default()
+        };
+        assert_close(
+            &score(ScoringMethod::Ora, &options),
+            [
+                [0.9700367766225568, 0.0, 0.0],
+                [0.0, 0.6690067809585756, 0.0],
+                [0.19188552623891314, 0.0, 0.6020599913279624],
+            ],
+        );
+    }
+
+    #[test]
+    fn gsea_matches_reference() {
+        let stats = expression().row(2).to_vec();
+        let result = &network().gsea(&stats, 0, &mut SeededRng::new(0))[0];
+        assert!((result.es - 0.6387096774193548).abs() < 1e-12);
+        assert_eq!(result.leading_edge, vec![2, 5]);
+        assert!(result.nes.is_nan());
+
+        assert_close(
+            &score(ScoringMethod::Gsea, &ScoringOptions::default()),
+            [
+                [1.0, -0.75, -0.8571428571428571],
+                [-1.0, 1.0, -0.5714285714285714],
+                [0.6387096774193548, -0.75, 1.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn gsea_scores_draw_distinct_streams_per_observation_and_pathway() {
+        // Two identical pathways scored on two identical observations: any reuse of
+        // a random stream across (observation, pathway) pairs yields equal scores.
+        let network = Network::from_usize_parts(
+            vec!["A".into(), "B".into()],
+            vec![0, 3],
+            vec![3, 3],
+            vec![0, 2, 5, 0, 2, 5],
+            vec![1.0; 6],
+        )
+        .unwrap();
+        let row = expression().row(0).to_owned();
+        let expression = ndarray::stack![ndarray::Axis(0), row, row];
+        let options = ScoringOptions {
+            permutations: 50,
+            ..ScoringOptions::default()
+        };
+        let scores: Array2<f64> = network
+            .score_matrix(
+                &expression,
+                Direction::ROW,
+                ScoringMethod::Gsea,
+                &options,
+                &mut SeededRng::new(7),
+            )
+            .unwrap();
+        let mut values: Vec<f64> = scores.iter().copied().collect();
+        values.sort_by(f64::total_cmp);
+        values.dedup();
+        assert_eq!(values.len(), 4);
+    }
+
+    #[test]
+    fn aucell_matches_reference() {
+        let options = ScoringOptions {
+            top_fraction: 0.5,
+            ..ScoringOptions::default()
+        };
+        assert_close(
+            &score(ScoringMethod::AuCell, &options),
+            [
+                [1.0, 0.1, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.5555555555555556, 0.1, 1.0],
+            ],
+        );
+    }
+
+    #[test]
+    fn ssgsea_matches_reference() {
+        assert_close(
+            &score(ScoringMethod::SsGsea, &ScoringOptions::default()),
+            [
+                [
+                    0.5056119909577758,
+                    -0.2877132904237779,
+                    -0.35900209911058645,
+                ],
+                [
+                    -0.49127291114968696,
+                    0.508727088850313,
+                    -0.07180041982211728,
+                ],
+                [0.24364448771671385, -0.41335283357210667, 0.502602938754821],
+            ],
+        );
+    }
+
+    #[test]
+    fn ulm_matches_reference() {
+        assert_close(
+            &score(ScoringMethod::Ulm, &ScoringOptions::default()),
+            [
+                [0.7813387990185491, -0.9199500091389435, -1.0092389028223725],
+                [
+                    -0.8117510506263886,
+                    0.6036246885958038,
+                    -0.28602577589751377,
+                ],
+                [0.6904581714193033, -1.0751068079948904, 2.0226752154694356],
+            ],
+        );
+    }
+
+    #[test]
+    fn wmean_matches_reference() {
+        assert_close(
+            &score(ScoringMethod::WMean, &ScoringOptions::default()),
+            [
+                [1.975, 0.8714285714285716, 0.7],
+                [0.4, 2.3285714285714287, 2.0],
+                [2.025, 0.6714285714285715, 5.5],
+            ],
+        );
+    }
+
+    #[test]
+    fn score_matrix_accepts_observations_along_columns() {
+        let options = ScoringOptions {
+            permutations: 20,
+            ..ScoringOptions::default()
+        };
+        let transposed = expression().t().to_owned();
+        for method in [
+            ScoringMethod::Gsea,
+            ScoringMethod::WMean,
+            ScoringMethod::Ulm,
+        ] {
+            let by_column: Array2<f64> = network()
+                .score_matrix(
+                    &transposed,
+                    Direction::COLUMN,
+                    method,
+                    &options,
+                    &mut SeededRng::new(0),
+                )
+                .unwrap();
+            assert_eq!(by_column, score(method, &options));
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Serde support for `GenericPathwayNetwork`.
+//!
+//! The network is serialized as a list of pathways, each holding its name, gene
+//! indices and weights, rather than the packed `starts`/`offsets` buffers:
+//!
+//! ```json
+//! {
+//!   "pathways": [ { "name": "P1", "genes": [0, 4], "weights": [1.0, 0.5] } ],
+//!   "feature_names": ["CD3E", "CD4", "CD8A", "MS4A1", "NKG7"]
+//! }
+//! ```
+//!
+//! `weights` may be omitted when deserializing, in which case every gene is weighted with 1.
+//! `feature_names` is only written for networks that store them and may be omitted.
+//! Networks that record edge signs, e.g. after `rank_weights`, additionally write
+//! `"signs": [1, -1]` for every pathway; pathways without `signs` take them from the
+//! signs of their weights.
+
+use super::{EdgeSign, GenericPathwayNetwork};
+use crate::traits::{FloatOps, UIndex};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize)]
+struct NetworkRef<'a, W, I> {
+    pathways: Vec<PathwayRef<'a, W, I>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    feature_names: Option<&'a [String]>,
+}
+
+#[derive(Serialize)]
+struct PathwayRef<'a, W, I> {
+    name: &'a str,
+    genes: &'a [I],
+    weights: &'a [W],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signs: Option<Vec<i8>>,
+}
+
+#[derive(Deserialize)]
+struct NetworkOwned<W> {
+    pathways: Vec<PathwayOwned<W>>,
+    #[serde(default)]
+    feature_names: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct PathwayOwned<W> {
+    name: String,
+    genes: Vec<usize>,
+    weights: Option<Vec<W>>,
+    #[serde(default)]
+    signs: Option<Vec<i8>>,
+}
+
+impl<W: FloatOps + Serialize, I: UIndex + Serialize> Serialize for GenericPathwayNetwork<W, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let pathways = self
+            .iter()
+            .enumerate()
+            .map(|(idx, (name, genes, weights))| PathwayRef {
+                name,
+                genes,
+                weights,
+                signs: self
+                    .gather_signs(self.pathway_range(idx))
+                    .map(|signs| signs.into_iter().map(EdgeSign::to_i8).collect()),
+            })
+            .collect();
+        NetworkRef {
+            pathways,
+            feature_names: self.feature_names(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, W: FloatOps + Deserialize<'de>, I: UIndex> Deserialize<'de>
+    for GenericPathwayNetwork<W, I>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = NetworkOwned::<W>::deserialize(deserializer)?;
+        let n_pathways = repr.pathways.len();
+        let total: usize = repr.pathways.iter().map(|p| p.genes.len()).sum();
+
+        let mut names = Vec::with_capacity(n_pathways);
+        let mut starts = Vec::with_capacity(n_pathways);
+        let mut offsets = Vec::with_capacity(n_pathways);
+        let mut cnct = Vec::with_capacity(total);
+        let mut weights = Vec::with_capacity(total);
+        let mut signs = Vec::with_capacity(total);
+        let mut record_signs = false;
+
+        for pathway in repr.pathways {
+            let len = pathway.genes.len();
+            let start = weights.len();
+            match pathway.weights {
+                Some(w) if w.len() != len => {
+                    return Err(D::Error::custom(format!(
+                        "pathway '{}' has {} genes but {} weights",
+                        pathway.name,
+                        len,
+                        w.len()
+                    )));
+                }
+                Some(w) => weights.extend(w),
+                None => weights.extend(std::iter::repeat_n(W::one(), len)),
+            }
+            match pathway.signs {
+                Some(codes) if codes.len() != len => {
+                    return Err(D::Error::custom(format!(
+                        "pathway '{}' has {} genes but {} signs",
+                        pathway.name,
+                        len,
+                        codes.len()
+                    )));
+                }
+                Some(codes) => {
+                    record_signs = true;
+                    for code in codes {
+                        signs.push(EdgeSign::from_i8(code).ok_or_else(|| {
+                            D::Error::custom(format!(
+                                "pathway '{}' has the invalid sign {}",
+                                pathway.name, code
+                            ))
+                        })?);
+                    }
+                }
+                None => signs.extend(weights[start..].iter().map(|&w| EdgeSign::of(w))),
+            }
+            starts.push(cnct.len());
+            offsets.push(len);
+            cnct.extend(pathway.genes);
+            names.push(pathway.name);
+        }
+
+        let mut network =
+            GenericPathwayNetwork::from_usize_parts(names, starts, offsets, cnct, weights)
+                .map_err(D::Error::custom)?;
+        if record_signs {
+            network.signs = Some(signs);
+        }
+        if let Some(feature_names) = repr.feature_names {
+            network
+                .set_feature_names(feature_names)
+                .map_err(D::Error::custom)?;
+        }
+        Ok(network)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{EdgeSign, PathwayNetwork, TieMethod};
+
+    #[test]
+    fn round_trip_keeps_feature_names() {
+        let mut network = PathwayNetwork::new(
+            vec!["P1".to_string(), "P2".to_string()],
+            vec![0, 2],
+            vec![2, 1],
+            vec![0, 2, 1],
+            vec![1.0, 0.5, -2.0],
+        );
+        let json = serde_json::to_string(&network).unwrap();
+        assert!(!json.contains("feature_names"));
+        assert_eq!(
+            serde_json::from_str::<PathwayNetwork>(&json).unwrap(),
+            network
+        );
+
+        network
+            .set_feature_names(vec!["CD3E".into(), "CD4".into(), "MS4A1".into()])
+            .unwrap();
+        let json = serde_json::to_string(&network).unwrap();
+        assert_eq!(
+            serde_json::from_str::<PathwayNetwork>(&json).unwrap(),
+            network
+        );
+    }
+
+    #[test]
+    fn round_trip_keeps_recorded_signs() {
+        let mut network = PathwayNetwork::new(
+            vec!["P1".to_string()],
+            vec![0],
+            vec![3],
+            vec![0, 2, 1],
+            vec![1.0, 0.5, -2.0],
+        );
+        network.rank_weights(TieMethod::Average, false);
+        let json = serde_json::to_string(&network).unwrap();
+        assert!(json.contains("\"signs\":[1,1,-1]"));
+        let restored = serde_json::from_str::<PathwayNetwork>(&json).unwrap();
+        assert_eq!(restored, network);
+        assert_eq!(
+            restored.get_pathway_signs(0),
+            [EdgeSign::Positive, EdgeSign::Positive, EdgeSign::Negative]
+        );
+
+        let invalid = r#"{"pathways":[{"name":"P1","genes":[0],"signs":[2]}]}"#;
+        assert!(serde_json::from_str::<PathwayNetwork>(invalid).is_err());
+    }
+}
@@ -0,0 +1,305 @@
+use super::GenericPathwayNetwork;
+use crate::traits::{FloatOps, UIndex};
+
+/// The sign of a (pathway, gene) edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeSign {
+    /// Activation, i.e. a positive weight
+    Positive,
+    /// Inhibition, i.e. a negative weight
+    Negative,
+    /// An edge with a weight of exactly zero
+    Zero,
+}
+
+impl EdgeSign {
+    /// Returns the sign of a weight.
+    pub fn of<W: FloatOps>(weight: W) -> Self {
+        if weight > W::zero() {
+            Self::Positive
+        } else if weight < W::zero() {
+            Self::Negative
+        } else {
+            Self::Zero
+        }
+    }
+
+    /// Encodes the sign as `1`, `-1` or `0` for the serialized formats.
+    pub(crate) fn to_i8(self) -> i8 {
+        match self {
+            Self::Positive => 1,
+            Self::Negative => -1,
+            Self::Zero => 0,
+        }
+    }
+
+    /// Decodes a sign written by `to_i8`, or `None` for any other value.
+    pub(crate) fn from_i8(code: i8) -> Option<Self> {
+        match code {
+            1 => Some(Self::Positive),
+            -1 => Some(Self::Negative),
+            0 => Some(Self::Zero),
+            _ => None,
+        }
+    }
+}
+
+/// The targets of a pathway split by edge sign, with weights stored as edge strengths.
+///
+/// The strength of an edge is its weight with the sign of inhibitory edges removed,
+/// i.e. the absolute weight unless the weights were transformed: ranks after
+/// `rank_weights`, and centered strengths, negative for edges weaker than the mean,
+/// after `normalize_weights` with `WeightNormalization::MeanCenter`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SignedTargets<W = f32> {
+    /// Activated genes
+    pub positive: Vec<usize>,
+    /// Strengths of the activating edges
+    pub positive_weights: Vec<W>,
+    /// Inhibited genes
+    pub negative: Vec<usize>,
+    /// Strengths of the inhibiting edges
+    pub negative_weights: Vec<W>,
+}
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Returns `true` if any edge is inhibitory.
+    ///
+    /// Signed priors such as DoRothEA or CollecTRI encode inhibition as negative
+    /// weights, so the sign of an edge is the sign of its weight and its strength is
+    /// the absolute value. `rank_weights` and `normalize_weights` with
+    /// `WeightNormalization::MeanCenter` transform the strengths, after which the sign
+    /// of a weight need not match the sign of its edge, so they record the signs first;
+    /// the signed accessors then keep using the recorded signs.
+    pub fn is_signed(&self) -> bool {
+        match &self.signs {
+            Some(signs) => signs.contains(&EdgeSign::Negative),
+            None => self.weights.iter().any(|&w| w < W::zero()),
+        }
+    }
+
+    /// Returns the sign of every gene of a pathway, in the order of `get_pathway_features`.
+    pub fn get_pathway_signs(&self, idx: usize) -> Vec<EdgeSign> {
+        self.pathway_range(idx)
+            .map(|position| self.edge_sign(position))
+            .collect()
+    }
+
+    /// Splits the targets of a pathway into activated and inhibited genes.
+    ///
+    /// Edges with a sign of zero belong to neither set.
+    pub fn get_pathway_signed_targets(&self, idx: usize) -> SignedTargets<W> {
+        let mut targets = SignedTargets::default();
+        for position in self.pathway_range(idx) {
+            let gene = self.cnct[position].to_index();
+            let strength = self.edge_strength(position);
+            match self.edge_sign(position) {
+                EdgeSign::Positive => {
+                    targets.positive.push(gene);
+                    targets.positive_weights.push(strength);
+                }
+                EdgeSign::Negative => {
+                    targets.negative.push(gene);
+                    targets.negative_weights.push(strength);
+                }
+                EdgeSign::Zero => {}
+            }
+        }
+        targets
+    }
+
+    /// Computes the strength-weighted sums of `values` over the positive and the
+    /// negative targets of a pathway.
+    ///
+    /// # Arguments
+    /// * `idx` - The pathway index
+    /// * `values` - One value per feature, e.g. per-gene statistics
+    ///
+    /// # Returns
+    /// `(positive_sum, negative_sum)`, both computed with the edge strengths, which
+    /// are non-negative unless the weights were mean-centered
+    pub fn signed_weighted_sums(&self, idx: usize, values: &[W]) -> (W, W) {
+        let mut positive = W::zero();
+        let mut negative = W::zero();
+        for position in self.pathway_range(idx) {
+            let value = self.edge_strength(position) * values[self.cnct[position].to_index()];
+            match self.edge_sign(position) {
+                EdgeSign::Positive => positive += value,
+                EdgeSign::Negative => negative += value,
+                EdgeSign::Zero => {}
+            }
+        }
+        (positive, negative)
+    }
+
+    /// Scores every pathway with a sign-aware combination function.
+    ///
+    /// `combine` receives the positive and negative sums of `signed_weighted_sums`
+    /// and returns the pathway score, e.g. `|pos, neg| pos - neg` for a net
+    /// activation score.
+    ///
+    /// # Arguments
+    /// * `values` - One value per feature, e.g. per-gene statistics
+    /// * `combine` - Maps `(positive_sum, negative_sum)` to a pathway score
+    pub fn score_signed<F>(&self, values: &[W], combine: F) -> Vec<W>
+    where
+        F: Fn(W, W) -> W,
+    {
+        (0..self.get_num_pathways())
+            .map(|idx| {
+                let (positive, negative) = self.signed_weighted_sums(idx, values);
+                combine(positive, negative)
+            })
+            .collect()
+    }
+
+    /// Records the sign of every edge before a transform that can change the sign of
+    /// the weights; signs that are already recorded are kept.
+    pub(crate) fn record_signs(&mut self) {
+        if self.signs.is_none() {
+            self.signs = Some(self.weights.iter().map(|&w| EdgeSign::of(w)).collect());
+        }
+    }
+
+    /// Returns the sign of the edge at `position` of the packed buffers.
+    pub(crate) fn edge_sign(&self, position: usize) -> EdgeSign {
+        match &self.signs {
+            Some(signs) => signs[position],
+            None => EdgeSign::of(self.weights[position]),
+        }
+    }
+
+    /// Returns the strength of the edge at `position` of the packed buffers, its
+    /// weight with the sign of an inhibitory edge removed.
+    pub(crate) fn edge_strength(&self, position: usize) -> W {
+        match self.edge_sign(position) {
+            EdgeSign::Negative => -self.weights[position],
+            _ => self.weights[position],
+        }
+    }
+
+    /// Returns the recorded signs of the edges at `positions` of the packed buffers,
+    /// or `None` if the signs of this network follow its weights.
+    pub(crate) fn gather_signs(
+        &self,
+        positions: impl IntoIterator<Item = usize>,
+    ) -> Option<Vec<EdgeSign>> {
+        let signs = self.signs.as_ref()?;
+        Some(positions.into_iter().map(|p| signs[p]).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ConflictPolicy, TieMethod, WeightCombination, WeightNormalization};
+
+    type Network = GenericPathwayNetwork<f64>;
+
+    fn network() -> Network {
+        Network::new(
+            vec!["P1".to_string(), "P2".to_string()],
+            vec![0, 3],
+            vec![3, 2],
+            vec![0, 1, 2, 1, 3],
+            vec![3.0, -2.0, 1.0, -3.0, 1.0],
+        )
+    }
+
+    #[test]
+    fn weight_transforms_keep_edge_signs() {
+        use EdgeSign::{Negative, Positive};
+
+        // strengths [3, 2, 1] and [3, 1] are centered around their means 2 and 2
+        let mut centered = network();
+        centered.normalize_weights(WeightNormalization::MeanCenter);
+        assert_eq!(
+            centered.get_pathway_features_and_weights(0).1,
+            [1.0, 0.0, -1.0]
+        );
+        assert_eq!(
+            centered.get_pathway_signs(0),
+            [Positive, Negative, Positive]
+        );
+        assert_eq!(
+            centered.get_pathway_signed_targets(1),
+            SignedTargets {
+                positive: vec![3],
+                positive_weights: vec![-1.0],
+                negative: vec![1],
+                negative_weights: vec![1.0],
+            }
+        );
+
+        // the strongest inhibitor of P2 gets the highest rank
+        let mut ranked = network();
+        ranked.rank_weights(TieMethod::Average, false);
+        assert!(ranked.is_signed());
+        assert_eq!(
+            ranked.get_pathway_features_and_weights(0).1,
+            [3.0, -2.0, 1.0]
+        );
+        assert_eq!(ranked.get_pathway_features_and_weights(1).1, [-2.0, 1.0]);
+        assert_eq!(ranked.get_pathway_signs(0), [Positive, Negative, Positive]);
+        assert_eq!(
+            ranked.signed_weighted_sums(0, &[1.0, 2.0, 3.0, 4.0]),
+            (6.0, 4.0)
+        );
+        assert_eq!(
+            ranked.signed_weighted_sums(1, &[1.0, 2.0, 3.0, 4.0]),
+            (4.0, 4.0)
+        );
+        let mut quantiles = network();
+        quantiles.rank_weights(TieMethod::Average, true);
+        assert_eq!(
+            quantiles.get_pathway_signed_targets(1).negative_weights,
+            [1.0]
+        );
+
+        // networks without inhibitors are transformed as before
+        let mut unsigned = Network::new(
+            vec!["P".to_string()],
+            vec![0],
+            vec![2],
+            vec![0, 1],
+            vec![1.0, 5.0],
+        );
+        unsigned.normalize_weights(WeightNormalization::MeanCenter);
+        assert_eq!(unsigned.get_pathway_features_and_weights(0).1, [-2.0, 2.0]);
+        assert_eq!(unsigned.get_pathway_signs(0), [Positive, Positive]);
+
+        // networks derived from a transformed one keep its signs
+        assert_eq!(
+            ranked.filter_by_size(2, 2).get_pathway_signs(0),
+            [Negative, Positive]
+        );
+        let first_gene = Network::new(vec!["P1".to_string()], vec![0], vec![1], vec![0], vec![1.0]);
+        assert_eq!(
+            ranked.difference(&first_gene).get_pathway_signs(0),
+            [Negative, Positive]
+        );
+        let merged =
+            Network::merge(&[&ranked, &network()], ConflictPolicy::RenameWithSuffix).unwrap();
+        assert_eq!(merged.get_pathway_signs(1), [Negative, Positive]);
+        assert_eq!(merged.get_pathway_signs(3), [Negative, Positive]);
+        assert_eq!(
+            ranked
+                .intersect(&network(), WeightCombination::Right)
+                .get_pathway_signs(0),
+            [Positive, Negative, Positive]
+        );
+
+        let features: Vec<String> = (0..4).map(|g| format!("g{}", g)).collect();
+        let reversed: Vec<String> = features.iter().rev().cloned().collect();
+        let (subset, _) = ranked
+            .subset_to_features(&reversed, &features, None)
+            .unwrap();
+        assert_eq!(subset.get_pathway_signs(1), [Negative, Positive]);
+        assert_eq!(subset.get_pathway_signed_targets(1).negative, [2]);
+
+        let mut pruned = ranked.select_pathways(|_, _, _| true);
+        assert_eq!(pruned.prune_frequent_genes(0.5, 1), [1]);
+        assert!(!pruned.is_signed());
+        assert_eq!(pruned.get_pathway_signs(0), [Positive, Positive]);
+    }
+}
@@ -0,0 +1,153 @@
+use super::GenericPathwayNetwork;
+use crate::traits::{FloatOps, UIndex};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Summary statistics of a pathway collection, as returned by `summary`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetworkSummary {
+    /// Number of pathways
+    pub num_pathways: usize,
+    /// Size of the smallest pathway
+    pub min_size: usize,
+    /// Median pathway size
+    pub median_size: f64,
+    /// Size of the largest pathway
+    pub max_size: usize,
+    /// Number of (pathway, gene) memberships over all pathways
+    pub total_genes: usize,
+    /// Number of distinct genes contained in at least one pathway
+    pub unique_genes: usize,
+    /// Fraction of the feature space covered by the pathways; only known if the
+    /// network stores its feature names
+    pub feature_coverage: Option<f64>,
+    /// Smallest weight
+    pub min_weight: f64,
+    /// Mean weight
+    pub mean_weight: f64,
+    /// Largest weight
+    pub max_weight: f64,
+    /// Number of negative weights
+    pub negative_weights: usize,
+}
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Computes size, coverage and weight statistics of the collection.
+    ///
+    /// Size and weight statistics of an empty network are 0.
+    pub fn summary(&self) -> NetworkSummary {
+        let mut sizes: Vec<usize> = self.offsets.iter().map(|o| o.to_index()).collect();
+        sizes.sort_unstable();
+        let median_size = match sizes.len() {
+            0 => 0.0,
+            n if n % 2 == 1 => sizes[n / 2] as f64,
+            n => (sizes[n / 2 - 1] + sizes[n / 2]) as f64 / 2.0,
+        };
+
+        let mut unique: HashSet<usize> = HashSet::new();
+        let mut total_genes = 0usize;
+        let mut weight_sum = 0.0;
+        let mut min_weight = f64::INFINITY;
+        let mut max_weight = f64::NEG_INFINITY;
+        let mut negative_weights = 0usize;
+        for (_, genes, weights) in self.iter() {
+            total_genes += genes.len();
+            unique.extend(genes.iter().map(|g| g.to_index()));
+            for w in weights {
+                let w = w.to_f64().unwrap();
+                weight_sum += w;
+                min_weight = min_weight.min(w);
+                max_weight = max_weight.max(w);
+                if w < 0.0 {
+                    negative_weights += 1;
+                }
+            }
+        }
+        if total_genes == 0 {
+            min_weight = 0.0;
+            max_weight = 0.0;
+        }
+
+        NetworkSummary {
+            num_pathways: self.get_num_pathways(),
+            min_size: sizes.first().copied().unwrap_or(0),
+            median_size,
+            max_size: sizes.last().copied().unwrap_or(0),
+            total_genes,
+            unique_genes: unique.len(),
+            feature_coverage: self
+                .feature_names
+                .as_ref()
+                .filter(|names| !names.is_empty())
+                .map(|names| unique.len() as f64 / names.len() as f64),
+            min_weight,
+            mean_weight: if total_genes > 0 {
+                weight_sum / total_genes as f64
+            } else {
+                0.0
+            },
+            max_weight,
+            negative_weights,
+        }
+    }
+}
+
+impl fmt::Display for NetworkSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<21}{}", "pathways", self.num_pathways)?;
+        writeln!(
+            f,
+            "{:<21}{} / {} / {}",
+            "size min/med/max", self.min_size, self.median_size, self.max_size
+        )?;
+        writeln!(f, "{:<21}{}", "memberships", self.total_genes)?;
+        writeln!(f, "{:<21}{}", "unique genes", self.unique_genes)?;
+        if let Some(coverage) = self.feature_coverage {
+            writeln!(f, "{:<21}{:.1}%", "feature coverage", coverage * 100.0)?;
+        }
+        writeln!(
+            f,
+            "{:<21}{:.4} / {:.4} / {:.4}",
+            "weight min/mean/max", self.min_weight, self.mean_weight, self.max_weight
+        )?;
+        write!(f, "{:<21}{}", "negative weights", self.negative_weights)
+    }
+}
+
+impl<W: FloatOps, I: UIndex> fmt::Display for GenericPathwayNetwork<W, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.summary())
+    }
+}
+
+/// Number of pathway names listed by the `Debug` representation of a network.
+const DEBUG_NAMES: usize = 5;
+
+impl<W: FloatOps, I: UIndex> fmt::Debug for GenericPathwayNetwork<W, I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GenericPathwayNetwork")
+            .field("num_pathways", &self.get_num_pathways())
+            .field("num_genes", &self.cnct.len())
+            .field(
+                "num_features",
+                &self.feature_names.as_ref().map(|names| names.len()),
+            )
+            .field("pathways", &NamePreview(&self.names))
+            .finish()
+    }
+}
+
+/// Lists the first few names of a slice.
+struct NamePreview<'a>(&'a [String]);
+
+impl fmt::Debug for NamePreview<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut list = f.debug_list();
+        list.entries(self.0.iter().take(DEBUG_NAMES));
+        if self.0.len() > DEBUG_NAMES {
+            list.finish_non_exhaustive()
+        } else {
+            list.finish()
+        }
+    }
+}
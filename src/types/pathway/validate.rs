@@ -0,0 +1,150 @@
+use super::GenericPathwayNetwork;
+use crate::traits::{FloatOps, UIndex};
+use thiserror::Error;
+
+/// Inconsistency found by `GenericPathwayNetwork::validate`.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum NetworkError {
+    /// Two buffers that must have the same length differ
+    #[error("Buffer '{buffer}' has length {found} but {expected} was expected!")]
+    LengthMismatch {
+        /// Name of the buffer checked against the reference buffer
+        buffer: &'static str,
+        /// Length of the reference buffer
+        expected: usize,
+        /// Length of the checked buffer
+        found: usize,
+    },
+    /// The gene range of a pathway extends past the end of the gene buffer
+    #[error("Pathway {pathway} ends at {end} but the gene buffer only has {len} entries!")]
+    RangeOutOfBounds {
+        /// Index of the pathway
+        pathway: usize,
+        /// End of the gene range
+        end: usize,
+        /// Length of the gene buffer
+        len: usize,
+    },
+    /// The gene ranges of two pathways overlap
+    #[error("Pathways {first} and {second} overlap!")]
+    OverlappingPathways {
+        /// Index of the pathway starting first
+        first: usize,
+        /// Index of the pathway starting inside the range of `first`
+        second: usize,
+    },
+    /// A gene index lies outside the feature space
+    #[error("Gene {gene} of pathway {pathway} exceeds the feature space of size {n_features}!")]
+    GeneOutOfRange {
+        /// Index of the pathway
+        pathway: usize,
+        /// The offending gene index
+        gene: usize,
+        /// Size of the feature space
+        n_features: usize,
+    },
+    /// A weight is NaN or infinite
+    #[error("Weight {position} of pathway {pathway} is not finite!")]
+    NonFiniteWeight {
+        /// Index of the pathway
+        pathway: usize,
+        /// Position of the weight within the pathway
+        position: usize,
+    },
+}
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Checks the internal consistency of the network.
+    ///
+    /// Verifies that the pathway buffers have matching lengths, that every pathway
+    /// range lies inside the gene buffer without overlapping another one, that every
+    /// gene index is below `n_features` and that every weight is finite.
+    ///
+    /// # Arguments
+    /// * `n_features` - Size of the feature space the gene indices refer to
+    ///
+    /// # Returns
+    /// `Ok(())`, or the first inconsistency found
+    pub fn validate(&self, n_features: usize) -> Result<(), NetworkError> {
+        self.validate_layout()?;
+        for (pathway, (genes, weights)) in (0..self.get_num_pathways())
+            .map(|idx| self.get_pathway_features_and_weights(idx))
+            .enumerate()
+        {
+            if let Some(gene) = genes
+                .iter()
+                .map(|g| g.to_index())
+                .find(|&g| g >= n_features)
+            {
+                return Err(NetworkError::GeneOutOfRange {
+                    pathway,
+                    gene,
+                    n_features,
+                });
+            }
+            if let Some(position) = weights
+                .iter()
+                .position(|&w| !num_traits::Float::is_finite(w))
+            {
+                return Err(NetworkError::NonFiniteWeight { pathway, position });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the buffer lengths and pathway ranges, everything `validate` checks
+    /// that does not depend on the feature space or the weight values.
+    pub(crate) fn validate_layout(&self) -> Result<(), NetworkError> {
+        let n_pathways = self.names.len();
+        for (buffer, found) in [
+            ("starts", self.starts.len()),
+            ("offsets", self.offsets.len()),
+        ] {
+            if found != n_pathways {
+                return Err(NetworkError::LengthMismatch {
+                    buffer,
+                    expected: n_pathways,
+                    found,
+                });
+            }
+        }
+        for (buffer, found) in [
+            ("weights", Some(self.weights.len())),
+            ("signs", self.signs.as_ref().map(Vec::len)),
+        ] {
+            if let Some(found) = found
+                && found != self.cnct.len()
+            {
+                return Err(NetworkError::LengthMismatch {
+                    buffer,
+                    expected: self.cnct.len(),
+                    found,
+                });
+            }
+        }
+
+        let mut ranges: Vec<(usize, usize, usize)> = Vec::with_capacity(n_pathways);
+        for pathway in 0..n_pathways {
+            let start = self.starts[pathway].to_index();
+            let end = start.saturating_add(self.offsets[pathway].to_index());
+            if end > self.cnct.len() {
+                return Err(NetworkError::RangeOutOfBounds {
+                    pathway,
+                    end,
+                    len: self.cnct.len(),
+                });
+            }
+            if end > start {
+                ranges.push((start, end, pathway));
+            }
+        }
+        ranges.sort_unstable();
+        for pair in ranges.windows(2) {
+            let ((_, end, first), (start, _, second)) = (pair[0], pair[1]);
+            if start < end {
+                return Err(NetworkError::OverlappingPathways { first, second });
+            }
+        }
+        Ok(())
+    }
+}
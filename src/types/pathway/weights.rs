@@ -0,0 +1,129 @@
+use super::{EdgeSign, GenericPathwayNetwork};
+use crate::stats::rank;
+use crate::traits::{FloatOps, UIndex};
+
+/// Per-pathway rescaling applied by `normalize_weights`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightNormalization {
+    /// Divide by the sum of absolute weights
+    L1,
+    /// Divide by the Euclidean norm of the weights
+    L2,
+    /// Divide by the largest absolute weight
+    MaxAbs,
+    /// Subtract the mean weight
+    MeanCenter,
+}
+
+/// Rank assigned to tied weights by `rank_weights`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieMethod {
+    /// Average of the ranks the tied weights would occupy
+    Average,
+    /// Lowest rank of the tied weights
+    Min,
+    /// Highest rank of the tied weights
+    Max,
+    /// Rank among the distinct values, so ranks have no gaps
+    Dense,
+    /// Distinct ranks in order of position (ordinal ranking)
+    First,
+}
+
+impl<W: FloatOps, I: UIndex> GenericPathwayNetwork<W, I> {
+    /// Rescales the weights of every pathway in place.
+    ///
+    /// Pathways whose norm is zero are left unchanged. The rescaling applies to the
+    /// strengths of the edges, the weights with the sign of inhibitory edges removed,
+    /// and the sign is put back afterwards, so inhibitors of a signed network are
+    /// scaled like activators of the same strength. `MeanCenter` subtracts the mean
+    /// strength, which turns edges weaker than the mean into negative strengths; it
+    /// records the edge signs first, as the sign of the centered weight no longer
+    /// tells activation from inhibition.
+    pub fn normalize_weights(&mut self, mode: WeightNormalization) {
+        if mode == WeightNormalization::MeanCenter {
+            self.record_signs();
+        }
+        self.transform_strengths(|strengths| normalize_slice(strengths, mode));
+    }
+
+    /// Replaces the weights of every pathway by their ranks within the pathway.
+    ///
+    /// Edges are ranked by strength, the weight with the sign of inhibitory edges
+    /// removed, and keep their sign: the weakest edge of a pathway gets rank 1 and the
+    /// strongest rank `n`, where `n` is the size of the pathway, so the strongest
+    /// inhibitor of a signed network gets the weight `-n`. With `quantiles` the ranks
+    /// are divided by `n`, mapping them into `(0, 1]` so pathways of different sizes
+    /// are comparable. NaN weights are ranked above all other weights. The edge signs
+    /// are recorded first, so edges with a weight of zero keep their sign as well.
+    ///
+    /// # Arguments
+    /// * `ties` - How ranks are assigned to equal weights
+    /// * `quantiles` - Whether to divide the ranks by the pathway size
+    pub fn rank_weights(&mut self, ties: TieMethod, quantiles: bool) {
+        self.record_signs();
+        self.transform_strengths(|strengths| rank_slice(strengths, ties, quantiles));
+    }
+
+    /// Returns a copy of the network with the weights of every pathway rescaled.
+    ///
+    /// See `normalize_weights` for details.
+    pub fn with_normalized_weights(&self, mode: WeightNormalization) -> Self {
+        let mut network = self.select_pathways(|_, _, _| true);
+        network.normalize_weights(mode);
+        network
+    }
+
+    /// Applies `transform` to the edge strengths of every pathway and restores the
+    /// edge signs on the results.
+    fn transform_strengths<F>(&mut self, transform: F)
+    where
+        F: Fn(&mut [W]),
+    {
+        let mut strengths = Vec::new();
+        for idx in 0..self.get_num_pathways() {
+            let range = self.pathway_range(idx);
+            strengths.clear();
+            strengths.extend(range.clone().map(|position| self.edge_strength(position)));
+            transform(&mut strengths);
+            for (position, strength) in range.zip(&strengths) {
+                self.weights[position] = match self.edge_sign(position) {
+                    EdgeSign::Negative => -*strength,
+                    _ => *strength,
+                };
+            }
+        }
+    }
+}
+
+fn normalize_slice<W: FloatOps>(weights: &mut [W], mode: WeightNormalization) {
+    if weights.is_empty() {
+        return;
+    }
+    let norm = match mode {
+        WeightNormalization::MeanCenter => {
+            let mean =
+                weights.iter().fold(W::zero(), |acc, &w| acc + w) / W::from(weights.len()).unwrap();
+            weights.iter_mut().for_each(|w| *w -= mean);
+            return;
+        }
+        WeightNormalization::L1 => weights
+            .iter()
+            .fold(W::zero(), |acc, &w| acc + num_traits::Float::abs(w)),
+        WeightNormalization::L2 => weights.iter().fold(W::zero(), |acc, &w| acc + w * w).sqrt(),
+        WeightNormalization::MaxAbs => weights.iter().fold(W::zero(), |acc, &w| {
+            num_traits::Float::max(acc, num_traits::Float::abs(w))
+        }),
+    };
+    if norm > W::zero() {
+        weights.iter_mut().for_each(|w| *w = *w / norm);
+    }
+}
+
+fn rank_slice<W: FloatOps>(weights: &mut [W], ties: TieMethod, quantiles: bool) {
+    let scale = if quantiles { weights.len() as f64 } else { 1.0 };
+    let ranks = rank(weights, ties);
+    for (w, rank) in weights.iter_mut().zip(ranks) {
+        *w = W::from_f64(rank / scale).unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+use crate::traits::{MatrixLike, SparseRowAccess, UIndex};
+use anyhow::anyhow;
+
+/// A matrix in compressed sparse row (CSR) layout.
+///
+/// Row `i` occupies the range `indptr[i]..indptr[i + 1]` of `indices` (column
+/// indices) and `data` (values). Column indices within a row are expected to be
+/// unique but do not need to be sorted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsrData<T, I = usize> {
+    nrows: usize,
+    ncols: usize,
+    indptr: Vec<usize>,
+    indices: Vec<I>,
+    data: Vec<T>,
+}
+
+impl<T: Copy, I: UIndex> CsrData<T, I> {
+    /// Creates a CSR matrix from its raw buffers after checking their consistency.
+    ///
+    /// # Arguments
+    /// * `nrows` - The number of rows
+    /// * `ncols` - The number of columns
+    /// * `indptr` - Row pointers of length `nrows + 1`, starting at 0 and non-decreasing
+    /// * `indices` - Column index of every stored value
+    /// * `data` - The stored values
+    pub fn new(
+        nrows: usize,
+        ncols: usize,
+        indptr: Vec<usize>,
+        indices: Vec<I>,
+        data: Vec<T>,
+    ) -> anyhow::Result<Self> {
+        if indptr.len() != nrows + 1 {
+            return Err(anyhow!(
+                "indptr must have length nrows + 1 ({}), got {}!",
+                nrows + 1,
+                indptr.len()
+            ));
+        }
+        if indices.len() != data.len() {
+            return Err(anyhow!(
+                "indices and data must have the same length ({} != {})!",
+                indices.len(),
+                data.len()
+            ));
+        }
+        if indptr[0] != 0 || indptr[nrows] != data.len() {
+            return Err(anyhow!(
+                "indptr must start at 0 and end at the number of stored values!"
+            ));
+        }
+        if indptr.windows(2).any(|w| w[0] > w[1]) {
+            return Err(anyhow!("indptr must be non-decreasing!"));
+        }
+        if let Some(pos) = indices.iter().position(|&c| c.to_index() >= ncols) {
+            return Err(anyhow!(
+                "Column index at position {} is out of bounds for {} columns!",
+                pos,
+                ncols
+            ));
+        }
+        Ok(Self {
+            nrows,
+            ncols,
+            indptr,
+            indices,
+            data,
+        })
+    }
+
+    pub fn indptr(&self) -> &[usize] {
+        &self.indptr
+    }
+
+    pub fn indices(&self) -> &[I] {
+        &self.indices
+    }
+
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Returns the stored values mutably, e.g. for element-wise transforms.
+    pub fn data_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    /// Returns the column indices and the mutable values of every row, e.g. for
+    /// processing rows in parallel.
+    pub(crate) fn rows_mut(&mut self) -> Vec<(&[I], &mut [T])> {
+        let mut rows = Vec::with_capacity(self.nrows);
+        let (mut indices, mut data) = (self.indices.as_slice(), self.data.as_mut_slice());
+        for bounds in self.indptr.windows(2) {
+            let len = bounds[1] - bounds[0];
+            let (row_indices, rest_indices) = indices.split_at(len);
+            let (row_data, rest_data) = data.split_at_mut(len);
+            rows.push((row_indices, row_data));
+            (indices, data) = (rest_indices, rest_data);
+        }
+        rows
+    }
+
+    /// Consumes the matrix and returns `(indptr, indices, data)`.
+    pub fn into_parts(self) -> (Vec<usize>, Vec<I>, Vec<T>) {
+        (self.indptr, self.indices, self.data)
+    }
+}
+
+impl<T: Copy, I: UIndex> MatrixLike<T> for CsrData<T, I> {
+    fn nrows(&self) -> usize {
+        self.nrows
+    }
+
+    fn ncols(&self) -> usize {
+        self.ncols
+    }
+
+    fn for_each_in_row<F: FnMut(usize, T)>(&self, row: usize, mut f: F) {
+        let (indices, values) = self.row_entries(row);
+        for (&col, &val) in indices.iter().zip(values) {
+            f(col.to_index(), val);
+        }
+    }
+
+    fn is_sparse(&self) -> bool {
+        true
+    }
+}
+
+impl<T: Copy, I: UIndex> SparseRowAccess<T> for CsrData<T, I> {
+    type Index = I;
+
+    fn row_entries(&self, row: usize) -> (&[I], &[T]) {
+        let range = self.indptr[row]..self.indptr[row + 1];
+        (&self.indices[range.clone()], &self.data[range])
+    }
+
+    fn nnz(&self) -> usize {
+        self.data.len()
+    }
+}
@@ -0,0 +1,68 @@
+use thiserror::Error;
+
+/// Failure kinds of the edge list validation behind network construction.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum NetworkValidationError {
+    /// Source and target columns have different lengths
+    #[error(
+        "Source and target must have the same length in order to be used for network construction (got {sources} sources and {targets} targets)!"
+    )]
+    LengthMismatch {
+        /// Number of sources
+        sources: usize,
+        /// Number of targets
+        targets: usize,
+    },
+    /// The weight column does not have one entry per edge
+    #[error(
+        "Weights must have the same length as source and target in order to be used for network construction (got {weights} weights for {edges} edges)!"
+    )]
+    WeightLengthMismatch {
+        /// Number of edges
+        edges: usize,
+        /// Number of weights
+        weights: usize,
+    },
+    /// A column of the edge list layout is not part of the header
+    #[error("Column '{name}' not found in the edge list header!")]
+    MissingColumn {
+        /// Name of the missing column
+        name: String,
+    },
+    /// A row of a delimited edge list has too few fields or an unparsable weight
+    #[error("Row {row} of the edge list is malformed: {message}!")]
+    MalformedRow {
+        /// Position of the edge in the edge list
+        row: usize,
+        /// Description of the problem
+        message: String,
+    },
+    /// A delimited edge list could not be read
+    #[error("The edge list could not be read: {message}")]
+    Read {
+        /// Description of the underlying error
+        message: String,
+    },
+    /// The weight of an edge is NaN or infinite
+    #[error("The weight of edge {row} is not finite!")]
+    NonFiniteWeight {
+        /// Position of the edge in the edge list
+        row: usize,
+    },
+    /// The source of an edge equals its target under `SelfLoopPolicy::Error`
+    #[error("Edge {row} connects '{name}' to itself!")]
+    SelfLoop {
+        /// Position of the edge in the edge list
+        row: usize,
+        /// Name of the source and target
+        name: String,
+    },
+    /// A target is listed more than once for the same source under `DuplicatePolicy::Error`
+    #[error("Target '{to}' is listed more than once for source '{from}'!")]
+    DuplicateEdge {
+        /// Name of the source
+        from: String,
+        /// Name of the duplicated target
+        to: String,
+    },
+}
@@ -1,58 +1,1147 @@
-use anyhow::anyhow;
+mod error;
+pub(crate) mod parallel;
+pub(crate) mod stats;
+#[cfg(feature = "io")]
+mod stream;
+
+pub use error::NetworkValidationError;
+#[cfg(feature = "io")]
+pub use stream::ColumnSpec;
+#[cfg(feature = "io")]
+pub use stream::validate_net_from_csv;
+
+use crate::traits::{FloatOps, FloatOpsTS};
+use crate::types::DuplicatePolicy;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Targets and weights of every source of an edge list, as returned by `validate_net`.
+///
+/// Sources are listed in order of their first appearance in the edge list.
+pub type EdgeGroups<W> = Vec<(String, Vec<(String, W)>)>;
+
+/// Case normalization applied to gene symbols by `validate_net`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolCase {
+    /// Keep symbols as they are
+    Preserve,
+    /// Convert symbols to upper case, the human (HGNC) convention
+    Upper,
+    /// Convert symbols to lower case
+    Lower,
+}
+
+impl SymbolCase {
+    /// Applies the case normalization to `name`, borrowing it if nothing changes.
+    pub(crate) fn apply<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::Preserve => Cow::Borrowed(name),
+            Self::Upper if !name.chars().any(char::is_lowercase) => Cow::Borrowed(name),
+            Self::Lower if !name.chars().any(char::is_uppercase) => Cow::Borrowed(name),
+            Self::Upper => Cow::Owned(name.to_uppercase()),
+            Self::Lower => Cow::Owned(name.to_lowercase()),
+        }
+    }
+}
+
+/// Treatment of NaN and infinite edge weights by `validate_net`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WeightPolicy {
+    /// Abort with an error naming the row of the first non-finite weight
+    Error,
+    /// Drop edges with a non-finite weight
+    Drop,
+    /// Clamp infinite weights to the largest finite weight of the same sign in the
+    /// edge list and drop edges with a NaN weight or with an infinite weight that has
+    /// no positive (or negative) finite counterpart
+    Clamp,
+    /// Replace non-finite weights with the given value
+    Replace(f64),
+}
+
+/// Treatment of edges whose source equals their target by `validate_net`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelfLoopPolicy {
+    /// Keep self-loops as regular edges
+    Keep,
+    /// Drop self-loops, e.g. transcription factors listed as their own target
+    Drop,
+    /// Abort with an error naming the row of the first self-loop
+    Error,
+}
+
+/// Options controlling how `validate_net` cleans an edge list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationOptions {
+    /// How a target listed more than once for the same source is resolved
+    pub duplicates: DuplicatePolicy,
+    /// Case normalization applied to source and target names
+    pub case: SymbolCase,
+    /// Replacement names for aliased symbols, e.g. outdated gene symbols; keys are
+    /// matched after case normalization and the replacements are normalized as well
+    pub aliases: HashMap<String, String>,
+    /// How NaN and infinite weights are handled
+    pub weights: WeightPolicy,
+    /// How edges whose source equals their target are handled
+    pub self_loops: SelfLoopPolicy,
+}
+
+impl Default for ValidationOptions {
+    fn default() -> Self {
+        Self {
+            duplicates: DuplicatePolicy::Last,
+            case: SymbolCase::Preserve,
+            aliases: HashMap::new(),
+            weights: WeightPolicy::Error,
+            self_loops: SelfLoopPolicy::Keep,
+        }
+    }
+}
+
+impl ValidationOptions {
+    /// Applies the case normalization and alias map to `name`.
+    ///
+    /// # Returns
+    /// The normalized name and whether an alias was applied
+    pub(crate) fn normalize<'a>(&'a self, name: &'a str) -> (Cow<'a, str>, bool) {
+        let name = self.case.apply(name);
+        match self.aliases.get(name.as_ref()) {
+            Some(alias) => (self.case.apply(alias), true),
+            None => (name, false),
+        }
+    }
+
+    fn normalizes(&self) -> bool {
+        self.case != SymbolCase::Preserve || !self.aliases.is_empty()
+    }
+}
+
+/// Summary of what `validate_net` found and changed in an edge list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Number of edges in the input; 0 for an empty edge list
+    pub num_edges: usize,
+    /// Number of distinct sources kept
+    pub num_sources: usize,
+    /// Number of edges collapsed into an earlier edge with the same source and target
+    pub duplicate_edges: usize,
+    /// Number of edges whose source equals their target
+    pub self_loops: usize,
+    /// Number of edges dropped because their source or target is an empty string
+    pub empty_names_dropped: usize,
+    /// Number of edges with a weight of exactly 0
+    pub zero_weights: usize,
+    /// Number of edges with a negative weight
+    pub negative_weights: usize,
+    /// Number of source and target names replaced through the alias map
+    pub aliased_names: usize,
+    /// Number of NaN or infinite weights, dropped or repaired according to the
+    /// weight policy
+    pub non_finite_weights: usize,
+    /// Number of edges dropped because of a non-finite weight
+    pub non_finite_dropped: usize,
+    /// Number of self-loops dropped under `SelfLoopPolicy::Drop`
+    pub self_loops_dropped: usize,
+}
+
+/// Groups the targets and weights of an edge list by source.
+///
+/// The edge list does not need to be sorted by source; sources are returned in order
+/// of their first appearance, so the output is reproducible across runs. Sources are interned once
+/// and targets are only copied into owned strings after duplicates are resolved, so
+/// borrowed `&str` columns can be passed without allocating per edge. Targets keep
+/// the position of their first occurrence; targets listed more than once for the
+/// same source are resolved according to `options.duplicates`. Source and target
+/// names are normalized according to `options.case` and `options.aliases` before
+/// grouping. Edges with an empty source or target name are dropped and NaN or
+/// infinite weights are handled according to `options.weights`. Self-loops are
+/// kept, dropped or rejected according to `options.self_loops`. With the `rayon`
+/// feature the edges are sharded by source and grouped in parallel; the output and
+/// the reported errors do not depend on the number of threads. An empty edge list is
+/// not an error; it gives no sources and a report with `num_edges` of 0.
+///
+/// # Returns
+/// The targets and weights of every source along with a report of the anomalies
+/// found in the edge list, or an error if the columns differ in length, a weight is not finite under `WeightPolicy::Error`, an edge is
+/// a self-loop under `SelfLoopPolicy::Error` or a target is duplicated under
+/// `DuplicatePolicy::Error`
+pub fn validate_net<S, W>(
+    source: &[S],
+    target: &[S],
+    weights: Option<&[W]>,
+    options: &ValidationOptions,
+) -> Result<(EdgeGroups<W>, ValidationReport), NetworkValidationError>
+where
+    S: AsRef<str> + Sync,
+    W: FloatOpsTS,
+{
+    if !options.normalizes() {
+        return group_edges(source, target, weights, options);
+    }
+    let mut aliased_names = 0;
+    let source = normalize_names(source, options, &mut aliased_names);
+    let target = normalize_names(target, options, &mut aliased_names);
+    let (groups, mut report) = group_edges(&source, &target, weights, options)?;
+    report.aliased_names = aliased_names;
+    Ok((groups, report))
+}
+
+fn normalize_names<'a, S: AsRef<str>>(
+    names: &'a [S],
+    options: &'a ValidationOptions,
+    aliased_names: &mut usize,
+) -> Vec<Cow<'a, str>> {
+    names
+        .iter()
+        .map(|name| {
+            let (name, aliased) = options.normalize(name.as_ref());
+            *aliased_names += aliased as usize;
+            name
+        })
+        .collect()
+}
 
-pub fn validate_net(
-    source: Vec<String>,
-    target: Vec<String>,
-    weights: Option<Vec<f32>>,
-    verbose: bool,
-) -> anyhow::Result<HashMap<String, Vec<(String, f32)>>> {
+fn group_edges<S, W>(
+    source: &[S],
+    target: &[S],
+    weights: Option<&[W]>,
+    options: &ValidationOptions,
+) -> Result<(EdgeGroups<W>, ValidationReport), NetworkValidationError>
+where
+    S: AsRef<str> + Sync,
+    W: FloatOpsTS,
+{
     let len_source = source.len();
     let len_target = target.len();
-    if (len_source != len_target) {
-        return Err(anyhow!(
-            "Source and target must have the same length in order to be used for network construction!"
-        ));
+    if len_source != len_target {
+        return Err(NetworkValidationError::LengthMismatch {
+            sources: len_source,
+            targets: len_target,
+        });
+    }
+    if let Some(we) = weights
+        && we.len() != len_source
+    {
+        return Err(NetworkValidationError::WeightLengthMismatch {
+            edges: len_source,
+            weights: we.len(),
+        });
     }
 
-    let mut map: HashMap<String, Vec<(String, f32)>> = HashMap::new();
-    let mut current_src: String = "".to_string();
-    let mut current_target_weight: HashMap<String, f32> = HashMap::new();
-    for (i, src) in source.iter().enumerate() {
-        if current_src.is_empty() {
-            // never set a value in there
-            current_src = src.clone();
+    let mut report = ValidationReport {
+        num_edges: len_source,
+        ..ValidationReport::default()
+    };
+    let repaired = weights
+        .map(|we| repair_weights(we, options.weights, &mut report))
+        .transpose()?;
+    let weights: Option<&[Option<W>]> = repaired.as_deref();
+    if let Some(we) = weights {
+        report.zero_weights = we.iter().filter(|&&w| w == Some(W::zero())).count();
+        report.negative_weights = we
+            .iter()
+            .filter(|&&w| w.is_some_and(|w| w < W::zero()))
+            .count();
+    }
+
+    // filter the edges in parallel chunks and split the kept rows into shards by
+    // source hash, so every source is grouped by exactly one shard
+    let num_shards = parallel::num_threads();
+    let chunk_len = len_source.div_ceil(num_shards);
+    let chunks = parallel::map_indices(num_shards, |chunk| {
+        let mut rows = ChunkRows {
+            shards: vec![Vec::new(); num_shards],
+            ..ChunkRows::default()
+        };
+        for i in (chunk * chunk_len)..((chunk + 1) * chunk_len).min(len_source) {
+            if weights.is_some_and(|we| we[i].is_none()) {
+                continue;
+            }
+            let (src, tgt) = (source[i].as_ref(), target[i].as_ref());
+            if src.is_empty() || tgt.is_empty() {
+                rows.empty_names_dropped += 1;
+                continue;
+            }
+            if src == tgt {
+                rows.self_loops += 1;
+                match options.self_loops {
+                    SelfLoopPolicy::Keep => {}
+                    SelfLoopPolicy::Drop => {
+                        rows.self_loops_dropped += 1;
+                        continue;
+                    }
+                    SelfLoopPolicy::Error => {
+                        return Err(NetworkValidationError::SelfLoop {
+                            row: i,
+                            name: src.to_string(),
+                        });
+                    }
+                }
+            }
+            rows.shards[shard_of(src, num_shards)].push(i);
         }
+        Ok(rows)
+    });
+    let chunks = chunks.into_iter().collect::<Result<Vec<_>, _>>()?;
+    for rows in &chunks {
+        report.empty_names_dropped += rows.empty_names_dropped;
+        report.self_loops += rows.self_loops;
+        report.self_loops_dropped += rows.self_loops_dropped;
+    }
 
-        if current_src != *src {
-            // incase this is a different node now
-            if !current_target_weight.is_empty() {
-                let data: Vec<(String, f32)> = current_target_weight
+    // group every shard in parallel; chunks are visited in order, so targets keep the
+    // position of their first occurrence
+    let shards = parallel::map_indices(num_shards, |shard| {
+        let mut source_ids: HashMap<&str, usize> = HashMap::new();
+        let mut rows: Vec<Vec<usize>> = Vec::new();
+        for &i in chunks.iter().flat_map(|chunk| &chunk.shards[shard]) {
+            let id = *source_ids.entry(source[i].as_ref()).or_insert_with(|| {
+                rows.push(Vec::new());
+                rows.len() - 1
+            });
+            rows[id].push(i);
+        }
+        rows.into_iter()
+            .map(|rows| {
+                let targets: Vec<(&str, W)> = rows
                     .iter()
-                    .map(|(key, value)| (key.clone(), *value))
+                    .map(|&i| {
+                        let weight = match weights {
+                            Some(we) => we[i].expect("edges with dropped weights are skipped"),
+                            None => W::one(),
+                        };
+                        (target[i].as_ref(), weight)
+                    })
                     .collect();
-                map.insert(current_src, data);
-                // cleanup
-                current_target_weight.clear();
-                current_src = src.clone();
+                let src = source[rows[0]].as_ref();
+                match dedup_entries(targets, options.duplicates) {
+                    Ok(targets) => Ok((
+                        rows[0],
+                        src.to_string(),
+                        targets
+                            .into_iter()
+                            .map(|(tgt, weight)| (tgt.to_string(), weight))
+                            .collect::<Vec<(String, W)>>(),
+                    )),
+                    Err(tgt) => Err((
+                        rows[0],
+                        NetworkValidationError::DuplicateEdge {
+                            from: src.to_string(),
+                            to: tgt.to_string(),
+                        },
+                    )),
+                }
+            })
+            .collect::<Vec<_>>()
+    });
+
+    // restore the order of first appearance and report the duplicate of the source
+    // appearing first, independent of the sharding
+    let mut groups = Vec::new();
+    let mut first_error: Option<(usize, NetworkValidationError)> = None;
+    for group in shards.into_iter().flatten() {
+        match group {
+            Ok(group) => groups.push(group),
+            Err((row, err)) => {
+                if first_error.as_ref().is_none_or(|(first, _)| row < *first) {
+                    first_error = Some((row, err));
+                }
             }
         }
+    }
+    if let Some((_, err)) = first_error {
+        return Err(err);
+    }
+    groups.sort_unstable_by_key(|&(first_row, _, _)| first_row);
+    let groups: EdgeGroups<W> = groups
+        .into_iter()
+        .map(|(_, src, targets)| (src, targets))
+        .collect();
+    report.num_sources = groups.len();
+    report.duplicate_edges = len_source
+        - report.empty_names_dropped
+        - report.non_finite_dropped
+        - report.self_loops_dropped
+        - groups
+            .iter()
+            .map(|(_, targets)| targets.len())
+            .sum::<usize>();
+    Ok((groups, report))
+}
+
+/// Groups an edge list by source while its edges arrive one at a time.
+///
+/// The streaming counterpart of `group_edges` behind `validate_net_from_csv` and
+/// `GenericPathwayNetwork::from_edges`: edges are cleaned as they are pushed, so only
+/// the grouped targets of every source are held in memory. Infinite weights under
+/// `WeightPolicy::Clamp` are clamped once all edges have been pushed.
+pub(crate) struct EdgeStream<'a, W> {
+    options: &'a ValidationOptions,
+    report: ValidationReport,
+    source_ids: HashMap<String, usize>,
+    groups: EdgeGroups<W>,
+    bounds: ClampBounds<W>,
+    // number of infinite weights awaiting `WeightPolicy::Clamp`
+    infinite: usize,
+}
+
+impl<'a, W: FloatOpsTS> EdgeStream<'a, W> {
+    pub(crate) fn new(options: &'a ValidationOptions) -> Self {
+        Self {
+            options,
+            report: ValidationReport::default(),
+            source_ids: HashMap::new(),
+            groups: Vec::new(),
+            bounds: ClampBounds::default(),
+            infinite: 0,
+        }
+    }
+
+    /// Cleans the next edge and adds it to the targets of its source.
+    ///
+    /// # Returns
+    /// An error if the weight is not finite under `WeightPolicy::Error` or the edge is
+    /// a self-loop under `SelfLoopPolicy::Error`
+    pub(crate) fn push(
+        &mut self,
+        source: &str,
+        target: &str,
+        weight: W,
+    ) -> Result<(), NetworkValidationError> {
+        let is_finite = |w: W| num_traits::Float::is_finite(w);
+        let options = self.options;
+        let report = &mut self.report;
+        let row = report.num_edges;
+        report.num_edges += 1;
+        let (src, src_aliased) = options.normalize(source);
+        let (tgt, tgt_aliased) = options.normalize(target);
+        report.aliased_names += src_aliased as usize + tgt_aliased as usize;
 
-        let src_target = target[i].clone();
-        let src_target_weight = match &weights {
-            Some(we) => we[i],
-            None => 1f32,
+        if is_finite(weight) {
+            self.bounds.observe(weight);
+        } else {
+            report.non_finite_weights += 1;
+        }
+        let Some(weight) = screen_weight(weight, options.weights, row)? else {
+            report.non_finite_dropped += 1;
+            return Ok(());
+        };
+        if is_finite(weight) {
+            report.zero_weights += (weight == W::zero()) as usize;
+            report.negative_weights += (weight < W::zero()) as usize;
+        }
+
+        if src.is_empty() || tgt.is_empty() {
+            report.empty_names_dropped += 1;
+            return Ok(());
+        }
+        if src == tgt {
+            report.self_loops += 1;
+            match options.self_loops {
+                SelfLoopPolicy::Keep => {}
+                SelfLoopPolicy::Drop => {
+                    report.self_loops_dropped += 1;
+                    return Ok(());
+                }
+                SelfLoopPolicy::Error => {
+                    return Err(NetworkValidationError::SelfLoop {
+                        row,
+                        name: src.into_owned(),
+                    });
+                }
+            }
+        }
+        self.infinite += !is_finite(weight) as usize;
+        let id = match self.source_ids.get(src.as_ref()) {
+            Some(&id) => id,
+            None => {
+                self.source_ids.insert(src.to_string(), self.groups.len());
+                self.groups.push((src.into_owned(), Vec::new()));
+                self.groups.len() - 1
+            }
         };
-        current_target_weight.insert(src_target, src_target_weight);
+        self.groups[id].1.push((tgt.into_owned(), weight));
+        Ok(())
     }
 
-    if !current_target_weight.is_empty() {
-        let data: Vec<(String, f32)> = current_target_weight
-            .iter()
-            .map(|(key, value)| (key.clone(), *value))
+    /// Clamps the remaining infinite weights and resolves duplicated targets.
+    ///
+    /// # Returns
+    /// The targets and weights of every source along with the report, or an error if
+    /// a target is duplicated under `DuplicatePolicy::Error`
+    pub(crate) fn finish(
+        self,
+    ) -> Result<(EdgeGroups<W>, ValidationReport), NetworkValidationError> {
+        let Self {
+            options,
+            mut report,
+            mut groups,
+            bounds,
+            infinite,
+            ..
+        } = self;
+        if infinite > 0 {
+            for (_, targets) in &mut groups {
+                targets.retain_mut(|(_, weight)| {
+                    if num_traits::Float::is_finite(*weight) {
+                        return true;
+                    }
+                    let Some(clamped) = bounds.clamp(*weight) else {
+                        report.non_finite_dropped += 1;
+                        return false;
+                    };
+                    report.negative_weights += (clamped < W::zero()) as usize;
+                    *weight = clamped;
+                    true
+                });
+            }
+            groups.retain(|(_, targets)| !targets.is_empty());
+        }
+
+        let groups = parallel::map_vec(groups, |(src, targets)| {
+            match dedup_entries(targets, options.duplicates) {
+                Ok(targets) => Ok((src, targets)),
+                Err(tgt) => Err(NetworkValidationError::DuplicateEdge { from: src, to: tgt }),
+            }
+        });
+        let groups = groups.into_iter().collect::<Result<EdgeGroups<W>, _>>()?;
+        report.num_sources = groups.len();
+        report.duplicate_edges = report.num_edges
+            - report.empty_names_dropped
+            - report.non_finite_dropped
+            - report.self_loops_dropped
+            - groups
+                .iter()
+                .map(|(_, targets)| targets.len())
+                .sum::<usize>();
+        Ok((groups, report))
+    }
+}
+
+/// Rows kept by the filtering pass over one chunk of an edge list.
+#[derive(Default)]
+struct ChunkRows {
+    /// Kept rows of every source shard, in order
+    shards: Vec<Vec<usize>>,
+    empty_names_dropped: usize,
+    self_loops: usize,
+    self_loops_dropped: usize,
+}
+
+/// Assigns `name` to one of `num_shards` shards.
+fn shard_of(name: &str, num_shards: usize) -> usize {
+    if num_shards == 1 {
+        return 0;
+    }
+    let mut hasher = DefaultHasher::new();
+    name.hash(&mut hasher);
+    (hasher.finish() % num_shards as u64) as usize
+}
+
+/// Applies `policy` to the non-finite weights of an edge list.
+///
+/// # Returns
+/// The weight of every edge, `None` for dropped edges, or an error naming the first
+/// non-finite weight under `WeightPolicy::Error`
+fn repair_weights<W: FloatOps>(
+    weights: &[W],
+    policy: WeightPolicy,
+    report: &mut ValidationReport,
+) -> Result<Vec<Option<W>>, NetworkValidationError> {
+    let is_finite = |w: W| num_traits::Float::is_finite(w);
+    report.non_finite_weights = weights.iter().filter(|&&w| !is_finite(w)).count();
+    if report.non_finite_weights == 0 {
+        return Ok(weights.iter().map(|&w| Some(w)).collect());
+    }
+
+    let mut bounds = ClampBounds::default();
+    weights
+        .iter()
+        .filter(|&&w| is_finite(w))
+        .for_each(|&w| bounds.observe(w));
+    let mut repaired = Vec::with_capacity(weights.len());
+    for (row, &w) in weights.iter().enumerate() {
+        let w = screen_weight(w, policy, row)?.and_then(|w| bounds.clamp(w));
+        report.non_finite_dropped += w.is_none() as usize;
+        repaired.push(w);
+    }
+    Ok(repaired)
+}
+
+/// Applies `policy` to the weight of the edge at `row`.
+///
+/// # Returns
+/// The weight to keep, `None` if the edge is dropped, or an error if the weight is
+/// not finite under `WeightPolicy::Error`. Infinite weights are kept under
+/// `WeightPolicy::Clamp` until `ClampBounds::clamp` replaces them.
+fn screen_weight<W: FloatOps>(
+    w: W,
+    policy: WeightPolicy,
+    row: usize,
+) -> Result<Option<W>, NetworkValidationError> {
+    if num_traits::Float::is_finite(w) {
+        return Ok(Some(w));
+    }
+    Ok(match policy {
+        WeightPolicy::Error => return Err(NetworkValidationError::NonFiniteWeight { row }),
+        WeightPolicy::Drop => None,
+        WeightPolicy::Clamp if num_traits::Float::is_nan(w) => None,
+        WeightPolicy::Clamp => Some(w),
+        WeightPolicy::Replace(value) => Some(W::from_f64(value).unwrap()),
+    })
+}
+
+/// Replacements of infinite weights under `WeightPolicy::Clamp`.
+#[derive(Debug, Clone, Copy)]
+struct ClampBounds<W> {
+    /// Largest positive finite weight seen so far
+    largest: Option<W>,
+    /// Smallest negative finite weight seen so far
+    smallest: Option<W>,
+}
+
+impl<W> Default for ClampBounds<W> {
+    fn default() -> Self {
+        Self {
+            largest: None,
+            smallest: None,
+        }
+    }
+}
+
+impl<W: FloatOps> ClampBounds<W> {
+    /// Widens the bounds by the finite weight `w`.
+    fn observe(&mut self, w: W) {
+        if w > W::zero() {
+            self.largest = Some(self.largest.map_or(w, |l| num_traits::Float::max(l, w)));
+        } else if w < W::zero() {
+            self.smallest = Some(self.smallest.map_or(w, |s| num_traits::Float::min(s, w)));
+        }
+    }
+
+    /// Replaces an infinite `w` by the bound of its sign; finite weights pass unchanged.
+    ///
+    /// # Returns
+    /// The replacement, or `None` if no finite weight of the same sign was observed
+    fn clamp(&self, w: W) -> Option<W> {
+        if num_traits::Float::is_finite(w) {
+            Some(w)
+        } else if w > W::zero() {
+            self.largest
+        } else {
+            self.smallest
+        }
+    }
+}
+
+/// Collapses entries sharing the same key according to `policy`.
+///
+/// Keys keep the position of their first occurrence.
+///
+/// # Returns
+/// The collapsed entries, or the first duplicated key under `DuplicatePolicy::Error`
+pub(crate) fn dedup_entries<K: Eq + Hash + Clone, W: FloatOps>(
+    entries: Vec<(K, W)>,
+    policy: DuplicatePolicy,
+) -> Result<Vec<(K, W)>, K> {
+    let mut positions: HashMap<K, usize> = HashMap::with_capacity(entries.len());
+    let mut out: Vec<(K, W)> = Vec::with_capacity(entries.len());
+    let mut counts: Vec<usize> = Vec::new();
+    for (key, weight) in entries {
+        match positions.get(&key) {
+            None => {
+                positions.insert(key.clone(), out.len());
+                out.push((key, weight));
+                counts.push(1);
+            }
+            Some(&pos) => match policy {
+                DuplicatePolicy::Error => return Err(key),
+                DuplicatePolicy::First => {}
+                DuplicatePolicy::Last => out[pos].1 = weight,
+                DuplicatePolicy::Sum | DuplicatePolicy::Mean => {
+                    out[pos].1 += weight;
+                    counts[pos] += 1;
+                }
+                DuplicatePolicy::MaxAbs => {
+                    if num_traits::Float::abs(weight) > num_traits::Float::abs(out[pos].1) {
+                        out[pos].1 = weight;
+                    }
+                }
+            },
+        }
+    }
+    if policy == DuplicatePolicy::Mean {
+        for ((_, weight), count) in out.iter_mut().zip(counts) {
+            *weight = *weight / W::from(count).unwrap();
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted_groups(groups: EdgeGroups<f32>) -> EdgeGroups<f32> {
+        let mut groups: EdgeGroups<f32> = groups
+            .into_iter()
+            .map(|(source, mut targets)| {
+                targets.sort_by(|a, b| a.0.cmp(&b.0));
+                (source, targets)
+            })
+            .collect();
+        groups.sort_by(|a, b| a.0.cmp(&b.0));
+        groups
+    }
+
+    #[test]
+    fn validate_net_merges_non_consecutive_sources() {
+        let groups = validate_net(
+            &["A", "B", "A", "C", "B", "A"],
+            &["g1", "g2", "g3", "g4", "g5", "g6"],
+            Some(&[1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0]),
+            &ValidationOptions::default(),
+        )
+        .unwrap()
+        .0;
+
+        assert_eq!(
+            groups,
+            vec![
+                (
+                    "A".to_string(),
+                    vec![
+                        ("g1".to_string(), 1.0),
+                        ("g3".to_string(), 3.0),
+                        ("g6".to_string(), 6.0)
+                    ]
+                ),
+                (
+                    "B".to_string(),
+                    vec![("g2".to_string(), 2.0), ("g5".to_string(), 5.0)]
+                ),
+                ("C".to_string(), vec![("g4".to_string(), 4.0)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_net_is_independent_of_edge_order() {
+        let sources = ["A", "A", "A", "B", "B", "C", "C", "C", "C"];
+        let targets = ["g1", "g2", "g3", "g1", "g4", "g2", "g5", "g6", "g7"];
+        let weights = [1.0f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0];
+        let expected = sorted_groups(
+            validate_net(
+                &sources,
+                &targets,
+                Some(&weights[..]),
+                &ValidationOptions::default(),
+            )
+            .unwrap()
+            .0,
+        );
+
+        let shuffles: [[usize; 9]; 3] = [
+            [8, 3, 0, 5, 1, 7, 4, 2, 6],
+            [5, 0, 3, 6, 1, 4, 7, 2, 8],
+            [3, 5, 0, 8, 6, 4, 2, 7, 1],
+        ];
+        for order in shuffles {
+            let shuffled = validate_net(
+                &order
+                    .iter()
+                    .map(|&i| sources[i].to_string())
+                    .collect::<Vec<_>>(),
+                &order
+                    .iter()
+                    .map(|&i| targets[i].to_string())
+                    .collect::<Vec<_>>(),
+                Some(&order.iter().map(|&i| weights[i]).collect::<Vec<_>>()),
+                &ValidationOptions::default(),
+            )
+            .unwrap()
+            .0;
+            assert_eq!(sorted_groups(shuffled), expected);
+        }
+    }
+
+    #[test]
+    fn validate_net_defaults_weights_to_one() {
+        let groups: EdgeGroups<f64> = validate_net(
+            &["B", "A", "B"],
+            &["g1", "g2", "g3"],
+            None,
+            &ValidationOptions::default(),
+        )
+        .unwrap()
+        .0;
+        assert_eq!(
+            groups,
+            vec![
+                (
+                    "B".to_string(),
+                    vec![("g1".to_string(), 1.0), ("g3".to_string(), 1.0)]
+                ),
+                ("A".to_string(), vec![("g2".to_string(), 1.0)]),
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_net_accepts_an_empty_edge_list() {
+        let options = ValidationOptions::default();
+        let empty = (EdgeGroups::<f64>::new(), ValidationReport::default());
+        assert_eq!(
+            validate_net::<&str, f64>(&[], &[], Some(&[]), &options).unwrap(),
+            empty
+        );
+        #[cfg(feature = "io")]
+        assert_eq!(
+            validate_net_from_csv::<_, f64>(
+                "source\ttarget\tweight\n".as_bytes(),
+                &ColumnSpec::default(),
+                &options
+            )
+            .unwrap(),
+            empty
+        );
+    }
+
+    #[test]
+    fn validate_net_rejects_mismatched_lengths() {
+        assert!(
+            validate_net::<&str, f32>(&["A", "B"], &["g1"], None, &ValidationOptions::default())
+                .is_err()
+        );
+        assert!(
+            validate_net(
+                &["A", "B"],
+                &["g1", "g2"],
+                Some(&[1.0f32]),
+                &ValidationOptions::default()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn validate_net_resolves_duplicate_edges() {
+        let run = |policy: DuplicatePolicy| {
+            validate_net(
+                &["A", "B", "A", "A"],
+                &["g1", "g1", "g2", "g1"],
+                Some(&[1.0f32, 7.0, 2.0, -4.0]),
+                &ValidationOptions {
+                    duplicates: policy,
+                    ..ValidationOptions::default()
+                },
+            )
+        };
+        let weight_of_g1 = |policy: DuplicatePolicy| run(policy).unwrap().0[0].1[0].1;
+
+        assert_eq!(weight_of_g1(DuplicatePolicy::First), 1.0);
+        assert_eq!(weight_of_g1(DuplicatePolicy::Last), -4.0);
+        assert_eq!(weight_of_g1(DuplicatePolicy::Sum), -3.0);
+        assert_eq!(weight_of_g1(DuplicatePolicy::Mean), -1.5);
+        assert_eq!(weight_of_g1(DuplicatePolicy::MaxAbs), -4.0);
+        assert_eq!(run(DuplicatePolicy::First).unwrap().0[0].1.len(), 2);
+        assert!(run(DuplicatePolicy::Error).is_err());
+    }
+
+    #[test]
+    fn validate_net_reports_typed_errors() {
+        let run = |sources: &[&str], targets: &[&str], weights: Option<&[f32]>| {
+            validate_net(
+                sources,
+                targets,
+                weights,
+                &ValidationOptions {
+                    duplicates: DuplicatePolicy::Error,
+                    ..ValidationOptions::default()
+                },
+            )
+            .unwrap_err()
+        };
+
+        assert_eq!(
+            run(&["A", "B"], &["g1"], None),
+            NetworkValidationError::LengthMismatch {
+                sources: 2,
+                targets: 1
+            }
+        );
+        assert_eq!(
+            run(&["A"], &["g1"], Some(&[1.0, 2.0])),
+            NetworkValidationError::WeightLengthMismatch {
+                edges: 1,
+                weights: 2
+            }
+        );
+        assert_eq!(
+            run(&["A", "A"], &["g1", "g2"], Some(&[1.0, f32::NAN])),
+            NetworkValidationError::NonFiniteWeight { row: 1 }
+        );
+        assert_eq!(
+            run(&["A", "A"], &["g1", "g1"], None),
+            NetworkValidationError::DuplicateEdge {
+                from: "A".to_string(),
+                to: "g1".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn validate_net_reports_anomalies() {
+        let (groups, report) = validate_net(
+            &["A", "A", "", "B", "A", "C"],
+            &["g1", "g1", "g2", "B", "g3", ""],
+            Some(&[1.0f32, 2.0, 1.0, 0.0, -1.0, 1.0]),
+            &ValidationOptions {
+                duplicates: DuplicatePolicy::Sum,
+                ..ValidationOptions::default()
+            },
+        )
+        .unwrap();
+
+        assert_eq!(groups.len(), 2);
+        assert_eq!(
+            report,
+            ValidationReport {
+                num_edges: 6,
+                num_sources: 2,
+                duplicate_edges: 1,
+                self_loops: 1,
+                empty_names_dropped: 2,
+                zero_weights: 1,
+                negative_weights: 1,
+                aliased_names: 0,
+                non_finite_weights: 0,
+                non_finite_dropped: 0,
+                self_loops_dropped: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn validate_net_normalizes_symbols() {
+        let options = ValidationOptions {
+            case: SymbolCase::Upper,
+            aliases: HashMap::from([("OLD1".to_string(), "new1".to_string())]),
+            ..ValidationOptions::default()
+        };
+        let (groups, report) = validate_net::<&str, f32>(
+            &["Pathway", "PATHWAY", "pathway"],
+            &["Actb", "old1", "NEW1"],
+            None,
+            &options,
+        )
+        .unwrap();
+
+        assert_eq!(
+            groups,
+            vec![(
+                "PATHWAY".to_string(),
+                vec![("ACTB".to_string(), 1.0), ("NEW1".to_string(), 1.0)]
+            )]
+        );
+        assert_eq!(report.aliased_names, 1);
+        assert_eq!(report.duplicate_edges, 1);
+    }
+
+    #[test]
+    fn validate_net_repairs_non_finite_weights() {
+        let run = |policy: WeightPolicy| {
+            validate_net(
+                &["A", "A", "A", "A"],
+                &["g1", "g2", "g3", "g4"],
+                Some(&[f32::INFINITY, -2.0, f32::NAN, 3.0]),
+                &ValidationOptions {
+                    weights: policy,
+                    ..ValidationOptions::default()
+                },
+            )
+        };
+        let weights = |policy: WeightPolicy| -> Vec<f32> {
+            run(policy).unwrap().0[0]
+                .1
+                .iter()
+                .map(|&(_, w)| w)
+                .collect()
+        };
+
+        assert_eq!(
+            run(WeightPolicy::Error).unwrap_err(),
+            NetworkValidationError::NonFiniteWeight { row: 0 }
+        );
+        assert_eq!(weights(WeightPolicy::Drop), vec![-2.0, 3.0]);
+        assert_eq!(weights(WeightPolicy::Clamp), vec![3.0, -2.0, 3.0]);
+        assert_eq!(
+            weights(WeightPolicy::Replace(1.0)),
+            vec![1.0, -2.0, 1.0, 3.0]
+        );
+
+        let (_, report) = run(WeightPolicy::Drop).unwrap();
+        assert_eq!(report.non_finite_weights, 2);
+        assert_eq!(report.non_finite_dropped, 2);
+        assert_eq!(report.duplicate_edges, 0);
+
+        // infinite weights without a finite weight of the same sign are dropped rather
+        // than clamped to 0, when validating in one batch or while streaming
+        let options = ValidationOptions {
+            weights: WeightPolicy::Clamp,
+            ..ValidationOptions::default()
+        };
+        let sources = ["A", "A", "B", "B"];
+        let targets = ["g1", "g2", "g1", "g2"];
+        let weights = [2.0, f32::NEG_INFINITY, f32::INFINITY, 0.0];
+        let expected = (
+            vec![
+                ("A".to_string(), vec![("g1".to_string(), 2.0)]),
+                (
+                    "B".to_string(),
+                    vec![("g1".to_string(), 2.0), ("g2".to_string(), 0.0)],
+                ),
+            ],
+            ValidationReport {
+                num_edges: 4,
+                num_sources: 2,
+                zero_weights: 1,
+                non_finite_weights: 2,
+                non_finite_dropped: 1,
+                ..ValidationReport::default()
+            },
+        );
+        let batch = validate_net(&sources, &targets, Some(&weights), &options).unwrap();
+        assert_eq!(batch, expected);
+        let mut stream = EdgeStream::new(&options);
+        for ((src, tgt), w) in sources.iter().zip(targets).zip(weights) {
+            stream.push(src, tgt, w).unwrap();
+        }
+        assert_eq!(stream.finish().unwrap(), batch);
+    }
+
+    #[test]
+    fn validate_net_handles_self_loops() {
+        let run = |policy: SelfLoopPolicy| {
+            validate_net(
+                &["TF1", "TF1", "TF2", "TF2"],
+                &["TF1", "g1", "g1", "TF2"],
+                None::<&[f32]>,
+                &ValidationOptions {
+                    self_loops: policy,
+                    ..ValidationOptions::default()
+                },
+            )
+        };
+
+        let (groups, report) = run(SelfLoopPolicy::Keep).unwrap();
+        assert_eq!(groups[0].1.len(), 2);
+        assert_eq!(report.self_loops, 2);
+        assert_eq!(report.self_loops_dropped, 0);
+
+        let (groups, report) = run(SelfLoopPolicy::Drop).unwrap();
+        assert_eq!(
+            groups,
+            vec![
+                ("TF1".to_string(), vec![("g1".to_string(), 1.0)]),
+                ("TF2".to_string(), vec![("g1".to_string(), 1.0)]),
+            ]
+        );
+        assert_eq!(report.self_loops, 2);
+        assert_eq!(report.self_loops_dropped, 2);
+        assert_eq!(report.duplicate_edges, 0);
+
+        assert_eq!(
+            run(SelfLoopPolicy::Error).unwrap_err(),
+            NetworkValidationError::SelfLoop {
+                row: 0,
+                name: "TF1".to_string()
+            }
+        );
+    }
+
+    #[cfg(feature = "io")]
+    #[test]
+    fn validate_net_from_csv_matches_validate_net() {
+        let table = "target,weight,source,mor\n\
+                     g1,1.5,TF1,1\n\
+                     g2,inf,TF2,1\n\
+                     TF1,2,TF1,1\n\
+                     ,1,TF2,1\n\
+                     g1,-0.5,TF1,-1\n\
+                     g3,nan,TF2,1\n\
+                     g2,0,TF2,1\n";
+        let options = ValidationOptions {
+            duplicates: DuplicatePolicy::Sum,
+            weights: WeightPolicy::Clamp,
+            self_loops: SelfLoopPolicy::Drop,
+            ..ValidationOptions::default()
+        };
+        let spec = ColumnSpec {
+            delimiter: b',',
+            ..ColumnSpec::default()
+        };
+        let streamed = validate_net_from_csv::<_, f64>(table.as_bytes(), &spec, &options).unwrap();
+
+        let rows: Vec<Vec<&str>> = table
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').collect())
             .collect();
-        map.insert(current_src, data);
+        let sources: Vec<&str> = rows.iter().map(|row| row[2]).collect();
+        let targets: Vec<&str> = rows.iter().map(|row| row[0]).collect();
+        let weights: Vec<f64> = rows.iter().map(|row| row[1].parse().unwrap()).collect();
+        let batch = validate_net(&sources, &targets, Some(&weights), &options).unwrap();
+
+        assert_eq!(streamed, batch);
+        assert_eq!(
+            streamed.0,
+            vec![
+                ("TF1".to_string(), vec![("g1".to_string(), 1.0)]),
+                ("TF2".to_string(), vec![("g2".to_string(), 2.0)]),
+            ]
+        );
+
+        let missing = ColumnSpec {
+            weight: Some("score".to_string()),
+            ..spec.clone()
+        };
+        assert_eq!(
+            validate_net_from_csv::<_, f64>(table.as_bytes(), &missing, &options).unwrap_err(),
+            NetworkValidationError::MissingColumn {
+                name: "score".to_string()
+            }
+        );
+        assert_eq!(
+            validate_net_from_csv::<_, f64>(
+                "source,target,weight\nA,b,x\n".as_bytes(),
+                &spec,
+                &options
+            )
+            .unwrap_err(),
+            NetworkValidationError::MalformedRow {
+                row: 0,
+                message: "invalid weight 'x'".to_string()
+            }
+        );
     }
 
-    Ok(map)
+    #[test]
+    fn validate_net_keeps_first_appearance_order() {
+        let sources: Vec<String> = (0..500).map(|i| format!("S{}", (i * 37) % 101)).collect();
+        let targets: Vec<String> = (0..500).map(|i| format!("g{}", i % 7)).collect();
+        let (groups, _) =
+            validate_net::<String, f32>(&sources, &targets, None, &ValidationOptions::default())
+                .unwrap();
+
+        let mut expected: Vec<&str> = Vec::new();
+        for source in &sources {
+            if !expected.contains(&source.as_str()) {
+                expected.push(source);
+            }
+        }
+        let order: Vec<&str> = groups.iter().map(|(source, _)| source.as_str()).collect();
+        assert_eq!(order, expected);
+    }
 }
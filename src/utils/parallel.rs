@@ -0,0 +1,97 @@
+//! Thin wrappers that run index-parallel loops on rayon when the `rayon`
+//! feature is enabled and fall back to sequential iteration otherwise.
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Number of threads the parallel loops run on; 1 without the `rayon` feature.
+pub(crate) fn num_threads() -> usize {
+    #[cfg(feature = "rayon")]
+    {
+        rayon::current_num_threads()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        1
+    }
+}
+
+/// Maps every index in `0..n` through `f`, preserving order.
+pub(crate) fn map_indices<R, F>(n: usize, f: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(usize) -> R + Sync + Send,
+{
+    #[cfg(feature = "rayon")]
+    {
+        (0..n).into_par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        (0..n).map(f).collect()
+    }
+}
+
+/// Maps every index in `0..n` through `f` like `map_indices`, passing scratch state
+/// created by `init` that is reused across the indices processed by the same worker.
+pub(crate) fn map_indices_init<S, R, I, F>(n: usize, init: I, f: F) -> Vec<R>
+where
+    R: Send,
+    I: Fn() -> S + Sync + Send,
+    F: Fn(&mut S, usize) -> R + Sync + Send,
+{
+    #[cfg(feature = "rayon")]
+    {
+        (0..n).into_par_iter().map_init(init, f).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        let mut state = init();
+        (0..n).map(|i| f(&mut state, i)).collect()
+    }
+}
+
+/// Maps every item of `items` through `f`, consuming the vector and preserving order.
+pub(crate) fn map_vec<T, R, F>(items: Vec<T>, f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync + Send,
+{
+    #[cfg(feature = "rayon")]
+    {
+        items.into_par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        items.into_iter().map(f).collect()
+    }
+}
+
+/// Applies `f` to consecutive chunks of at most `chunk_len` values of `data`.
+pub(crate) fn for_each_chunk_mut<T, F>(data: &mut [T], chunk_len: usize, f: F)
+where
+    T: Send,
+    F: Fn(&mut [T]) + Sync + Send,
+{
+    #[cfg(feature = "rayon")]
+    {
+        data.par_chunks_mut(chunk_len).for_each(f);
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        data.chunks_mut(chunk_len).for_each(f);
+    }
+}
+
+/// Concatenates `parts` into one vector, preserving order.
+pub(crate) fn concat<T: Copy + Send + Sync>(parts: &[Vec<T>]) -> Vec<T> {
+    #[cfg(feature = "rayon")]
+    {
+        parts.par_iter().flatten_iter().copied().collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        parts.concat()
+    }
+}
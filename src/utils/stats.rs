@@ -0,0 +1,11 @@
+//! Numerical helpers shared by the statistical routines of the crate.
+
+use crate::stats::special::lgamma;
+
+/// Natural logarithm of the binomial coefficient `n choose k`.
+pub(crate) fn ln_choose(n: usize, k: usize) -> f64 {
+    if k > n {
+        return f64::NEG_INFINITY;
+    }
+    lgamma(n as f64 + 1.0) - lgamma(k as f64 + 1.0) - lgamma((n - k) as f64 + 1.0)
+}
@@ -0,0 +1,125 @@
+use super::{EdgeGroups, EdgeStream, NetworkValidationError, ValidationOptions, ValidationReport};
+use crate::traits::FloatOpsTS;
+use std::io::Read;
+
+/// Layout of a delimited edge list read by `validate_net_from_csv`.
+///
+/// The defaults match the `source`/`target`/`weight` layout of decoupler and OmniPath
+/// exports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSpec {
+    /// Name of the column holding the source of every edge
+    pub source: String,
+    /// Name of the column holding the target of every edge
+    pub target: String,
+    /// Name of the column holding the edge weight, or `None` to weight every edge with 1
+    pub weight: Option<String>,
+    /// Name of a column holding the edge sign (e.g. `mor`), or `None` if weights are
+    /// already signed. When set, every weight is replaced by its magnitude carrying
+    /// the sign of this column.
+    pub sign: Option<String>,
+    /// The field delimiter, e.g. `b','` for CSV or `b'\t'` for TSV
+    pub delimiter: u8,
+}
+
+impl Default for ColumnSpec {
+    fn default() -> Self {
+        Self {
+            source: "source".to_string(),
+            target: "target".to_string(),
+            weight: Some("weight".to_string()),
+            sign: None,
+            delimiter: b'\t',
+        }
+    }
+}
+
+/// Groups the targets and weights of a delimited edge list by source while reading it.
+///
+/// Behaves like `validate_net`, but rows are processed as they are read, so the
+/// source and target columns are never held in memory; only the grouped targets of
+/// every source are. Columns are located by their header name, which allows
+/// additional columns to be present, and fields are trimmed. Infinite weights under
+/// `WeightPolicy::Clamp` are clamped once the whole edge list has been read.
+///
+/// # Arguments
+/// * `reader` - The source of the delimited content, starting with a header row
+/// * `spec` - Column names and delimiter of the edge list
+/// * `options` - How duplicates, symbols, weights and self-loops are handled
+///
+/// # Returns
+/// The targets and weights of every source along with a report of the anomalies
+/// found in the edge list, or an error if the content cannot be read, a column is
+/// missing, a row is malformed or the edge list is rejected by `options`
+pub fn validate_net_from_csv<R, W>(
+    reader: R,
+    spec: &ColumnSpec,
+    options: &ValidationOptions,
+) -> Result<(EdgeGroups<W>, ValidationReport), NetworkValidationError>
+where
+    R: Read,
+    W: FloatOpsTS,
+{
+    let read_error = |err: csv::Error| NetworkValidationError::Read {
+        message: err.to_string(),
+    };
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(spec.delimiter)
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(reader);
+    let header = reader.headers().map_err(read_error)?.clone();
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|c| c == name)
+            .ok_or_else(|| NetworkValidationError::MissingColumn {
+                name: name.to_string(),
+            })
+    };
+    let source_idx = column(&spec.source)?;
+    let target_idx = column(&spec.target)?;
+    let weight_idx = spec.weight.as_deref().map(column).transpose()?;
+    let sign_idx = spec.sign.as_deref().map(column).transpose()?;
+
+    let mut edges = EdgeStream::new(options);
+    let mut record = csv::StringRecord::new();
+    for row in 0.. {
+        if !reader.read_record(&mut record).map_err(read_error)? {
+            break;
+        }
+        let field = |idx: usize| {
+            record
+                .get(idx)
+                .ok_or_else(|| NetworkValidationError::MalformedRow {
+                    row,
+                    message: "too few fields".to_string(),
+                })
+        };
+        let (src, tgt) = (field(source_idx)?, field(target_idx)?);
+        let number = |idx: usize, what: &str| -> Result<W, NetworkValidationError> {
+            let text = field(idx)?;
+            let value: f64 = text
+                .parse()
+                .map_err(|_| NetworkValidationError::MalformedRow {
+                    row,
+                    message: format!("invalid {} '{}'", what, text),
+                })?;
+            Ok(W::from_f64(value).unwrap_or_else(num_traits::Float::nan))
+        };
+        let mut weight = match weight_idx {
+            Some(idx) => number(idx, "weight")?,
+            None => W::one(),
+        };
+        if let Some(idx) = sign_idx {
+            let magnitude = num_traits::Float::abs(weight);
+            weight = if number(idx, "sign")? < W::zero() {
+                -magnitude
+            } else {
+                magnitude
+            };
+        }
+        edges.push(src, tgt, weight)?;
+    }
+    edges.finish()
+}